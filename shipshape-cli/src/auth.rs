@@ -475,20 +475,20 @@ fn auth_store_path(auth_path: Option<PathBuf>) -> CliResult<PathBuf> {
     if let Some(path) = auth_path {
         return Ok(path);
     }
-    if let Ok(path) = std::env::var("SHIPSHAPE_AUTH_PATH") {
-        if !path.trim().is_empty() {
-            return Ok(PathBuf::from(path));
-        }
+    if let Ok(path) = std::env::var("SHIPSHAPE_AUTH_PATH")
+        && !path.trim().is_empty()
+    {
+        return Ok(PathBuf::from(path));
     }
-    if let Ok(base) = std::env::var("XDG_CONFIG_HOME") {
-        if !base.trim().is_empty() {
-            return Ok(PathBuf::from(base).join("shipshape").join("auth.json"));
-        }
+    if let Ok(base) = std::env::var("XDG_CONFIG_HOME")
+        && !base.trim().is_empty()
+    {
+        return Ok(PathBuf::from(base).join("shipshape").join("auth.json"));
     }
-    if let Ok(home) = std::env::var("HOME") {
-        if !home.trim().is_empty() {
-            return Ok(PathBuf::from(home).join(".config/shipshape/auth.json"));
-        }
+    if let Ok(home) = std::env::var("HOME")
+        && !home.trim().is_empty()
+    {
+        return Ok(PathBuf::from(home).join(".config/shipshape/auth.json"));
     }
     Err("unable to resolve auth storage path".into())
 }
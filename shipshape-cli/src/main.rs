@@ -7,14 +7,29 @@ mod auth;
 
 use auth::LoginArgs;
 use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use shipshape_core::duplication::DUPLICATE_BLOCKS_REPORTED;
+use shipshape_core::inspector::inspect_coverage_with_artifacts;
+use shipshape_core::projects::{ROOT_PROJECT, nested_project_dirs, project_violations};
 use shipshape_core::{
-    CloneStatus, LanguageDistribution, LaunchReport, Mechanic, RefitReport, RepoReport,
-    StdFileSystem, TokeiInspector, build_mechanics, format_language_stats, generate_ci_config,
-    render_audit_markdown, render_json, render_launch_markdown, render_refit_markdown,
+    AuditCache, ChangedLines, CiTarget, CloneStatus, ComplexityThresholds, CoverageReport,
+    DEFAULT_VERIFY_TIMEOUT, DrydockOptions, DuplicationConfig, FileSystem, HealthModel,
+    LanguageDistribution, LaunchReport, LicensePolicy, Mechanic, Project, ProjectFileSystem,
+    ProjectLaunch, ProjectReport, RefitReport, RepoReport, SkipExcluded, SourceCorpus,
+    StdFileSystem, TokeiInspector, ToolchainDefaults, VerifyOptions, VerifyRuntime,
+    apply_api_doc_coverage, build_mechanics, cached_mechanic_audit, complexity_lines,
+    complexity_signals, coverage_lines, detect_generated_files, detect_stacks, diff_lines,
+    discover_projects, drop_excluded_violations, duplication_lines, duplication_signals,
+    excluded_files_lines, find_cross_repo_duplicates, format_health_deduction,
+    format_language_stats, format_violation, generate_drydock, history_lines, history_signals,
+    inspect_api_docs, inspect_complexity, inspect_duplication, inspect_history, inspect_license,
+    inspect_test_mapping, license_lines, project_lines, project_stacks, render_audit_markdown,
+    render_json, render_launch_markdown, render_refit_markdown, stack_lines, test_mapping_lines,
+    test_mapping_signals, tree_hash, verification_lines, verify_local,
 };
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::Command;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
@@ -66,6 +81,130 @@ struct MechanicArgs {
     mechanic: Vec<String>,
 }
 
+#[derive(Args, Clone, Default)]
+struct AuditArgs {
+    /// Coverage artifacts to ingest (lcov, Cobertura, Go, JaCoCo, llvm-cov JSON).
+    /// Relative paths resolve against each repository root.
+    #[arg(long = "coverage-report", value_delimiter = ',')]
+    coverage_reports: Vec<PathBuf>,
+    /// Health score model (TOML or JSON) overriding the default weights and thresholds.
+    #[arg(long = "health-model")]
+    health_model_path: Option<PathBuf>,
+    /// Health model loaded from `health_model_path`, or the default model.
+    #[arg(skip)]
+    health_model: HealthModel,
+    /// Report functions whose cyclomatic complexity exceeds this value (default 15).
+    #[arg(long = "max-cyclomatic")]
+    max_cyclomatic: Option<u32>,
+    /// Report functions nested deeper than this many blocks (default 5).
+    #[arg(long = "max-nesting")]
+    max_nesting: Option<u32>,
+    /// Report functions longer than this many lines (default 100).
+    #[arg(long = "max-function-length")]
+    max_function_length: Option<u32>,
+    /// Minimum size of a reported duplicate block, in tokens (default 50).
+    #[arg(long = "min-duplicate-tokens")]
+    min_duplicate_tokens: Option<usize>,
+    /// License policy (TOML or JSON) with allowed SPDX ids and header requirements.
+    #[arg(long = "license-policy")]
+    license_policy_path: Option<PathBuf>,
+    /// License policy loaded from `license_policy_path`, or the default policy.
+    #[arg(skip)]
+    license_policy: LicensePolicy,
+    /// Report source files without an SPDX tag or license header.
+    #[arg(long = "require-license-headers")]
+    require_license_headers: bool,
+    /// Audit generated and vendored files instead of listing them separately.
+    #[arg(long = "include-generated")]
+    include_generated: bool,
+    /// Recompute every result instead of reusing cached line stats and mechanic results.
+    #[arg(long = "no-cache")]
+    no_cache: bool,
+    /// Directory for cached audit results (default `$XDG_CACHE_HOME/shipshape`).
+    #[arg(long = "cache-dir")]
+    cache_dir: Option<PathBuf>,
+    /// Cache opened from `cache_dir`, or `None` when caching is disabled.
+    #[arg(skip)]
+    cache: Option<AuditCache>,
+    /// Only report violations on lines changed since this git revision.
+    #[arg(long = "since")]
+    since: Option<String>,
+}
+
+impl AuditArgs {
+    fn complexity_thresholds(&self) -> ComplexityThresholds {
+        let defaults = ComplexityThresholds::default();
+        ComplexityThresholds {
+            cyclomatic: self.max_cyclomatic.unwrap_or(defaults.cyclomatic),
+            nesting: self.max_nesting.unwrap_or(defaults.nesting),
+            length: self.max_function_length.unwrap_or(defaults.length),
+        }
+    }
+
+    fn duplication_config(&self) -> DuplicationConfig {
+        let defaults = DuplicationConfig::default();
+        DuplicationConfig {
+            min_tokens: self.min_duplicate_tokens.unwrap_or(defaults.min_tokens),
+            ..defaults
+        }
+    }
+}
+
+#[derive(Args, Clone, Default)]
+struct LaunchArgs {
+    /// Toolchain image table (TOML or JSON) used when a repository pins no version.
+    #[arg(long = "toolchain-defaults")]
+    toolchain_defaults_path: Option<PathBuf>,
+    /// Table loaded from `toolchain_defaults_path`, or the built-in defaults.
+    #[arg(skip)]
+    toolchain_defaults: ToolchainDefaults,
+    /// CI systems to generate pipelines for (gitlab, github; default both).
+    #[arg(long = "ci", value_delimiter = ',')]
+    ci: Vec<CiTarget>,
+    /// Generate pipelines without dependency caches or BuildKit cache mounts.
+    /// Repositories can also opt out with `cache = false` under `[drydock]`
+    /// in `.shipshape.toml`.
+    #[arg(long = "no-ci-cache")]
+    no_ci_cache: bool,
+    /// Run the generated drydock jobs against a copy of each repository and
+    /// report whether they pass.
+    #[arg(long = "verify-local")]
+    verify_local: bool,
+    /// Where `--verify-local` runs jobs: `host`, or a docker-compatible
+    /// container runtime command such as `docker` or `podman`.
+    #[arg(long = "verify-runtime", default_value = "docker")]
+    verify_runtime: VerifyRuntime,
+    /// Seconds each `--verify-local` job may run before it is killed
+    /// (default 1800).
+    #[arg(long = "verify-timeout")]
+    verify_timeout: Option<u64>,
+}
+
+impl LaunchArgs {
+    fn drydock_options(&self) -> DrydockOptions {
+        let defaults = DrydockOptions::default();
+        DrydockOptions {
+            toolchains: self.toolchain_defaults.clone(),
+            targets: if self.ci.is_empty() {
+                defaults.targets
+            } else {
+                self.ci.clone()
+            },
+            cache: !self.no_ci_cache,
+        }
+    }
+
+    fn verify_options(&self) -> VerifyOptions {
+        VerifyOptions {
+            runtime: self.verify_runtime.clone(),
+            timeout: self
+                .verify_timeout
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_VERIFY_TIMEOUT),
+        }
+    }
+}
+
 #[derive(Args, Clone)]
 struct OutputArgs {
     /// Output format for report data.
@@ -94,6 +233,8 @@ enum Commands {
         #[command(flatten)]
         mechanics: MechanicArgs,
         #[command(flatten)]
+        audit: AuditArgs,
+        #[command(flatten)]
         report: OutputArgs,
     },
     /// Audit repositories from a URL, file, directory, or local path.
@@ -105,6 +246,8 @@ enum Commands {
         #[command(flatten)]
         mechanics: MechanicArgs,
         #[command(flatten)]
+        audit: AuditArgs,
+        #[command(flatten)]
         report: OutputArgs,
     },
     /// Run refit mechanics in dry-run mode or apply fixes.
@@ -128,6 +271,8 @@ enum Commands {
         #[command(flatten)]
         clone: CloneArgs,
         #[command(flatten)]
+        launch: LaunchArgs,
+        #[command(flatten)]
         report: OutputArgs,
     },
     /// Authenticate the CLI via the GitHub device flow.
@@ -144,6 +289,7 @@ async fn main() -> CliResult<()> {
             source,
             clone,
             mechanics,
+            audit,
             report,
         } => {
             let source = resolve_source_args(&source)?;
@@ -152,6 +298,7 @@ async fn main() -> CliResult<()> {
                 clone.output,
                 clone.concurrency,
                 mechanics.mechanic,
+                audit,
                 report,
             )
             .await?
@@ -160,6 +307,7 @@ async fn main() -> CliResult<()> {
             source,
             clone,
             mechanics,
+            audit,
             report,
         } => {
             let source = resolve_source_args(&source)?;
@@ -168,6 +316,7 @@ async fn main() -> CliResult<()> {
                 clone.output,
                 clone.concurrency,
                 mechanics.mechanic,
+                audit,
                 report,
             )
             .await?
@@ -193,10 +342,11 @@ async fn main() -> CliResult<()> {
         Commands::Launch {
             source,
             clone,
+            launch,
             report,
         } => {
             let source = resolve_source_args(&source)?;
-            run_launch(source, clone.output, clone.concurrency, report).await?
+            run_launch(source, clone.output, clone.concurrency, launch, report).await?
         }
         Commands::Login(args) => {
             auth::run_login(args).await?;
@@ -214,8 +364,26 @@ async fn run_audit(
     clone_output: PathBuf,
     concurrency: usize,
     mechanic_ids: Vec<String>,
+    audit: AuditArgs,
     report: OutputArgs,
 ) -> CliResult<()> {
+    let mut audit = audit;
+    if let Some(path) = &audit.health_model_path {
+        audit.health_model = HealthModel::from_path(path)?;
+    }
+    if let Some(path) = &audit.license_policy_path {
+        audit.license_policy = LicensePolicy::from_path(path)?;
+    }
+    if audit.require_license_headers {
+        audit.license_policy.require_headers = true;
+    }
+    if !audit.no_cache {
+        let dir = audit
+            .cache_dir
+            .clone()
+            .unwrap_or_else(AuditCache::default_dir);
+        audit.cache = Some(AuditCache::new(dir));
+    }
     let targets = load_repo_targets(source, &clone_output).await?;
     if targets.is_empty() {
         println!("No repositories found to audit.");
@@ -229,6 +397,7 @@ async fn run_audit(
         tokio::fs::create_dir_all(&clone_output).await?;
     }
     let mechanics = Arc::new(build_mechanics(&mechanic_ids)?);
+    let audit = Arc::new(audit);
     let concurrency = if concurrency == 0 { 1 } else { concurrency };
     let semaphore = Arc::new(Semaphore::new(concurrency));
     let mut tasks = JoinSet::new();
@@ -236,9 +405,10 @@ async fn run_audit(
     for target in targets {
         let permit = semaphore.clone().acquire_owned().await?;
         let mechanics = mechanics.clone();
+        let audit = audit.clone();
         tasks.spawn(async move {
             let _permit = permit;
-            audit_target(target, mechanics).await
+            audit_target(target, mechanics, audit).await
         });
     }
 
@@ -249,6 +419,7 @@ async fn run_audit(
             Err(err) => reports.push(repo_report_from_task_error(err)),
         }
     }
+    attach_cross_repo_duplicates(&mut reports, &audit.duplication_config());
 
     emit_audit_reports(&reports, &report).await?;
 
@@ -306,8 +477,14 @@ async fn run_launch(
     source: BatchSource,
     clone_output: PathBuf,
     concurrency: usize,
+    launch: LaunchArgs,
     report: OutputArgs,
 ) -> CliResult<()> {
+    let mut launch = launch;
+    if let Some(path) = &launch.toolchain_defaults_path {
+        launch.toolchain_defaults = ToolchainDefaults::from_path(path)?;
+    }
+    let launch = Arc::new(launch);
     let targets = load_repo_targets(source, &clone_output).await?;
     if targets.is_empty() {
         println!("No repositories found to launch.");
@@ -326,9 +503,10 @@ async fn run_launch(
 
     for target in targets {
         let permit = semaphore.clone().acquire_owned().await?;
+        let launch = launch.clone();
         tasks.spawn(async move {
             let _permit = permit;
-            launch_target(target).await
+            launch_target(target, launch).await
         });
     }
 
@@ -341,6 +519,13 @@ async fn run_launch(
     }
 
     emit_launch_reports(&reports, &report).await?;
+    if reports
+        .iter()
+        .filter_map(|report| report.verification.as_ref())
+        .any(|verification| !verification.passed)
+    {
+        return Err("local verification failed".into());
+    }
 
     Ok(())
 }
@@ -443,10 +628,11 @@ enum RepoTarget {
 async fn audit_target(
     target: RepoTarget,
     mechanics: Arc<Vec<Arc<dyn Mechanic + Send + Sync>>>,
+    audit: Arc<AuditArgs>,
 ) -> RepoReport {
     match target {
-        RepoTarget::Clone { url, dest } => clone_and_audit(url, dest, mechanics).await,
-        RepoTarget::Local { path } => audit_local(path, mechanics).await,
+        RepoTarget::Clone { url, dest } => clone_and_audit(url, dest, mechanics, audit).await,
+        RepoTarget::Local { path } => audit_local(path, mechanics, audit).await,
     }
 }
 
@@ -461,10 +647,10 @@ async fn refit_target(
     }
 }
 
-async fn launch_target(target: RepoTarget) -> LaunchReport {
+async fn launch_target(target: RepoTarget, launch: Arc<LaunchArgs>) -> LaunchReport {
     match target {
-        RepoTarget::Clone { url, dest } => clone_and_launch(url, dest).await,
-        RepoTarget::Local { path } => launch_local(path).await,
+        RepoTarget::Clone { url, dest } => clone_and_launch(url, dest, launch).await,
+        RepoTarget::Local { path } => launch_local(path, launch).await,
     }
 }
 
@@ -472,6 +658,7 @@ async fn clone_and_audit(
     url: String,
     repo_dir: PathBuf,
     mechanics: Arc<Vec<Arc<dyn Mechanic + Send + Sync>>>,
+    audit: Arc<AuditArgs>,
 ) -> RepoReport {
     let mut report = RepoReport::new(url, repo_dir);
 
@@ -489,7 +676,7 @@ async fn clone_and_audit(
         }
     }
 
-    populate_audit(&mut report, mechanics.as_ref());
+    populate_audit(&mut report, mechanics.as_ref(), &audit);
 
     report
 }
@@ -521,7 +708,7 @@ async fn clone_and_refit(
     report
 }
 
-async fn clone_and_launch(url: String, repo_dir: PathBuf) -> LaunchReport {
+async fn clone_and_launch(url: String, repo_dir: PathBuf, launch: Arc<LaunchArgs>) -> LaunchReport {
     let mut report = LaunchReport::new(url, repo_dir);
 
     if report.path.exists() {
@@ -538,7 +725,7 @@ async fn clone_and_launch(url: String, repo_dir: PathBuf) -> LaunchReport {
         }
     }
 
-    populate_launch(&mut report);
+    populate_launch(&mut report, &launch);
 
     report
 }
@@ -546,6 +733,7 @@ async fn clone_and_launch(url: String, repo_dir: PathBuf) -> LaunchReport {
 async fn audit_local(
     path: PathBuf,
     mechanics: Arc<Vec<Arc<dyn Mechanic + Send + Sync>>>,
+    audit: Arc<AuditArgs>,
 ) -> RepoReport {
     let mut report = RepoReport::new(path.display().to_string(), path);
     if !report.path.is_dir() {
@@ -555,7 +743,7 @@ async fn audit_local(
     }
 
     report.clone_status = CloneStatus::Local;
-    populate_audit(&mut report, mechanics.as_ref());
+    populate_audit(&mut report, mechanics.as_ref(), &audit);
     report
 }
 
@@ -576,7 +764,7 @@ async fn refit_local(
     report
 }
 
-async fn launch_local(path: PathBuf) -> LaunchReport {
+async fn launch_local(path: PathBuf, launch: Arc<LaunchArgs>) -> LaunchReport {
     let mut report = LaunchReport::new(path.display().to_string(), path);
     if !report.path.is_dir() {
         report.clone_status =
@@ -585,24 +773,262 @@ async fn launch_local(path: PathBuf) -> LaunchReport {
     }
 
     report.clone_status = CloneStatus::Local;
-    populate_launch(&mut report);
+    populate_launch(&mut report, &launch);
     report
 }
 
-fn populate_audit(report: &mut RepoReport, mechanics: &[Arc<dyn Mechanic + Send + Sync>]) {
-    match inspect_language_stats(&report.path) {
+fn populate_audit(
+    report: &mut RepoReport,
+    mechanics: &[Arc<dyn Mechanic + Send + Sync>],
+    audit: &AuditArgs,
+) {
+    if !audit.include_generated {
+        match detect_generated_files(&StdFileSystem::new(), &report.path) {
+            Ok(files) => report.excluded_files = files,
+            Err(err) => report.audit_errors.push(format!("generated files: {err}")),
+        }
+    }
+    let fs = SkipExcluded::new(StdFileSystem::new(), &report.path, &report.excluded_files);
+
+    match inspect_language_stats(&report.path, audit) {
         Ok(stats) => report.language_stats = Some(stats),
         Err(err) => report.audit_errors.push(format!("language stats: {err}")),
     }
 
+    match inspect_repo_coverage(&fs, &report.path, &audit.coverage_reports) {
+        Ok(coverage) => report.coverage = Some(coverage),
+        Err(err) => report.audit_errors.push(format!("coverage: {err}")),
+    }
+
+    match inspect_api_docs(&fs, &report.path) {
+        Ok((api_docs, mut violations)) => {
+            if let Some(coverage) = report.coverage.as_mut() {
+                apply_api_doc_coverage(coverage, &api_docs);
+            }
+            report.violations.append(&mut violations);
+        }
+        Err(err) => report.audit_errors.push(format!("api docs: {err}")),
+    }
+
+    match inspect_test_mapping(&fs, &report.path) {
+        Ok(mapping) => report.test_mapping = Some(mapping),
+        Err(err) => report.audit_errors.push(format!("test mapping: {err}")),
+    }
+
+    match inspect_history(&report.path) {
+        Ok(history) => report.history = history,
+        Err(err) => report.audit_errors.push(format!("history: {err}")),
+    }
+
+    match inspect_complexity(&fs, &report.path, &audit.complexity_thresholds()) {
+        Ok((complexity, mut violations)) => {
+            report.complexity = Some(complexity);
+            report.violations.append(&mut violations);
+        }
+        Err(err) => report.audit_errors.push(format!("complexity: {err}")),
+    }
+
+    let inspector =
+        TokeiInspector::new(StdFileSystem::new()).include_generated(audit.include_generated);
+    match inspect_duplication(&inspector, &report.path, &audit.duplication_config()) {
+        Ok(duplication) => report.duplication = Some(duplication),
+        Err(err) => report.audit_errors.push(format!("duplication: {err}")),
+    }
+
+    match inspect_license(&StdFileSystem::new(), &report.path, &audit.license_policy) {
+        Ok((license, mut violations)) => {
+            report.license = Some(license);
+            report.violations.append(&mut violations);
+        }
+        Err(err) => report.audit_errors.push(format!("license: {err}")),
+    }
+
+    let tree = match &audit.cache {
+        Some(_) if !mechanics.is_empty() => match tree_hash(&StdFileSystem::new(), &report.path) {
+            Ok(tree) => Some(tree),
+            Err(err) => {
+                report.audit_errors.push(format!("cache: {err}"));
+                None
+            }
+        },
+        _ => None,
+    };
     for mechanic in mechanics.iter() {
-        match mechanic.audit(&report.path) {
-            Ok(mut violations) => report.violations.append(&mut violations),
+        let result = match (&audit.cache, &tree) {
+            (Some(cache), Some(tree)) => {
+                cached_mechanic_audit(cache, mechanic.as_ref(), &report.path, tree)
+            }
+            _ => mechanic.audit(&report.path),
+        };
+        match result {
+            Ok(mut violations) => {
+                drop_excluded_violations(&mut violations, &report.path, &report.excluded_files);
+                report.violations.append(&mut violations);
+            }
             Err(err) => report
                 .audit_errors
                 .push(format!("mechanic {}: {err}", mechanic.id())),
         }
     }
+
+    if let Some(coverage) = &report.coverage {
+        let mut signals = report
+            .history
+            .as_ref()
+            .map(history_signals)
+            .unwrap_or_default();
+        if let Some(complexity) = &report.complexity {
+            signals.extend(complexity_signals(complexity));
+        }
+        if let Some(duplication) = &report.duplication {
+            signals.extend(duplication_signals(duplication));
+        }
+        if let Some(mapping) = &report.test_mapping {
+            signals.extend(test_mapping_signals(mapping));
+        }
+        report.health = Some(
+            audit
+                .health_model
+                .evaluate(coverage, &report.violations, &signals),
+        );
+    }
+
+    populate_projects(report, &fs, audit);
+
+    if let Some(base) = &audit.since {
+        match ChangedLines::from_git(&report.path, base, None) {
+            Ok(changes) => scope_to_diff(report, &changes),
+            Err(err) => report.audit_errors.push(format!("diff: {err}")),
+        }
+    }
+}
+
+/// Keep only violations on changed lines, setting pre-existing ones aside.
+fn scope_to_diff(report: &mut RepoReport, changes: &ChangedLines) {
+    let violations = std::mem::take(&mut report.violations);
+    let (new, preexisting) = changes.partition(&report.path, violations);
+    report.violations = new;
+    report.diff = Some(changes.report(preexisting));
+    for project in &mut report.projects {
+        let violations = std::mem::take(&mut project.violations);
+        project.violations = changes.partition(&report.path, violations).0;
+    }
+}
+
+/// Audit each project separately when the repository contains several.
+fn populate_projects<F: FileSystem>(report: &mut RepoReport, fs: &F, audit: &AuditArgs) {
+    let projects = match discover_projects(fs, &report.path) {
+        Ok(projects) => projects,
+        Err(err) => {
+            report.audit_errors.push(format!("projects: {err}"));
+            return;
+        }
+    };
+    if projects.len() < 2 {
+        return;
+    }
+
+    for project in &projects {
+        let root = project_root(&report.path, project);
+        let scoped =
+            ProjectFileSystem::new(fs, nested_project_dirs(&report.path, project, &projects));
+        let mut errors = Vec::new();
+        let mut project_report = ProjectReport::new(project.clone());
+        project_report.violations = project_violations(project, &projects, &report.violations);
+
+        let inspector = TokeiInspector::new(ProjectFileSystem::new(
+            fs,
+            nested_project_dirs(&report.path, project, &projects),
+        ))
+        .include_generated(audit.include_generated);
+        match inspector.inspect(&root) {
+            Ok(stats) => project_report.language_stats = Some(stats),
+            Err(err) => errors.push(format!("language stats: {err}")),
+        }
+        match inspect_repo_coverage(&scoped, &root, &[]) {
+            Ok(mut coverage) => {
+                if let Ok((api_docs, _)) = inspect_api_docs(&scoped, &root) {
+                    apply_api_doc_coverage(&mut coverage, &api_docs);
+                }
+                project_report.coverage = Some(coverage);
+            }
+            Err(err) => errors.push(format!("coverage: {err}")),
+        }
+        match inspect_test_mapping(&scoped, &root) {
+            Ok(mapping) => project_report.test_mapping = Some(mapping),
+            Err(err) => errors.push(format!("test mapping: {err}")),
+        }
+        match inspect_complexity(&scoped, &root, &audit.complexity_thresholds()) {
+            Ok((complexity, _)) => project_report.complexity = Some(complexity),
+            Err(err) => errors.push(format!("complexity: {err}")),
+        }
+
+        if let Some(coverage) = &project_report.coverage {
+            let mut signals = report
+                .history
+                .as_ref()
+                .map(history_signals)
+                .unwrap_or_default();
+            if let Some(complexity) = &project_report.complexity {
+                signals.extend(complexity_signals(complexity));
+            }
+            if let Some(mapping) = &project_report.test_mapping {
+                signals.extend(test_mapping_signals(mapping));
+            }
+            project_report.health = Some(audit.health_model.evaluate(
+                coverage,
+                &project_report.violations,
+                &signals,
+            ));
+        }
+        report.audit_errors.extend(
+            errors
+                .into_iter()
+                .map(|error| format!("project {}: {error}", project.path)),
+        );
+        report.projects.push(project_report);
+    }
+}
+
+fn project_root(repo: &Path, project: &Project) -> PathBuf {
+    if project.path == ROOT_PROJECT {
+        repo.to_path_buf()
+    } else {
+        repo.join(&project.path)
+    }
+}
+
+/// Record blocks shared between audited repositories on each side's report.
+fn attach_cross_repo_duplicates(reports: &mut [RepoReport], config: &DuplicationConfig) {
+    let audited: Vec<usize> = (0..reports.len())
+        .filter(|index| reports[*index].duplication.is_some())
+        .collect();
+    if audited.len() < 2 {
+        return;
+    }
+
+    let inspector = TokeiInspector::new(StdFileSystem::new());
+    let mut corpus = SourceCorpus::new();
+    for index in audited {
+        let report = &mut reports[index];
+        if let Err(err) = corpus.add_repository(&inspector, &report.path, Some(&report.source)) {
+            report.audit_errors.push(format!("duplication: {err}"));
+        }
+    }
+
+    for block in find_cross_repo_duplicates(&corpus, config) {
+        for report in reports.iter_mut() {
+            let involved = [&block.first.repo, &block.second.repo]
+                .into_iter()
+                .any(|repo| repo.as_deref() == Some(report.source.as_str()));
+            if let Some(duplication) = report.duplication.as_mut()
+                && involved
+                && duplication.cross_repo.len() < DUPLICATE_BLOCKS_REPORTED
+            {
+                duplication.cross_repo.push(block.clone());
+            }
+        }
+    }
 }
 
 fn populate_refit(
@@ -644,10 +1070,57 @@ fn populate_refit(
     }
 }
 
-fn populate_launch(report: &mut LaunchReport) {
-    let (dockerfile, ci_config) = generate_ci_config(&report.path);
-    report.dockerfile = Some(dockerfile);
-    report.ci_config = Some(ci_config);
+fn populate_launch(report: &mut LaunchReport, launch: &LaunchArgs) {
+    let fs = StdFileSystem::new();
+    match detect_stacks(&fs, &report.path) {
+        Ok(stacks) => report.stacks = stacks,
+        Err(err) => report.errors.push(format!("stacks: {err}")),
+    }
+    let defaults = launch.drydock_options();
+    let options = defaults.for_repo(&fs, &report.path).unwrap_or_else(|err| {
+        report.errors.push(format!("drydock config: {err}"));
+        defaults.clone()
+    });
+    let drydock = generate_drydock(&report.path, &report.stacks, &options);
+    report.dockerfile = Some(drydock.dockerfile);
+    report.dockerignore = Some(drydock.dockerignore);
+    report.ci_files = drydock.ci_files;
+    if launch.verify_local {
+        let source = report.source.clone();
+        match verify_local(
+            &report.path,
+            &drydock.jobs,
+            &launch.verify_options(),
+            |job, line| eprintln!("[{source}:{job}] {line}"),
+        ) {
+            Ok(verification) => report.verification = Some(verification),
+            Err(err) => report.errors.push(format!("verify: {err}")),
+        }
+    }
+
+    match discover_projects(&fs, &report.path) {
+        Ok(projects) if projects.len() > 1 => {
+            for project in projects {
+                let root = project_root(&report.path, &project);
+                let stacks = project_stacks(&fs, &report.path, &project);
+                let project_options = options.for_repo(&fs, &root).unwrap_or_else(|err| {
+                    report
+                        .errors
+                        .push(format!("drydock config ({}): {err}", project.path));
+                    options.clone()
+                });
+                let drydock = generate_drydock(&root, &stacks, &project_options);
+                report.projects.push(ProjectLaunch {
+                    project,
+                    dockerfile: drydock.dockerfile,
+                    dockerignore: drydock.dockerignore,
+                    ci_files: drydock.ci_files,
+                });
+            }
+        }
+        Ok(_) => {}
+        Err(err) => report.errors.push(format!("projects: {err}")),
+    }
 }
 
 async fn clone_repo(url: &str, dest: &Path) -> CliResult<()> {
@@ -665,11 +1138,36 @@ async fn clone_repo(url: &str, dest: &Path) -> CliResult<()> {
     }
 }
 
-fn inspect_language_stats(path: &Path) -> shipshape_core::Result<LanguageDistribution> {
-    let inspector = TokeiInspector::new(StdFileSystem::new());
+fn inspect_language_stats(
+    path: &Path,
+    audit: &AuditArgs,
+) -> shipshape_core::Result<LanguageDistribution> {
+    let mut inspector =
+        TokeiInspector::new(StdFileSystem::new()).include_generated(audit.include_generated);
+    if let Some(cache) = &audit.cache {
+        inspector = inspector.with_cache(cache.clone());
+    }
     inspector.inspect(path)
 }
 
+fn inspect_repo_coverage<F: FileSystem>(
+    fs: &F,
+    path: &Path,
+    artifacts: &[PathBuf],
+) -> shipshape_core::Result<CoverageReport> {
+    let artifacts: Vec<PathBuf> = artifacts
+        .iter()
+        .map(|artifact| {
+            if artifact.is_absolute() {
+                artifact.clone()
+            } else {
+                path.join(artifact)
+            }
+        })
+        .collect();
+    inspect_coverage_with_artifacts(fs, path, &artifacts)
+}
+
 fn repo_dir_name(url: &str) -> String {
     let trimmed = url.trim_end_matches('/');
     let last_segment = trimmed.rsplit('/').next().unwrap_or(trimmed);
@@ -767,13 +1265,89 @@ fn render_audit_text(reports: &[RepoReport]) -> String {
             }
         }
 
+        if let Some(health) = &report.health {
+            let _ = writeln!(output, "Health score: {}/100", health.score);
+            for deduction in &health.deductions {
+                let _ = writeln!(output, "- {}", format_health_deduction(deduction));
+            }
+        }
+        if let Some(coverage) = &report.coverage {
+            let _ = writeln!(output, "Coverage:");
+            for line in coverage_lines(coverage) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+        if let Some(mapping) = &report.test_mapping {
+            let _ = writeln!(output, "Test mapping:");
+            for line in test_mapping_lines(mapping) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+        if let Some(history) = &report.history {
+            let _ = writeln!(output, "History:");
+            for line in history_lines(history) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+        if let Some(complexity) = &report.complexity {
+            let _ = writeln!(output, "Complexity:");
+            for line in complexity_lines(complexity) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+        if let Some(duplication) = &report.duplication {
+            let _ = writeln!(output, "Duplication:");
+            for line in duplication_lines(duplication) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+        if let Some(license) = &report.license {
+            let _ = writeln!(output, "License:");
+            for line in license_lines(license) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+        if !report.excluded_files.is_empty() {
+            let _ = writeln!(output, "Generated and vendored files:");
+            for line in excluded_files_lines(&report.excluded_files) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+
+        let violations_label = match &report.diff {
+            Some(diff) => {
+                let _ = writeln!(output, "Diff scope:");
+                for line in diff_lines(diff) {
+                    let _ = writeln!(output, "- {line}");
+                }
+                if !diff.preexisting_violations.is_empty() {
+                    let _ = writeln!(output, "Pre-existing violations:");
+                    for violation in &diff.preexisting_violations {
+                        let _ = writeln!(output, "- {}", format_violation(violation));
+                    }
+                }
+                "New violations"
+            }
+            None => "Violations",
+        };
         if !report.violations.is_empty() {
-            let _ = writeln!(output, "Violations:");
+            let _ = writeln!(output, "{violations_label}:");
             for violation in &report.violations {
-                let _ = writeln!(output, "- [{}] {}", violation.id, violation.message);
+                let _ = writeln!(output, "- {}", format_violation(violation));
             }
         } else {
-            let _ = writeln!(output, "Violations: none");
+            let _ = writeln!(output, "{violations_label}: none");
+        }
+
+        for project in &report.projects {
+            let _ = writeln!(
+                output,
+                "Project {} ({}):",
+                project.project.name, project.project.path
+            );
+            for line in project_lines(project) {
+                let _ = writeln!(output, "- {line}");
+            }
         }
 
         if !report.audit_errors.is_empty() {
@@ -857,6 +1431,15 @@ fn render_launch_text(reports: &[LaunchReport]) -> String {
             }
         }
 
+        if report.stacks.is_empty() {
+            let _ = writeln!(output, "Detected stacks: none");
+        } else {
+            let _ = writeln!(output, "Detected stacks:");
+            for line in stack_lines(&report.stacks) {
+                let _ = writeln!(output, "- {line}");
+            }
+        }
+
         match &report.dockerfile {
             Some(contents) => {
                 let _ = writeln!(output, "Dockerfile:");
@@ -866,14 +1449,35 @@ fn render_launch_text(reports: &[LaunchReport]) -> String {
                 let _ = writeln!(output, "Dockerfile: unavailable");
             }
         }
+        if let Some(contents) = &report.dockerignore {
+            let _ = writeln!(output, ".dockerignore:");
+            let _ = writeln!(output, "{contents}");
+        }
 
-        match &report.ci_config {
-            Some(contents) => {
-                let _ = writeln!(output, ".gitlab-ci.yml:");
+        if report.ci_files.is_empty() {
+            let _ = writeln!(output, "CI config: unavailable");
+        }
+        for (file, contents) in &report.ci_files {
+            let _ = writeln!(output, "{file}:");
+            let _ = writeln!(output, "{contents}");
+        }
+
+        for launch in &report.projects {
+            let path = &launch.project.path;
+            let _ = writeln!(output, "Dockerfile ({path}):");
+            let _ = writeln!(output, "{}", launch.dockerfile);
+            let _ = writeln!(output, ".dockerignore ({path}):");
+            let _ = writeln!(output, "{}", launch.dockerignore);
+            for (file, contents) in &launch.ci_files {
+                let _ = writeln!(output, "{file} ({path}):");
                 let _ = writeln!(output, "{contents}");
             }
-            None => {
-                let _ = writeln!(output, "CI config: unavailable");
+        }
+
+        if let Some(verification) = &report.verification {
+            let _ = writeln!(output, "Local verification:");
+            for line in verification_lines(verification) {
+                let _ = writeln!(output, "- {line}");
             }
         }
 
@@ -892,14 +1496,18 @@ fn render_launch_text(reports: &[LaunchReport]) -> String {
 #[cfg(test)]
 mod tests {
     use super::{
-        BatchSource, CloneStatus, LaunchReport, OutputArgs, OutputFormat, RefitReport, RepoReport,
-        RepoSourceArgs, audit_local, clone_and_audit, clone_repo, emit_audit_reports,
-        emit_launch_reports, emit_refit_reports, launch_local, load_repo_paths_from_dir,
-        load_repo_targets, load_repo_urls, populate_audit, populate_launch, populate_refit,
-        refit_local, render_audit_text, render_launch_text, render_refit_text, repo_dir_name,
-        resolve_source_args, run_audit, run_launch, run_refit,
+        AuditArgs, BatchSource, CloneStatus, LaunchArgs, LaunchReport, OutputArgs, OutputFormat,
+        RefitReport, RepoReport, RepoSourceArgs, attach_cross_repo_duplicates, audit_local,
+        clone_and_audit, clone_repo, emit_audit_reports, emit_launch_reports, emit_refit_reports,
+        launch_local, load_repo_paths_from_dir, load_repo_targets, load_repo_urls, populate_audit,
+        populate_launch, populate_refit, refit_local, render_audit_text, render_launch_text,
+        render_refit_text, repo_dir_name, resolve_source_args, run_audit, run_launch, run_refit,
+    };
+    use shipshape_core::{
+        AuditCache, CiTarget, CoverageReport, CoverageSource, HealthBreakdown, HealthCategory,
+        HealthDeduction, HealthModel, LicensePolicy, Mechanic, Severity, ShipShapeError,
+        VerifyRuntime, Violation, format_language_stats,
     };
-    use shipshape_core::{Mechanic, ShipShapeError, Violation, format_language_stats};
     use std::collections::BTreeMap;
     use std::path::{Path, PathBuf};
     use std::process::Command;
@@ -1062,13 +1670,13 @@ mod tests {
         let missing = std::env::temp_dir().join(unique_dir_name());
         let mechanics = Arc::new(Vec::new());
 
-        let audit_report = audit_local(missing.clone(), mechanics.clone()).await;
+        let audit_report = audit_local(missing.clone(), mechanics.clone(), Arc::default()).await;
         assert!(matches!(audit_report.clone_status, CloneStatus::Failed(_)));
 
         let refit_report = refit_local(missing.clone(), mechanics.clone(), false).await;
         assert!(matches!(refit_report.clone_status, CloneStatus::Failed(_)));
 
-        let launch_report = launch_local(missing.clone()).await;
+        let launch_report = launch_local(missing.clone(), Arc::new(LaunchArgs::default())).await;
         assert!(matches!(launch_report.clone_status, CloneStatus::Failed(_)));
     }
 
@@ -1097,31 +1705,487 @@ mod tests {
         std::fs::create_dir_all(&dest).expect("create dest");
 
         let mechanics = Arc::new(Vec::new());
-        let report = clone_and_audit(source, dest.clone(), mechanics).await;
+        let report = clone_and_audit(source, dest.clone(), mechanics, Arc::default()).await;
 
         assert!(matches!(report.clone_status, CloneStatus::Failed(_)));
 
         std::fs::remove_dir_all(&dest).expect("cleanup dest");
     }
 
+    #[test]
+    fn populate_audit_reports_complexity_violations() {
+        let repo = temp_repo_with_file(
+            "src/lib.rs",
+            "pub fn pick(a: bool, b: bool) -> u8 {\n    if a && b { 1 } else { 0 }\n}\n",
+        );
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        let audit = AuditArgs {
+            max_cyclomatic: Some(2),
+            ..AuditArgs::default()
+        };
+
+        populate_audit(&mut report, &[], &audit);
+
+        let complexity = report.complexity.as_ref().expect("complexity");
+        assert_eq!(complexity.functions_analyzed, 1);
+        assert_eq!(complexity.max_cyclomatic, 3);
+        let violation = report
+            .violations
+            .iter()
+            .find(|violation| violation.id == "complexity-cyclomatic")
+            .expect("complexity violation");
+        assert_eq!(violation.location().as_deref(), Some("src/lib.rs:1"));
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn audit_reports_duplicates_within_and_across_repositories() {
+        let source = "pub fn parse(line: &str) -> Option<(String, String)> {\n    let trimmed = line.trim();\n    if trimmed.is_empty() {\n        return None;\n    }\n    let (key, value) = trimmed.split_once('=')?;\n    Some((key.to_string(), value.to_string()))\n}\n";
+        let repo_a = temp_repo_with_file("src/a.rs", source);
+        std::fs::write(repo_a.join("src/b.rs"), source).expect("write copy");
+        let repo_b = temp_repo_with_file("lib/c.rs", source);
+        let audit = AuditArgs {
+            min_duplicate_tokens: Some(20),
+            ..AuditArgs::default()
+        };
+        let mut reports = vec![
+            RepoReport::new("repo-a".to_string(), repo_a.clone()),
+            RepoReport::new("repo-b".to_string(), repo_b.clone()),
+        ];
+        for report in &mut reports {
+            report.clone_status = CloneStatus::Local;
+            populate_audit(report, &[], &audit);
+        }
+
+        attach_cross_repo_duplicates(&mut reports, &audit.duplication_config());
+
+        let first = reports[0].duplication.as_ref().expect("duplication");
+        assert_eq!(first.duplicate_blocks, 1);
+        assert_eq!(first.blocks[0].first.path, "src/a.rs");
+        assert_eq!(first.blocks[0].second.path, "src/b.rs");
+        assert_eq!(first.cross_repo.len(), 2);
+        let second = reports[1].duplication.as_ref().expect("duplication");
+        assert_eq!(second.duplicate_blocks, 0);
+        assert_eq!(second.cross_repo.len(), 2);
+        assert_eq!(second.cross_repo[0].second.repo.as_deref(), Some("repo-b"));
+        assert!(
+            render_audit_text(&reports)
+                .contains("Shared: repo-a:src/a.rs:1-8 and repo-b:lib/c.rs:1-8")
+        );
+
+        std::fs::remove_dir_all(&repo_a).expect("cleanup repo a");
+        std::fs::remove_dir_all(&repo_b).expect("cleanup repo b");
+    }
+
+    #[test]
+    fn populate_audit_reads_git_history() {
+        let repo = init_git_repo();
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        report.clone_status = CloneStatus::Local;
+
+        populate_audit(&mut report, &[], &AuditArgs::default());
+
+        let history = report.history.as_ref().expect("history");
+        assert_eq!(history.commits_analyzed, 1);
+        assert_eq!(history.active_contributors, 1);
+        assert!(render_audit_text(&[report]).contains("Last commit: 0 days ago"));
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
     #[test]
     fn populate_audit_collects_language_stats_and_violations() {
         let repo = temp_repo_with_file("src/main.rs", "fn main() {}\n");
+        std::fs::write(repo.join("LICENSE"), "SPDX-License-Identifier: MIT\n").expect("license");
         let mut report = RepoReport::new("local".to_string(), repo.clone());
         let mechanics: Vec<Arc<dyn Mechanic + Send + Sync>> = vec![Arc::new(TestMechanic {
             id: "demo",
             audit_result: Ok(vec![Violation {
                 id: "demo".to_string(),
                 message: "violation".to_string(),
+                severity: Severity::Warning,
+                path: None,
+                line: None,
             }]),
             dry_run_result: Ok(String::new()),
             apply_result: Ok(false),
         })];
 
-        populate_audit(&mut report, &mechanics);
+        populate_audit(&mut report, &mechanics, &AuditArgs::default());
 
         assert!(report.language_stats.is_some());
         assert_eq!(report.violations.len(), 1);
+        assert!(report.coverage.is_some());
+        assert!(report.health.is_some());
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn populate_audit_reuses_cached_results_for_unchanged_repos() {
+        let repo = temp_repo_with_file("src/main.rs", "fn main() {}\n");
+        let cache_dir = repo.with_extension("cache");
+        let audit = AuditArgs {
+            cache: Some(AuditCache::new(&cache_dir)),
+            ..AuditArgs::default()
+        };
+        let mechanic = |audit_result| -> Vec<Arc<dyn Mechanic + Send + Sync>> {
+            vec![Arc::new(TestMechanic {
+                id: "demo",
+                audit_result,
+                dry_run_result: Ok(String::new()),
+                apply_result: Ok(false),
+            })]
+        };
+        let violation = Violation {
+            id: "demo".to_string(),
+            message: "violation".to_string(),
+            severity: Severity::Warning,
+            path: None,
+            line: None,
+        };
+
+        let mut first = RepoReport::new("local".to_string(), repo.clone());
+        populate_audit(&mut first, &mechanic(Ok(vec![violation])), &audit);
+        let mut second = RepoReport::new("local".to_string(), repo.clone());
+        let failing = mechanic(Err("not rerun".to_string()));
+        populate_audit(&mut second, &failing, &audit);
+
+        assert!(second.audit_errors.is_empty(), "{:?}", second.audit_errors);
+        assert_eq!(second.language_stats, first.language_stats);
+        assert!(second.violations.iter().any(|v| v.id == "demo"));
+
+        std::fs::write(repo.join("src/main.rs"), "fn main() { run(); }\n").expect("edit");
+        let mut third = RepoReport::new("local".to_string(), repo.clone());
+        populate_audit(&mut third, &failing, &audit);
+        assert!(
+            third
+                .audit_errors
+                .iter()
+                .any(|err| err.contains("not rerun"))
+        );
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+        std::fs::remove_dir_all(&cache_dir).expect("cleanup cache");
+    }
+
+    #[test]
+    fn populate_audit_since_separates_new_and_preexisting_violations() {
+        let repo = init_git_repo();
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\nfn b() {}\nfn c() {}\n").expect("lib");
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(&repo)
+            .status()
+            .expect("git add");
+        Command::new("git")
+            .args([
+                "-c",
+                "user.name=ShipShape",
+                "-c",
+                "user.email=shipshape@example.com",
+                "commit",
+                "-qm",
+                "lib",
+            ])
+            .current_dir(&repo)
+            .status()
+            .expect("git commit");
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\nfn b2() {}\nfn c() {}\n").expect("edit");
+        std::fs::write(repo.join("new.rs"), "fn d() {}\n").expect("untracked");
+        let at = |id: &str, path: &str, line: Option<u32>| Violation {
+            id: id.to_string(),
+            message: "finding".to_string(),
+            severity: Severity::Warning,
+            path: Some(path.to_string()),
+            line,
+        };
+        let mechanics: Vec<Arc<dyn Mechanic + Send + Sync>> = vec![Arc::new(TestMechanic {
+            id: "demo",
+            audit_result: Ok(vec![
+                at("changed", "lib.rs", Some(2)),
+                at("untouched", "lib.rs", Some(3)),
+                at("new-file", "new.rs", None),
+                at("elsewhere", "README.md", Some(1)),
+            ]),
+            dry_run_result: Ok(String::new()),
+            apply_result: Ok(false),
+        })];
+        let audit = AuditArgs {
+            since: Some("HEAD".to_string()),
+            ..AuditArgs::default()
+        };
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+
+        populate_audit(&mut report, &mechanics, &audit);
+
+        let ids = |violations: &[Violation]| -> Vec<String> {
+            violations.iter().map(|v| v.id.clone()).collect()
+        };
+        assert!(report.audit_errors.is_empty(), "{:?}", report.audit_errors);
+        assert_eq!(ids(&report.violations), vec!["changed", "new-file"]);
+        let diff = report.diff.as_ref().expect("diff scope");
+        assert_eq!(diff.changed_files, vec!["lib.rs", "new.rs"]);
+        assert_eq!(diff.changed_lines, 2);
+        assert_eq!(ids(&diff.preexisting_violations), vec!["untouched"]);
+        report.clone_status = CloneStatus::Local;
+        let text = render_audit_text(std::slice::from_ref(&report));
+        assert!(text.contains("- Changes: HEAD to working tree"));
+        assert!(text.contains("New violations:\n- [changed]"));
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn populate_audit_measures_public_api_docs() {
+        let repo = temp_repo_with_file(
+            "src/lib.rs",
+            "//! Crate docs.\n\n/// Documented.\npub fn documented() {}\n\npub fn bare() {}\n",
+        );
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+
+        populate_audit(&mut report, &[], &AuditArgs::default());
+
+        let coverage = report.coverage.as_ref().expect("coverage");
+        assert_eq!(coverage.api_doc_coverage, Some(0.5));
+        assert_eq!((coverage.documented_items, coverage.public_items), (1, 2));
+        let violation = report
+            .violations
+            .iter()
+            .find(|violation| violation.id == "undocumented-public-api")
+            .expect("undocumented violation");
+        assert_eq!(violation.location().as_deref(), Some("src/lib.rs:6"));
+        let health = report.health.as_ref().expect("health");
+        assert!(
+            health
+                .deductions
+                .iter()
+                .any(|deduction| deduction.reason == "API doc coverage 50.0% (1/2 public items)")
+        );
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn populate_audit_lists_untested_modules() {
+        let repo = temp_repo_with_file("app/models.py", "def load():\n    pass\n");
+        std::fs::write(repo.join("app/views.py"), "def index():\n    pass\n").expect("views");
+        std::fs::create_dir_all(repo.join("tests")).expect("tests dir");
+        std::fs::write(
+            repo.join("tests/test_models.py"),
+            "def test_load():\n    pass\n",
+        )
+        .expect("test file");
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        report.clone_status = CloneStatus::Local;
+
+        populate_audit(&mut report, &[], &AuditArgs::default());
+
+        let mapping = report.test_mapping.as_ref().expect("test mapping");
+        assert_eq!((mapping.tested_files, mapping.source_files), (1, 2));
+        assert_eq!(mapping.untested, vec!["app/views.py".to_string()]);
+        let output = render_audit_text(&[report]);
+        assert!(output.contains("Test mapping:\n- Modules with tests: 50.0% (1/2)"));
+        assert!(output.contains("- Untested: app/views.py"));
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn populate_audit_checks_license_headers() {
+        let repo = temp_repo_with_file(
+            "src/lib.rs",
+            "// SPDX-License-Identifier: GPL-3.0-only\npub fn a() {}\n",
+        );
+        std::fs::write(repo.join("src/main.rs"), "fn main() {}\n").expect("main");
+        std::fs::write(
+            repo.join("LICENSE"),
+            "Permission is hereby granted, free of charge, to any person",
+        )
+        .expect("license");
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        report.clone_status = CloneStatus::Local;
+        let audit = AuditArgs {
+            license_policy: LicensePolicy {
+                require_headers: true,
+                allowed: Vec::new(),
+            },
+            ..AuditArgs::default()
+        };
+
+        populate_audit(&mut report, &[], &audit);
+
+        let license = report.license.as_ref().expect("license");
+        assert_eq!(license.license.as_deref(), Some("MIT"));
+        let rules: Vec<&str> = report
+            .violations
+            .iter()
+            .map(|violation| violation.id.as_str())
+            .filter(|id| id.starts_with("license-"))
+            .collect();
+        assert_eq!(
+            rules,
+            vec!["license-incompatible", "license-header-missing"]
+        );
+        let output = render_audit_text(&[report]);
+        assert!(output.contains("License:\n- License: MIT (LICENSE)"));
+        assert!(output.contains("- Incompatible: src/lib.rs (GPL-3.0-only)"));
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn populate_audit_excludes_generated_and_vendored_files() {
+        let repo = temp_repo_with_file("src/main.rs", "fn main() {}\n");
+        std::fs::create_dir_all(repo.join("vendor/lib")).expect("vendor dir");
+        std::fs::write(repo.join("vendor/lib/util.py"), "def util():\n    pass\n")
+            .expect("vendored");
+        std::fs::write(
+            repo.join("src/schema.rs"),
+            "// @generated automatically by Diesel CLI.\npub fn table() {}\n",
+        )
+        .expect("generated");
+        std::fs::write(repo.join("package-lock.json"), "{}\n").expect("lockfile");
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        report.clone_status = CloneStatus::Local;
+
+        populate_audit(&mut report, &[], &AuditArgs::default());
+
+        let excluded: Vec<&str> = report
+            .excluded_files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect();
+        assert_eq!(
+            excluded,
+            vec!["package-lock.json", "src/schema.rs", "vendor/lib/util.py"]
+        );
+        let stats = report.language_stats.as_ref().expect("stats");
+        assert_eq!(stats.keys().collect::<Vec<_>>(), vec!["Rust"]);
+        assert_eq!(report.coverage.as_ref().expect("coverage").code_files, 1);
+        let output = render_audit_text(std::slice::from_ref(&report));
+        assert!(output.contains(
+            "Generated and vendored files:\n- Excluded: 2 generated, 1 vendored\n- Generated: package-lock.json (lockfile)"
+        ));
+
+        let audit = AuditArgs {
+            include_generated: true,
+            ..AuditArgs::default()
+        };
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        populate_audit(&mut report, &[], &audit);
+        assert!(report.excluded_files.is_empty());
+        assert_eq!(report.coverage.as_ref().expect("coverage").code_files, 3);
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    fn temp_monorepo() -> PathBuf {
+        let repo = temp_repo_with_file("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n");
+        for (path, contents) in [
+            ("crates/core/Cargo.toml", "[package]\nname = \"core\"\n"),
+            ("crates/core/src/lib.rs", "pub fn add() {}\n"),
+            ("web/package.json", "{\"name\": \"web\"}"),
+            ("web/src/app.js", "export function app() {}\n"),
+            ("web/src/app.test.js", "test('app', () => {});\n"),
+            ("tools/pyproject.toml", "[project]\nname = \"tools\"\n"),
+            ("tools/run.py", "def run():\n    pass\n"),
+        ] {
+            let path = repo.join(path);
+            std::fs::create_dir_all(path.parent().expect("parent")).expect("project dir");
+            std::fs::write(path, contents).expect("project file");
+        }
+        repo
+    }
+
+    #[test]
+    fn populate_audit_reports_each_project() {
+        let repo = temp_monorepo();
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        report.clone_status = CloneStatus::Local;
+
+        populate_audit(&mut report, &[], &AuditArgs::default());
+
+        let projects: Vec<(&str, &str, Option<&str>)> = report
+            .projects
+            .iter()
+            .map(|project| {
+                (
+                    project.project.path.as_str(),
+                    project.project.name.as_str(),
+                    project.project.workspace.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            projects,
+            vec![
+                ("crates/core", "core", Some(".")),
+                ("tools", "tools", None),
+                ("web", "web", None)
+            ]
+        );
+        let web = &report.projects[2];
+        let stats = web.language_stats.as_ref().expect("web stats");
+        assert_eq!(stats.keys().collect::<Vec<_>>(), vec!["JSON", "JavaScript"]);
+        let coverage = web.coverage.as_ref().expect("web coverage");
+        assert_eq!((coverage.code_files, coverage.test_files), (2, 1));
+        assert!(web.health.is_some());
+        let output = render_audit_text(&[report]);
+        assert!(output.contains("Project web (web):\n- Kind: Node\n"));
+
+        let mut launch = LaunchReport::new("local".to_string(), repo.clone());
+        populate_launch(&mut launch, &LaunchArgs::default());
+        let dockerfiles: Vec<(&str, bool)> = launch
+            .projects
+            .iter()
+            .map(|project| {
+                (
+                    project.project.path.as_str(),
+                    project.dockerfile.contains("node:20-alpine"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            dockerfiles,
+            vec![("crates/core", false), ("tools", false), ("web", true)]
+        );
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn populate_audit_applies_custom_health_model() {
+        let repo = temp_repo_with_file("src/main.rs", "fn main() {}\n");
+        std::fs::write(repo.join("LICENSE"), "SPDX-License-Identifier: MIT\n").expect("license");
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        let mechanics: Vec<Arc<dyn Mechanic + Send + Sync>> = vec![Arc::new(TestMechanic {
+            id: "demo",
+            audit_result: Ok(vec![Violation {
+                id: "demo".to_string(),
+                message: "violation".to_string(),
+                severity: Severity::Warning,
+                path: None,
+                line: None,
+            }]),
+            dry_run_result: Ok(String::new()),
+            apply_result: Ok(false),
+        })];
+        let audit = AuditArgs {
+            health_model: HealthModel::from_toml_str(
+                "test_thresholds = []\ndoc_thresholds = []\n[violations.rules]\ndemo = 7\n",
+            )
+            .expect("model"),
+            ..AuditArgs::default()
+        };
+
+        populate_audit(&mut report, &mechanics, &audit);
+
+        let health = report.health.expect("health");
+        assert_eq!(health.score, 93);
+        assert_eq!(health.deductions.len(), 1);
+        assert_eq!(health.deductions[0].category, HealthCategory::Violations);
 
         std::fs::remove_dir_all(&repo).expect("cleanup repo");
     }
@@ -1134,9 +2198,34 @@ mod tests {
         );
         let mechanics = Vec::new();
 
-        populate_audit(&mut report, &mechanics);
+        populate_audit(&mut report, &mechanics, &AuditArgs::default());
 
         assert!(!report.audit_errors.is_empty());
+        assert!(report.health.is_none());
+    }
+
+    #[test]
+    fn populate_audit_ingests_coverage_artifacts() {
+        let repo = temp_repo_with_file("src/lib.rs", "pub fn demo() {}\n");
+        std::fs::write(
+            repo.join("coverage.out"),
+            "mode: set\nexample.com/demo/lib.go:1.1,2.2 4 1\nexample.com/demo/lib.go:3.1,4.2 1 0\n",
+        )
+        .expect("write profile");
+        let mut report = RepoReport::new("local".to_string(), repo.clone());
+        let audit = AuditArgs {
+            coverage_reports: vec![PathBuf::from("coverage.out")],
+            ..AuditArgs::default()
+        };
+
+        populate_audit(&mut report, &[], &audit);
+
+        let coverage = report.coverage.expect("coverage");
+        assert_eq!(coverage.source, CoverageSource::Measured);
+        assert_eq!(coverage.line_coverage, Some(0.8));
+        assert_eq!(coverage.artifacts.len(), 1);
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
     }
 
     #[test]
@@ -1179,12 +2268,74 @@ mod tests {
     #[test]
     fn populate_launch_sets_outputs() {
         let repo = temp_repo_with_file("Cargo.toml", "[package]\nname = \"demo\"\n");
+        std::fs::write(repo.join("rust-toolchain"), "1.80.0\n").expect("write toolchain");
         let mut report = LaunchReport::new("local".to_string(), repo.clone());
 
-        populate_launch(&mut report);
+        populate_launch(&mut report, &LaunchArgs::default());
 
-        assert!(report.dockerfile.is_some());
-        assert!(report.ci_config.is_some());
+        assert!(
+            report
+                .dockerfile
+                .as_deref()
+                .is_some_and(|dockerfile| dockerfile.contains("\nFROM rust:1.80.0 AS deps\n"))
+        );
+        assert!(
+            report
+                .dockerignore
+                .as_deref()
+                .is_some_and(|dockerignore| dockerignore.contains("\n# rust\ntarget\n"))
+        );
+        assert_eq!(
+            report.ci_files.keys().collect::<Vec<_>>(),
+            vec![".github/workflows/shipshape.yml", ".gitlab-ci.yml"]
+        );
+        assert!(
+            report.ci_files[".github/workflows/shipshape.yml"].contains("toolchain: \"1.80.0\"")
+        );
+        assert_eq!(report.stacks.len(), 1);
+        assert_eq!(report.stacks[0].evidence, vec!["Cargo.toml"]);
+        let toolchain = report.stacks[0].toolchain.as_ref().expect("toolchain");
+        assert_eq!(toolchain.source, "rust-toolchain");
+
+        let mut uncached = LaunchReport::new("local".to_string(), repo.clone());
+        let launch = LaunchArgs {
+            ci: vec![CiTarget::GitLab],
+            no_ci_cache: true,
+            ..LaunchArgs::default()
+        };
+        populate_launch(&mut uncached, &launch);
+        assert_eq!(
+            uncached.ci_files.keys().collect::<Vec<_>>(),
+            vec![".gitlab-ci.yml"]
+        );
+        assert!(!uncached.ci_files[".gitlab-ci.yml"].contains("cache:"));
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    #[test]
+    fn populate_launch_verifies_locally() {
+        let repo = temp_repo_with_file("README.md", "# demo\n");
+        let mut report = LaunchReport::new("local".to_string(), repo.clone());
+        let launch = LaunchArgs {
+            verify_local: true,
+            verify_runtime: VerifyRuntime::Host,
+            verify_timeout: Some(30),
+            ..LaunchArgs::default()
+        };
+
+        report.clone_status = CloneStatus::Local;
+        populate_launch(&mut report, &launch);
+
+        let verification = report.verification.as_ref().expect("verification");
+        assert_eq!(verification.runtime, "host");
+        assert!(verification.passed);
+        assert_eq!(verification.jobs.len(), 1);
+        assert_eq!(verification.jobs[0].job, "test");
+        assert!(
+            render_launch_text(&[report])
+                .contains("Local verification:\n- passed (host)\n- test (.): passed in ")
+        );
 
         std::fs::remove_dir_all(&repo).expect("cleanup repo");
     }
@@ -1210,7 +2361,34 @@ mod tests {
         report_a.violations = vec![Violation {
             id: "v1".to_string(),
             message: "m1".to_string(),
+            severity: Severity::Warning,
+            path: None,
+            line: None,
         }];
+        report_a.health = Some(HealthBreakdown {
+            score: 71,
+            deductions: vec![HealthDeduction {
+                category: HealthCategory::Tests,
+                points: 29,
+                reason: "test file ratio 50.0% (1/2)".to_string(),
+            }],
+        });
+        report_a.coverage = Some(CoverageReport {
+            code_files: 2,
+            test_files: 1,
+            doc_files: 0,
+            test_coverage: 0.5,
+            doc_coverage: 0.0,
+            low_test_coverage: false,
+            low_doc_coverage: true,
+            source: CoverageSource::Heuristic,
+            line_coverage: None,
+            branch_coverage: None,
+            artifacts: Vec::new(),
+            public_items: 0,
+            documented_items: 0,
+            api_doc_coverage: None,
+        });
 
         let mut report_b = RepoReport::new("local".to_string(), PathBuf::from("/tmp/b"));
         report_b.clone_status = CloneStatus::Local;
@@ -1239,6 +2417,8 @@ mod tests {
         assert!(output.contains("Violations: none"));
         assert!(output.contains("[v1] m1"));
         assert!(output.contains("Audit errors:"));
+        assert!(output.contains("Health score: 71/100"));
+        assert!(output.contains("- Source: heuristic"));
     }
 
     #[test]
@@ -1272,7 +2452,9 @@ mod tests {
         let mut report_a = LaunchReport::new("cloned".to_string(), PathBuf::from("/tmp/a"));
         report_a.clone_status = CloneStatus::Cloned;
         report_a.dockerfile = Some("FROM rust".to_string());
-        report_a.ci_config = Some("stages:".to_string());
+        report_a
+            .ci_files
+            .insert(".gitlab-ci.yml".to_string(), "stages:".to_string());
 
         let mut report_b = LaunchReport::new("local".to_string(), PathBuf::from("/tmp/b"));
         report_b.clone_status = CloneStatus::Local;
@@ -1350,6 +2532,7 @@ mod tests {
             output.clone(),
             1,
             Vec::new(),
+            AuditArgs::default(),
             report.clone(),
         )
         .await
@@ -1362,6 +2545,7 @@ mod tests {
             output.clone(),
             1,
             Vec::new(),
+            AuditArgs::default(),
             report.clone(),
         )
         .await
@@ -1382,6 +2566,7 @@ mod tests {
             BatchSource::Path(repo.clone()),
             output.clone(),
             1,
+            LaunchArgs::default(),
             report.clone(),
         )
         .await
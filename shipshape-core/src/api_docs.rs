@@ -0,0 +1,711 @@
+//! Documentation coverage for public APIs.
+//!
+//! Finds the public items of Rust, Python, Go, JavaScript and TypeScript
+//! sources and checks each for documentation: `///` comments on Rust `pub`
+//! items, docstrings on public Python functions and classes, comments on
+//! exported Go identifiers and JSDoc blocks on JS/TS exports. Whenever public
+//! items are found the result replaces the doc-file ratio in
+//! [`CoverageReport`]. The scan is line based and ignores visibility of
+//! enclosing modules, so counts are approximate.
+
+use std::path::Path;
+
+use crate::Result;
+use crate::complexity::SourceLanguage;
+use crate::coverage::is_dependency_dir;
+use crate::domain::{CoverageReport, Severity, Violation};
+use crate::fs::FileSystem;
+use crate::inspector::is_test_file;
+
+/// Violation id for files with undocumented public items.
+pub const UNDOCUMENTED_API_RULE: &str = "undocumented-public-api";
+/// API doc coverage ratio below which documentation is flagged as low.
+pub const LOW_API_DOC_COVERAGE: f64 = 0.50;
+/// Number of undocumented item names listed in a violation message.
+const NAMES_PER_VIOLATION: usize = 5;
+
+/// A public item found in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicItem {
+    /// Item name.
+    pub name: String,
+    /// 1-based line of the declaration.
+    pub line: u32,
+    /// Whether the item carries documentation.
+    pub documented: bool,
+}
+
+/// Documentation counts for the public items of a repository.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiDocCoverage {
+    /// Source files scanned for public items.
+    pub files_analyzed: usize,
+    /// Public items found.
+    pub public_items: usize,
+    /// Public items that carry documentation.
+    pub documented_items: usize,
+}
+
+impl ApiDocCoverage {
+    /// Documented share of public items, or `None` if none were found.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.public_items == 0 {
+            return None;
+        }
+        Some(self.documented_items as f64 / self.public_items as f64)
+    }
+}
+
+/// Measure API documentation across a repository.
+///
+/// Returns the totals and one [`UNDOCUMENTED_API_RULE`] violation per file
+/// with undocumented public items. Tests, dependency directories and files
+/// that are not valid UTF-8 are skipped.
+pub fn inspect_api_docs<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+) -> Result<(ApiDocCoverage, Vec<Violation>)> {
+    let mut files = fs.list_files(root)?;
+    files.sort();
+    let mut coverage = ApiDocCoverage::default();
+    let mut violations = Vec::new();
+
+    for path in files {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if is_dependency_dir(relative) || is_test_file(relative) {
+            continue;
+        }
+        let Some(language) = SourceLanguage::from_path(&path) else {
+            continue;
+        };
+        if matches!(language, SourceLanguage::C | SourceLanguage::Cpp) {
+            continue;
+        }
+        let Ok(source) = fs.read_to_string(&path) else {
+            continue;
+        };
+        coverage.files_analyzed += 1;
+
+        let items = find_public_items(language, &source);
+        let undocumented: Vec<&PublicItem> = items.iter().filter(|item| !item.documented).collect();
+        coverage.public_items += items.len();
+        coverage.documented_items += items.len() - undocumented.len();
+        if let Some(first) = undocumented.first() {
+            violations.push(Violation {
+                id: UNDOCUMENTED_API_RULE.to_string(),
+                message: undocumented_message(&undocumented),
+                severity: Severity::Info,
+                path: Some(relative.to_string_lossy().replace('\\', "/")),
+                line: Some(first.line),
+            });
+        }
+    }
+
+    Ok((coverage, violations))
+}
+
+/// Replace the doc-file ratio of a coverage report with API doc coverage.
+///
+/// Leaves the report untouched when no public items were found, so the
+/// file-based ratio remains as a fallback.
+pub fn apply_api_doc_coverage(report: &mut CoverageReport, api: &ApiDocCoverage) {
+    let Some(ratio) = api.ratio() else {
+        return;
+    };
+    report.public_items = api.public_items;
+    report.documented_items = api.documented_items;
+    report.api_doc_coverage = Some(ratio);
+    report.low_doc_coverage = ratio < LOW_API_DOC_COVERAGE;
+}
+
+/// List the public items declared in a single source file.
+///
+/// C and C++ have no notion of exported items here and yield nothing.
+pub fn find_public_items(language: SourceLanguage, source: &str) -> Vec<PublicItem> {
+    match language {
+        SourceLanguage::Rust => rust_items(source),
+        SourceLanguage::Python => python_items(source),
+        SourceLanguage::Go => go_items(source),
+        SourceLanguage::JavaScript | SourceLanguage::TypeScript => script_items(source),
+        SourceLanguage::C | SourceLanguage::Cpp => Vec::new(),
+    }
+}
+
+fn undocumented_message(items: &[&PublicItem]) -> String {
+    let mut names: Vec<String> = items
+        .iter()
+        .take(NAMES_PER_VIOLATION)
+        .map(|item| format!("`{}`", item.name))
+        .collect();
+    if items.len() > NAMES_PER_VIOLATION {
+        names.push(format!("and {} more", items.len() - NAMES_PER_VIOLATION));
+    }
+    let suffix = if items.len() == 1 { "" } else { "s" };
+    format!(
+        "{} undocumented public item{suffix}: {}",
+        items.len(),
+        names.join(", ")
+    )
+}
+
+fn item(name: String, index: usize, documented: bool) -> PublicItem {
+    PublicItem {
+        name,
+        line: index as u32 + 1,
+        documented,
+    }
+}
+
+fn rust_items(source: &str) -> Vec<PublicItem> {
+    let mut items = Vec::new();
+    let mut documented = false;
+    let mut in_doc_block = false;
+    // Unclosed brackets of a multi-line attribute.
+    let mut attribute_depth = 0i32;
+
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if in_doc_block {
+            in_doc_block = !line.contains("*/");
+            continue;
+        }
+        if attribute_depth > 0 || line.starts_with("#[") {
+            attribute_depth += balance(line, '[', ']');
+            if line.starts_with("#[doc") {
+                documented = true;
+            }
+            continue;
+        }
+        if line.starts_with("///") && !line.starts_with("////") {
+            documented = true;
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("/**")
+            && !rest.starts_with('*')
+        {
+            documented = true;
+            in_doc_block = !rest.contains("*/");
+            continue;
+        }
+        // Attributes and plain comments sit between docs and the item.
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        if let Some(name) = rust_public_name(line) {
+            items.push(item(name, index, documented));
+        }
+        documented = false;
+    }
+    items
+}
+
+/// Net count of `open` over `close` characters in a line.
+fn balance(line: &str, open: char, close: char) -> i32 {
+    line.chars()
+        .map(|ch| match ch {
+            _ if ch == open => 1,
+            _ if ch == close => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn rust_public_name(line: &str) -> Option<String> {
+    let words: Vec<&str> = line.strip_prefix("pub ")?.split_whitespace().collect();
+    let mut index = 0;
+    while let Some(word) = words.get(index) {
+        match *word {
+            "async" | "unsafe" | "extern" | "default" => index += 1,
+            _ if word.starts_with('"') => index += 1,
+            "const" | "static" if matches!(words.get(index + 1), Some(&"fn")) => index += 1,
+            "static" if matches!(words.get(index + 1), Some(&"mut")) => index += 1,
+            "mod" if line.ends_with(';') => return None,
+            "fn" | "struct" | "enum" | "trait" | "type" | "union" | "mod" | "const" | "static"
+            | "mut" => {
+                return identifier(words.get(index + 1)?);
+            }
+            "use" | "impl" | "crate" | "macro_rules!" => return None,
+            _ => {
+                // A struct field: `pub name: Type`.
+                let name = identifier(word)?;
+                let rest = &word[name.len()..];
+                return (rest.starts_with(':') && !rest.starts_with("::")).then_some(name);
+            }
+        }
+    }
+    None
+}
+
+fn python_items(source: &str) -> Vec<PublicItem> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut items = Vec::new();
+    // Enclosing definitions as (indent, is_class).
+    let mut scopes: Vec<(usize, bool)> = Vec::new();
+    let mut open_string: Option<&str> = None;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let raw = lines[index];
+        let trimmed = raw.trim_start();
+        index += 1;
+        if let Some(delimiter) = open_string {
+            if trimmed.contains(delimiter) {
+                open_string = None;
+            }
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        open_string = ["\"\"\"", "'''"]
+            .into_iter()
+            .find(|delimiter| trimmed.matches(delimiter).count() % 2 == 1);
+
+        let indent = raw.len() - trimmed.len();
+        while scopes.last().is_some_and(|(scope, _)| indent <= *scope) {
+            scopes.pop();
+        }
+        let header = trimmed.strip_prefix("async ").unwrap_or(trimmed);
+        let (is_class, rest) = if let Some(rest) = header.strip_prefix("def ") {
+            (false, rest)
+        } else if let Some(rest) = header.strip_prefix("class ") {
+            (true, rest)
+        } else {
+            continue;
+        };
+        let Some(name) = identifier(rest.trim_start()) else {
+            continue;
+        };
+
+        let end = python_header_end(&lines, index - 1);
+        let documented = lines[end + 1..]
+            .iter()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .is_some_and(is_python_string);
+        let public = !name.starts_with('_') && scopes.iter().all(|(_, class)| *class);
+        if public {
+            items.push(item(name, index - 1, documented));
+        }
+        scopes.push((indent, is_class));
+        index = end + 1;
+    }
+    items
+}
+
+/// Index of the line closing a (possibly multi-line) `def`/`class` header.
+fn python_header_end(lines: &[&str], start: usize) -> usize {
+    let mut depth = 0i32;
+    for (index, line) in lines.iter().enumerate().skip(start).take(50) {
+        let code = line.split('#').next().unwrap_or_default();
+        for ch in code.chars() {
+            match ch {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth <= 0 && code.contains(':') {
+            return index;
+        }
+    }
+    start
+}
+
+fn is_python_string(line: &str) -> bool {
+    let rest = line.trim_start_matches(['r', 'R', 'u', 'U', 'b', 'B', 'f', 'F']);
+    rest.len() + 2 >= line.len() && (rest.starts_with('"') || rest.starts_with('\''))
+}
+
+fn go_items(source: &str) -> Vec<PublicItem> {
+    let mut items = Vec::new();
+    let mut documented = false;
+    let mut in_comment = false;
+    // Inside a `const (`/`var (`/`type (` group: whether the group is documented.
+    let mut group: Option<bool> = None;
+
+    for (index, raw) in source.lines().enumerate() {
+        let line = raw.trim();
+        if in_comment {
+            in_comment = !line.contains("*/");
+            continue;
+        }
+        if line.starts_with("//") {
+            documented = true;
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("/*") {
+            documented = true;
+            in_comment = !rest.contains("*/");
+            continue;
+        }
+        if line.is_empty() {
+            documented = false;
+            continue;
+        }
+        if let Some(group_documented) = group {
+            if line == ")" {
+                group = None;
+            } else if let Some(name) = identifier(line).filter(|name| is_exported(name)) {
+                items.push(item(name, index, documented || group_documented));
+            }
+            documented = false;
+            continue;
+        }
+        if raw.starts_with(char::is_whitespace) {
+            documented = false;
+            continue;
+        }
+
+        let name = if let Some(rest) = line.strip_prefix("func ") {
+            // Skip the receiver of a method.
+            let rest = match rest.strip_prefix('(') {
+                Some(receiver) => receiver.split_once(')').map_or("", |(_, rest)| rest),
+                None => rest,
+            };
+            identifier(rest.trim_start())
+        } else if let Some(rest) = ["type ", "var ", "const "]
+            .into_iter()
+            .find_map(|keyword| line.strip_prefix(keyword))
+        {
+            if rest.trim() == "(" {
+                group = Some(documented);
+                documented = false;
+                continue;
+            }
+            identifier(rest.trim_start())
+        } else {
+            None
+        };
+        if let Some(name) = name.filter(|name| is_exported(name)) {
+            items.push(item(name, index, documented));
+        }
+        documented = false;
+    }
+    items
+}
+
+fn is_exported(name: &str) -> bool {
+    name.starts_with(|ch: char| ch.is_uppercase())
+}
+
+fn script_items(source: &str) -> Vec<PublicItem> {
+    let mut items = Vec::new();
+    let mut documented = false;
+    // Inside a block comment: whether it is a JSDoc block.
+    let mut block: Option<bool> = None;
+    // Unclosed parentheses of a multi-line decorator.
+    let mut decorator_depth = 0i32;
+
+    for (index, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if let Some(jsdoc) = block {
+            if line.contains("*/") {
+                block = None;
+                documented = jsdoc;
+            }
+            continue;
+        }
+        if decorator_depth > 0 || line.starts_with('@') {
+            decorator_depth += balance(line, '(', ')');
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("/*") {
+            let jsdoc = rest.starts_with('*');
+            if rest.contains("*/") {
+                documented = jsdoc;
+            } else {
+                block = Some(jsdoc);
+            }
+            continue;
+        }
+        // Decorators and line comments sit between JSDoc and the export.
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        if let Some(name) = export_name(line) {
+            items.push(item(name, index, documented));
+        }
+        documented = false;
+    }
+    items
+}
+
+fn export_name(line: &str) -> Option<String> {
+    let words: Vec<&str> = line.strip_prefix("export ")?.split_whitespace().collect();
+    let mut index = 0;
+    while let Some(word) = words.get(index) {
+        match word.trim_end_matches('*') {
+            "async" | "abstract" | "declare" => index += 1,
+            "const" if matches!(words.get(index + 1), Some(&"enum")) => index += 1,
+            "default" => match words.get(index + 1) {
+                Some(next) if is_script_declaration(next.trim_end_matches('*')) => index += 1,
+                _ => return Some("default".to_string()),
+            },
+            keyword if is_script_declaration(keyword) => {
+                return words
+                    .get(index + 1)
+                    .and_then(|name| identifier(name))
+                    .or_else(|| Some("default".to_string()));
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn is_script_declaration(word: &str) -> bool {
+    matches!(
+        word,
+        "async"
+            | "abstract"
+            | "function"
+            | "class"
+            | "const"
+            | "let"
+            | "var"
+            | "interface"
+            | "type"
+            | "enum"
+            | "namespace"
+    )
+}
+
+fn identifier(text: &str) -> Option<String> {
+    let end = text
+        .find(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '$'))
+        .unwrap_or(text.len());
+    let name = &text[..end];
+    (!name.is_empty() && !name.starts_with(|ch: char| ch.is_ascii_digit()))
+        .then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MockFileSystem;
+    use std::path::PathBuf;
+
+    fn names(items: &[PublicItem]) -> Vec<(&str, bool)> {
+        items
+            .iter()
+            .map(|item| (item.name.as_str(), item.documented))
+            .collect()
+    }
+
+    #[test]
+    fn finds_rust_public_items() {
+        let source = r#"//! Module docs.
+pub mod nested;
+
+/// Documented struct.
+#[derive(
+    Debug,
+    Clone,
+)]
+pub struct Config {
+    /// Documented field.
+    pub name: String,
+    pub count: u32,
+    private: bool,
+}
+
+pub(crate) fn internal() {}
+
+/** Block docs. */
+pub const fn answer() -> u32 { 42 }
+
+pub async fn fetch() {}
+pub static mut COUNTER: u32 = 0;
+pub use crate::other::Thing;
+"#;
+        let items = rust_items(source);
+        assert_eq!(
+            names(&items),
+            vec![
+                ("Config", true),
+                ("name", true),
+                ("count", false),
+                ("answer", true),
+                ("fetch", false),
+                ("COUNTER", false),
+            ]
+        );
+        assert_eq!(items[0].line, 9);
+    }
+
+    #[test]
+    fn finds_python_public_items() {
+        let source = r#"class Client:
+    """A client."""
+
+    def __init__(self):
+        pass
+
+    def send(
+        self,
+        payload,
+    ):
+        '''Send a payload.'''
+        def helper():
+            pass
+        return helper
+
+    def close(self):
+        return None
+
+
+def _private():
+    pass
+
+
+async def load(path):
+    """
+Load a file.
+"""
+    def inner():
+        pass
+
+
+def save(path): pass
+"#;
+        assert_eq!(
+            names(&python_items(source)),
+            vec![
+                ("Client", true),
+                ("send", true),
+                ("close", false),
+                ("load", true),
+                ("save", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_go_exported_identifiers() {
+        let source = r#"package store
+
+// Store keeps records.
+type Store struct{}
+
+func NewStore() *Store { return &Store{} }
+
+// Get returns a record.
+func (s *Store) Get(id string) string { return id }
+
+func (s *Store) helper() {}
+
+// Limits for the store.
+const (
+	MaxSize = 10
+	// MinSize is the minimum.
+	MinSize = 1
+	hidden = 2
+)
+
+var (
+	Default = NewStore()
+)
+"#;
+        assert_eq!(
+            names(&go_items(source)),
+            vec![
+                ("Store", true),
+                ("NewStore", false),
+                ("Get", true),
+                ("MaxSize", true),
+                ("MinSize", true),
+                ("Default", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_script_exports() {
+        let source = r#"/**
+ * Adds numbers.
+ */
+export function add(a, b) { return a + b; }
+
+/* Not JSDoc. */
+export const limit = 10;
+
+/** A widget. */
+@Component({
+  selector: "app-widget",
+})
+export class Widget {}
+
+export default async function () {}
+export interface Options { verbose: boolean }
+export { add as sum };
+"#;
+        assert_eq!(
+            names(&script_items(source)),
+            vec![
+                ("add", true),
+                ("limit", false),
+                ("Widget", true),
+                ("default", false),
+                ("Options", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_undocumented_items_per_file() {
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(|_| {
+            Ok(vec![
+                PathBuf::from("/repo/src/lib.rs"),
+                PathBuf::from("/repo/src/util.py"),
+                PathBuf::from("/repo/tests/api.rs"),
+                PathBuf::from("/repo/README.md"),
+            ])
+        });
+        fs.expect_read_to_string().returning(|path| {
+            Ok(if path.ends_with("lib.rs") {
+                "/// Documented.\npub fn a() {}\npub fn b() {}\npub fn c() {}\n".to_string()
+            } else {
+                "def run():\n    \"\"\"Run.\"\"\"\n".to_string()
+            })
+        });
+
+        let (api, violations) = inspect_api_docs(&fs, Path::new("/repo")).expect("api docs");
+
+        assert_eq!(api.files_analyzed, 2);
+        assert_eq!(api.public_items, 4);
+        assert_eq!(api.documented_items, 2);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].id, UNDOCUMENTED_API_RULE);
+        assert_eq!(violations[0].severity, Severity::Info);
+        assert_eq!(violations[0].location().as_deref(), Some("src/lib.rs:3"));
+        assert_eq!(
+            violations[0].message,
+            "2 undocumented public items: `b`, `c`"
+        );
+
+        let mut report = CoverageReport {
+            code_files: 2,
+            test_files: 0,
+            doc_files: 1,
+            test_coverage: 0.0,
+            doc_coverage: 0.5,
+            low_test_coverage: true,
+            low_doc_coverage: false,
+            source: Default::default(),
+            line_coverage: None,
+            branch_coverage: None,
+            artifacts: Vec::new(),
+            public_items: 0,
+            documented_items: 0,
+            api_doc_coverage: None,
+        };
+        apply_api_doc_coverage(&mut report, &ApiDocCoverage::default());
+        assert_eq!(report.api_doc_coverage, None);
+        apply_api_doc_coverage(&mut report, &api);
+        assert_eq!(report.api_doc_coverage, Some(0.5));
+        assert_eq!(report.effective_doc_coverage(), 0.5);
+        assert!(!report.low_doc_coverage);
+    }
+}
@@ -0,0 +1,246 @@
+//! Filesystem view of a tar or zip archive.
+//!
+//! Archives are unpacked into memory rather than onto disk, so uploaded
+//! tarballs can be audited directly and hostile entry names (absolute paths,
+//! `..`) never escape the archive root.
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use crate::error::{Result, ShipShapeError};
+use crate::fs::{FileSystem, MemoryFileSystem};
+
+/// Supported archive formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// An uncompressed tar archive.
+    Tar,
+    /// A gzip-compressed tar archive.
+    TarGz,
+    /// A zip archive.
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detect the format from a file name (`.tar`, `.tar.gz`, `.tgz`, `.zip`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// The regular files of an archive, presented under a root path.
+///
+/// When every entry sits inside one top-level directory (as in
+/// `project-1.0.tar.gz` or a forge download), that directory is stripped so
+/// the root holds the project itself. Links and special files are skipped.
+#[derive(Debug, Clone)]
+pub struct ArchiveFileSystem {
+    root: PathBuf,
+    files: MemoryFileSystem,
+}
+
+impl ArchiveFileSystem {
+    /// Load an archive file, presenting its contents under the archive path.
+    pub fn open(path: &Path) -> Result<Self> {
+        let format = ArchiveFormat::from_path(path).ok_or_else(|| {
+            ShipShapeError::Other(format!("unsupported archive format: {}", path.display()))
+        })?;
+        Self::from_reader(BufReader::new(File::open(path)?), format, path)
+    }
+
+    /// Load an archive from a reader, presenting its contents under `root`.
+    pub fn from_reader<R: Read + Seek>(
+        reader: R,
+        format: ArchiveFormat,
+        root: &Path,
+    ) -> Result<Self> {
+        let entries = match format {
+            ArchiveFormat::Tar => tar_entries(reader)?,
+            ArchiveFormat::TarGz => tar_entries(GzDecoder::new(reader))?,
+            ArchiveFormat::Zip => zip_entries(reader)?,
+        };
+        let prefix = common_top_dir(&entries);
+        let files = entries
+            .into_iter()
+            .map(|(path, contents)| {
+                let relative = prefix
+                    .as_ref()
+                    .and_then(|prefix| path.strip_prefix(prefix).ok())
+                    .unwrap_or(&path);
+                (root.join(relative), contents)
+            })
+            .collect();
+        Ok(Self {
+            root: root.to_path_buf(),
+            files,
+        })
+    }
+
+    /// Path the archive contents are presented under.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl FileSystem for ArchiveFileSystem {
+    fn list_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        self.files.list_files(root)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.files.read_to_string(path)
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        self.files.read(path)
+    }
+}
+
+fn tar_entries<R: Read>(reader: R) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let Some(path) = safe_relative(&entry.path()?) else {
+            continue;
+        };
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        entries.push((path, contents));
+    }
+    Ok(entries)
+}
+
+fn zip_entries<R: Read + Seek>(reader: R) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut archive = zip::ZipArchive::new(reader)
+        .map_err(|err| ShipShapeError::Other(format!("invalid zip archive: {err}")))?;
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|err| ShipShapeError::Other(format!("invalid zip entry: {err}")))?;
+        if !entry.is_file() || entry.is_symlink() {
+            continue;
+        }
+        let Some(path) = entry.enclosed_name().and_then(|path| safe_relative(&path)) else {
+            continue;
+        };
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        entries.push((path, contents));
+    }
+    Ok(entries)
+}
+
+/// Normalise an entry name, rejecting absolute paths and parent references.
+fn safe_relative(path: &Path) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+fn common_top_dir(entries: &[(PathBuf, Vec<u8>)]) -> Option<PathBuf> {
+    let mut top = None;
+    for (path, _) in entries {
+        let mut components = path.components();
+        let first = components.next()?;
+        components.next()?;
+        match &top {
+            None => top = Some(first),
+            Some(existing) if *existing == first => {}
+            Some(_) => return None,
+        }
+    }
+    top.map(|component| PathBuf::from(component.as_os_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArchiveFileSystem, ArchiveFormat};
+    use crate::fs::FileSystem;
+    use std::io::{Cursor, Write};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn reads_tarballs_and_strips_the_top_directory() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, contents) in [
+            ("demo-1.0/src/main.rs", "fn main() {}\n"),
+            ("demo-1.0/Cargo.toml", "[package]\nname = \"demo\"\n"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .expect("append");
+        }
+        let bytes = builder.into_inner().expect("tar").finish().expect("gzip");
+        let root = Path::new("/uploads/demo-1.0.tar.gz");
+        assert_eq!(ArchiveFormat::from_path(root), Some(ArchiveFormat::TarGz));
+
+        let fs = ArchiveFileSystem::from_reader(Cursor::new(bytes), ArchiveFormat::TarGz, root)
+            .expect("open tarball");
+
+        assert_eq!(
+            fs.list_files(root).expect("list"),
+            vec![root.join("Cargo.toml"), root.join("src/main.rs")]
+        );
+        assert_eq!(
+            fs.read_to_string(&root.join("src/main.rs")).expect("read"),
+            "fn main() {}\n"
+        );
+    }
+
+    #[test]
+    fn reads_zip_archives_and_skips_escaping_entries() {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (path, contents) in [
+            ("go.mod", "module demo\n"),
+            ("cmd/main.go", "package main\n"),
+            ("../escape.txt", "outside"),
+        ] {
+            writer.start_file(path, options).expect("start file");
+            writer.write_all(contents.as_bytes()).expect("write file");
+        }
+        writer.add_directory("docs/", options).expect("directory");
+        let bytes = writer.finish().expect("zip").into_inner();
+        let root = PathBuf::from("/uploads/demo.zip");
+
+        let fs = ArchiveFileSystem::from_reader(Cursor::new(bytes), ArchiveFormat::Zip, &root)
+            .expect("open zip");
+
+        assert_eq!(
+            fs.list_files(&root).expect("list"),
+            vec![root.join("cmd/main.go"), root.join("go.mod")]
+        );
+        assert!(fs.read(Path::new("/uploads/escape.txt")).is_err());
+        assert_eq!(ArchiveFormat::from_path(Path::new("notes.txt")), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Persistent content-hash cache for incremental audits.
+//!
+//! Entries are JSON files under a cache directory, keyed by a SHA-256 of
+//! everything that determines the result: the repository tree (paths and
+//! file content hashes), the tool id and version, and a hash of its
+//! configuration. Unchanged inputs map to the same key, so re-audits reuse
+//! stored line stats and mechanic results; any change produces a new key.
+//! Unreadable or corrupt entries are treated as misses.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use sha2::{Digest, Sha256};
+
+use crate::domain::Violation;
+use crate::error::{Result, ShipShapeError};
+use crate::fs::FileSystem;
+use crate::mechanic::Mechanic;
+
+/// Bump to invalidate every entry written by earlier releases.
+pub const CACHE_FORMAT: &str = "1";
+/// Namespace of cached language distributions.
+pub const LANGUAGE_STATS_NAMESPACE: &str = "language-stats";
+/// Namespace of cached mechanic audit results.
+pub const MECHANICS_NAMESPACE: &str = "mechanics";
+
+/// An on-disk cache of audit results.
+#[derive(Debug, Clone)]
+pub struct AuditCache {
+    dir: PathBuf,
+}
+
+impl AuditCache {
+    /// Use `dir` as the cache directory; it is created on first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$XDG_CACHE_HOME/shipshape`, falling back to `~/.cache/shipshape`.
+    pub fn default_dir() -> PathBuf {
+        std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+            .unwrap_or_else(std::env::temp_dir)
+            .join("shipshape")
+    }
+
+    /// The cache directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Look up an entry, returning `None` on a miss or an unreadable entry.
+    pub fn get<T: DeserializeOwned>(&self, namespace: &str, key: &str) -> Option<T> {
+        let contents = std::fs::read(self.entry_path(namespace, key)).ok()?;
+        serde_json::from_slice(&contents).ok()
+    }
+
+    /// Store an entry, replacing any previous value atomically.
+    pub fn put<T: Serialize>(&self, namespace: &str, key: &str, value: &T) -> Result<()> {
+        let path = self.entry_path(namespace, key);
+        let dir = path
+            .parent()
+            .ok_or_else(|| ShipShapeError::Other(format!("invalid cache key {key}")))?;
+        std::fs::create_dir_all(dir)?;
+        let contents = serde_json::to_vec(value)
+            .map_err(|err| ShipShapeError::Other(format!("cache entry {key}: {err}")))?;
+        let temp = dir.join(format!(".{key}.{}.tmp", std::process::id()));
+        std::fs::write(&temp, contents)?;
+        std::fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    fn entry_path(&self, namespace: &str, key: &str) -> PathBuf {
+        let shard = key.get(..2).unwrap_or(key);
+        self.dir
+            .join(namespace)
+            .join(shard)
+            .join(format!("{key}.json"))
+    }
+}
+
+/// Hex SHA-256 of file contents.
+pub fn content_hash(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+/// Combine key parts into a single cache key.
+pub fn cache_key(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex(&hasher.finalize())
+}
+
+/// Hash of every file under `root`: relative paths and content hashes.
+pub fn tree_hash<F: FileSystem>(fs: &F, root: &Path) -> Result<String> {
+    let mut files = fs.list_files(root)?;
+    files.sort();
+    let mut hasher = Sha256::new();
+    for path in files {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(content_hash(&fs.read(&path)?).as_bytes());
+        hasher.update([0]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Run a mechanic audit, reusing the stored result for an unchanged tree.
+///
+/// `tree` is the [`tree_hash`] of `root`. Failed audits are not cached.
+pub fn cached_mechanic_audit(
+    cache: &AuditCache,
+    mechanic: &dyn Mechanic,
+    root: &Path,
+    tree: &str,
+) -> Result<Vec<Violation>> {
+    let config = content_hash(mechanic.config().as_bytes());
+    let key = cache_key(&[
+        CACHE_FORMAT,
+        mechanic.id(),
+        mechanic.version(),
+        &config,
+        tree,
+    ]);
+    if let Some(violations) = cache.get(MECHANICS_NAMESPACE, &key) {
+        return Ok(violations);
+    }
+    let violations = mechanic.audit(root)?;
+    // A cache that cannot be written only costs the next run its speed-up.
+    let _ = cache.put(MECHANICS_NAMESPACE, &key, &violations);
+    Ok(violations)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+    use crate::fs::StdFileSystem;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingMechanic {
+        runs: AtomicUsize,
+        version: &'static str,
+    }
+
+    impl Mechanic for CountingMechanic {
+        fn id(&self) -> &str {
+            "counting"
+        }
+
+        fn version(&self) -> &str {
+            self.version
+        }
+
+        fn audit(&self, _path: &Path) -> Result<Vec<Violation>> {
+            let run = self.runs.fetch_add(1, Ordering::Relaxed);
+            Ok(vec![Violation {
+                id: "counting".to_string(),
+                message: format!("run {run}"),
+                severity: Severity::Info,
+                path: None,
+                line: None,
+            }])
+        }
+
+        fn dry_run(&self, _path: &Path) -> Result<String> {
+            Ok(String::new())
+        }
+
+        fn apply(&self, _path: &Path) -> Result<bool> {
+            Ok(false)
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        std::env::temp_dir().join(format!("shipshape_cache_{name}_{nanos}"))
+    }
+
+    #[test]
+    fn reuses_mechanic_results_until_inputs_change() {
+        let repo = temp_dir("repo");
+        std::fs::create_dir_all(&repo).expect("repo");
+        std::fs::write(repo.join("main.go"), "package main\n").expect("source");
+        let cache = AuditCache::new(temp_dir("store"));
+        let fs = StdFileSystem::new();
+        let mechanic = CountingMechanic {
+            runs: AtomicUsize::new(0),
+            version: "1.0",
+        };
+        let messages = |violations: Vec<Violation>| violations[0].message.clone();
+
+        let tree = tree_hash(&fs, &repo).expect("tree");
+        let first = cached_mechanic_audit(&cache, &mechanic, &repo, &tree).expect("audit");
+        let second = cached_mechanic_audit(&cache, &mechanic, &repo, &tree).expect("audit");
+        assert_eq!(
+            (messages(first), messages(second)),
+            ("run 0".into(), "run 0".into())
+        );
+
+        std::fs::write(repo.join("main.go"), "package main\n\nfunc main() {}\n").expect("edit");
+        let changed = tree_hash(&fs, &repo).expect("tree");
+        assert_ne!(changed, tree);
+        let third = cached_mechanic_audit(&cache, &mechanic, &repo, &changed).expect("audit");
+        assert_eq!(messages(third), "run 1");
+
+        let upgraded = CountingMechanic {
+            runs: AtomicUsize::new(5),
+            version: "2.0",
+        };
+        let fourth = cached_mechanic_audit(&cache, &upgraded, &repo, &changed).expect("audit");
+        assert_eq!(messages(fourth), "run 5");
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+        std::fs::remove_dir_all(cache.dir()).expect("cleanup cache");
+    }
+
+    #[test]
+    fn treats_corrupt_entries_as_misses() {
+        let cache = AuditCache::new(temp_dir("corrupt"));
+        let key = cache_key(&["a", "b"]);
+        assert_ne!(key, cache_key(&["ab"]));
+        cache.put("stats", &key, &vec![1, 2, 3]).expect("put");
+        assert_eq!(cache.get::<Vec<u32>>("stats", &key), Some(vec![1, 2, 3]));
+
+        std::fs::write(cache.entry_path("stats", &key), "{not json").expect("corrupt");
+        assert_eq!(cache.get::<Vec<u32>>("stats", &key), None);
+
+        std::fs::remove_dir_all(cache.dir()).expect("cleanup cache");
+    }
+}
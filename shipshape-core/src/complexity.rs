@@ -0,0 +1,1082 @@
+//! Per-function complexity metrics.
+//!
+//! A lightweight tokenizer strips comments and string literals, then a
+//! per-language pass finds function bodies and counts decision points, block
+//! nesting and length. The numbers approximate what a compiler-grade analyser
+//! would report; they are meant to surface hotspots, not to be exact.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+use crate::coverage::is_dependency_dir;
+use crate::domain::{ComplexityReport, FunctionComplexity, Severity, Violation};
+use crate::fs::FileSystem;
+use crate::health::HealthSignals;
+
+/// Number of functions listed as hotspots in a [`ComplexityReport`].
+pub const COMPLEXITY_HOTSPOTS: usize = 10;
+/// Signal name for [`ComplexityReport::max_cyclomatic`].
+pub const SIGNAL_MAX_CYCLOMATIC: &str = "max_cyclomatic";
+/// Signal name for [`ComplexityReport::average_cyclomatic`].
+pub const SIGNAL_AVERAGE_CYCLOMATIC: &str = "average_cyclomatic";
+
+/// Languages understood by the complexity analyser.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SourceLanguage {
+    /// Rust (`.rs`).
+    Rust,
+    /// Python (`.py`).
+    Python,
+    /// Go (`.go`).
+    Go,
+    /// C (`.c`, `.h`).
+    C,
+    /// C++ (`.cc`, `.cpp`, `.cxx`, `.hh`, `.hpp`, `.hxx`).
+    Cpp,
+    /// JavaScript (`.js`, `.jsx`, `.mjs`, `.cjs`).
+    JavaScript,
+    /// TypeScript (`.ts`, `.tsx`, `.mts`, `.cts`).
+    TypeScript,
+}
+
+impl SourceLanguage {
+    /// Detect the language from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "rs" => Some(Self::Rust),
+            "py" => Some(Self::Python),
+            "go" => Some(Self::Go),
+            "c" | "h" => Some(Self::C),
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Some(Self::Cpp),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "tsx" | "mts" | "cts" => Some(Self::TypeScript),
+            _ => None,
+        }
+    }
+
+    fn is_c_like(self) -> bool {
+        !matches!(self, Self::Python)
+    }
+
+    fn is_js(self) -> bool {
+        matches!(self, Self::JavaScript | Self::TypeScript)
+    }
+}
+
+/// Limits above which a function is reported as a violation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ComplexityThresholds {
+    /// Maximum cyclomatic complexity.
+    pub cyclomatic: u32,
+    /// Maximum block nesting depth.
+    pub nesting: u32,
+    /// Maximum function length in lines.
+    pub length: u32,
+}
+
+impl Default for ComplexityThresholds {
+    fn default() -> Self {
+        Self {
+            cyclomatic: 15,
+            nesting: 5,
+            length: 100,
+        }
+    }
+}
+
+/// Analyse every supported source file under `root`.
+///
+/// Returns the repository summary together with a violation for each function
+/// that exceeds `thresholds`. Dependency directories and files that are not
+/// valid UTF-8 are skipped.
+pub fn inspect_complexity<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    thresholds: &ComplexityThresholds,
+) -> Result<(ComplexityReport, Vec<Violation>)> {
+    let mut files = fs.list_files(root)?;
+    files.sort();
+    let mut files_analyzed = 0;
+    let mut functions = Vec::new();
+
+    for path in files {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if is_dependency_dir(relative) {
+            continue;
+        }
+        let Some(language) = SourceLanguage::from_path(&path) else {
+            continue;
+        };
+        let Ok(source) = fs.read_to_string(&path) else {
+            continue;
+        };
+        files_analyzed += 1;
+        let display = relative.to_string_lossy().replace('\\', "/");
+        for mut function in analyze_source(language, &source) {
+            function.path = display.clone();
+            functions.push(function);
+        }
+    }
+
+    let violations = complexity_violations(&functions, thresholds);
+    Ok((summarize_complexity(files_analyzed, functions), violations))
+}
+
+/// Measure every function in a single source file. Returned paths are empty.
+pub fn analyze_source(language: SourceLanguage, source: &str) -> Vec<FunctionComplexity> {
+    let tokens = tokenize(language, source);
+    match language {
+        SourceLanguage::Python => analyze_indented(&tokens, source),
+        _ => analyze_braced(language, &tokens),
+    }
+}
+
+/// Expose complexity metrics as health model signals.
+pub fn complexity_signals(report: &ComplexityReport) -> HealthSignals {
+    let mut signals = HealthSignals::new();
+    if report.functions_analyzed > 0 {
+        signals.insert(
+            SIGNAL_MAX_CYCLOMATIC.to_string(),
+            f64::from(report.max_cyclomatic),
+        );
+        signals.insert(
+            SIGNAL_AVERAGE_CYCLOMATIC.to_string(),
+            report.average_cyclomatic,
+        );
+    }
+    signals
+}
+
+fn summarize_complexity(
+    files_analyzed: usize,
+    mut functions: Vec<FunctionComplexity>,
+) -> ComplexityReport {
+    let total: u64 = functions
+        .iter()
+        .map(|function| u64::from(function.cyclomatic))
+        .sum();
+    let average_cyclomatic = if functions.is_empty() {
+        0.0
+    } else {
+        total as f64 / functions.len() as f64
+    };
+    let functions_analyzed = functions.len();
+    functions.sort_by(|a, b| {
+        b.cyclomatic
+            .cmp(&a.cyclomatic)
+            .then_with(|| b.max_nesting.cmp(&a.max_nesting))
+            .then_with(|| b.length.cmp(&a.length))
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    let max_cyclomatic = functions.first().map_or(0, |function| function.cyclomatic);
+    functions.truncate(COMPLEXITY_HOTSPOTS);
+
+    ComplexityReport {
+        files_analyzed,
+        functions_analyzed,
+        average_cyclomatic,
+        max_cyclomatic,
+        hotspots: functions,
+    }
+}
+
+fn complexity_violations(
+    functions: &[FunctionComplexity],
+    thresholds: &ComplexityThresholds,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for function in functions {
+        let mut push = |id: &str, message: String| {
+            violations.push(Violation {
+                id: id.to_string(),
+                message,
+                severity: Severity::Warning,
+                path: Some(function.path.clone()),
+                line: Some(function.line),
+            });
+        };
+        if function.cyclomatic > thresholds.cyclomatic {
+            push(
+                "complexity-cyclomatic",
+                format!(
+                    "`{}` has cyclomatic complexity {} (max {})",
+                    function.name, function.cyclomatic, thresholds.cyclomatic
+                ),
+            );
+        }
+        if function.max_nesting > thresholds.nesting {
+            push(
+                "complexity-nesting",
+                format!(
+                    "`{}` nests blocks {} deep (max {})",
+                    function.name, function.max_nesting, thresholds.nesting
+                ),
+            );
+        }
+        if function.length > thresholds.length {
+            push(
+                "complexity-length",
+                format!(
+                    "`{}` is {} lines long (max {})",
+                    function.name, function.length, thresholds.length
+                ),
+            );
+        }
+    }
+    violations
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token<'a> {
+    text: &'a str,
+    line: u32,
+}
+
+const TWO_CHAR_PUNCTUATION: &[&str] = &[
+    "&&", "||", "=>", "->", "::", "??", "?.", "==", "!=", "<=", ">=",
+];
+
+fn tokenize(language: SourceLanguage, source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut line = 1u32;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let next = bytes.get(index + 1).copied();
+        if byte == b'\n' {
+            line += 1;
+            index += 1;
+        } else if byte.is_ascii_whitespace() {
+            index += 1;
+        } else if (byte == b'#' && language == SourceLanguage::Python)
+            || (byte == b'/' && next == Some(b'/') && language.is_c_like())
+        {
+            while index < bytes.len() && bytes[index] != b'\n' {
+                index += 1;
+            }
+        } else if byte == b'/' && next == Some(b'*') && language.is_c_like() {
+            index = skip_until(bytes, index + 2, b"*/", &mut line);
+        } else if byte == b'"'
+            || (byte == b'\'' && language != SourceLanguage::Rust)
+            || (byte == b'`' && (language == SourceLanguage::Go || language.is_js()))
+        {
+            // Literals are kept as single opaque tokens so keywords inside
+            // them are ignored while they still count as operands.
+            let start_line = line;
+            let end = skip_string(language, bytes, index, &mut line);
+            tokens.push(Token {
+                text: &source[index..end],
+                line: start_line,
+            });
+            index = end;
+        } else if byte == b'\'' {
+            // Rust: a char literal closes within one (possibly escaped) char,
+            // otherwise this is a lifetime and the name becomes a token.
+            let start_line = line;
+            let end = skip_rust_quote(source, index, &mut line);
+            if end > index + 1 {
+                tokens.push(Token {
+                    text: &source[index..end],
+                    line: start_line,
+                });
+            }
+            index = end;
+        } else if is_identifier_byte(byte, language) && !byte.is_ascii_digit() {
+            let start = index;
+            let start_line = line;
+            while index < bytes.len() && is_identifier_byte(bytes[index], language) {
+                index += 1;
+            }
+            let word = &source[start..index];
+            if let Some(end) = string_after_prefix(language, bytes, word, index, &mut line) {
+                index = end;
+            }
+            tokens.push(Token {
+                text: &source[start..index],
+                line: start_line,
+            });
+        } else if byte.is_ascii_digit() {
+            let start = index;
+            while index < bytes.len()
+                && (bytes[index].is_ascii_alphanumeric() || matches!(bytes[index], b'_' | b'.'))
+            {
+                index += 1;
+            }
+            tokens.push(Token {
+                text: &source[start..index],
+                line,
+            });
+        } else {
+            let width = match source.get(index..index + 2) {
+                Some(pair) if TWO_CHAR_PUNCTUATION.contains(&pair) => 2,
+                _ => 1,
+            };
+            tokens.push(Token {
+                text: &source[index..index + width],
+                line,
+            });
+            index += width;
+        }
+    }
+
+    tokens
+}
+
+fn is_identifier_byte(byte: u8, language: SourceLanguage) -> bool {
+    byte.is_ascii_alphanumeric()
+        || byte == b'_'
+        || byte >= 0x80
+        || (byte == b'$' && language.is_js())
+}
+
+fn skip_until(bytes: &[u8], mut index: usize, terminator: &[u8], line: &mut u32) -> usize {
+    while index < bytes.len() {
+        if bytes[index..].starts_with(terminator) {
+            return index + terminator.len();
+        }
+        if bytes[index] == b'\n' {
+            *line += 1;
+        }
+        index += 1;
+    }
+    index
+}
+
+fn skip_string(language: SourceLanguage, bytes: &[u8], start: usize, line: &mut u32) -> usize {
+    let quote = bytes[start];
+    if language == SourceLanguage::Python && bytes[start..].starts_with(&[quote, quote, quote]) {
+        let terminator = [quote, quote, quote];
+        return skip_until(bytes, start + 3, &terminator, line);
+    }
+    let raw = quote == b'`' && language == SourceLanguage::Go;
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' if !raw => {
+                if bytes.get(index + 1) == Some(&b'\n') {
+                    *line += 1;
+                }
+                index += 2;
+                continue;
+            }
+            b'\n' => *line += 1,
+            byte if byte == quote => return index + 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    index
+}
+
+fn skip_rust_quote(source: &str, start: usize, line: &mut u32) -> usize {
+    let bytes = source.as_bytes();
+    if bytes.get(start + 1) == Some(&b'\\') {
+        return skip_string(SourceLanguage::C, bytes, start, line);
+    }
+    if let Some(ch) = source[start + 1..].chars().next() {
+        let end = start + 1 + ch.len_utf8();
+        if bytes.get(end) == Some(&b'\'') {
+            return end + 1;
+        }
+    }
+    start + 1
+}
+
+/// Handle string prefixes such as Rust raw strings (`r#"..."#`) and Python
+/// `r"..."`/`b'...'`/`f"..."`, returning the index after the literal.
+fn string_after_prefix(
+    language: SourceLanguage,
+    bytes: &[u8],
+    word: &str,
+    index: usize,
+    line: &mut u32,
+) -> Option<usize> {
+    let next = *bytes.get(index)?;
+    match language {
+        SourceLanguage::Rust if matches!(word, "r" | "br") && matches!(next, b'"' | b'#') => {
+            let hashes = bytes[index..]
+                .iter()
+                .take_while(|byte| **byte == b'#')
+                .count();
+            if bytes.get(index + hashes) != Some(&b'"') {
+                return None;
+            }
+            let mut terminator = vec![b'"'];
+            terminator.extend(std::iter::repeat_n(b'#', hashes));
+            Some(skip_until(bytes, index + hashes + 1, &terminator, line))
+        }
+        SourceLanguage::Python
+            if matches!(next, b'"' | b'\'')
+                && word.len() <= 2
+                && word
+                    .chars()
+                    .all(|ch| matches!(ch.to_ascii_lowercase(), 'r' | 'b' | 'u' | 'f')) =>
+        {
+            Some(skip_string(language, bytes, index, line))
+        }
+        _ => None,
+    }
+}
+
+fn new_function(name: String, line: u32) -> FunctionComplexity {
+    FunctionComplexity {
+        path: String::new(),
+        name,
+        line,
+        cyclomatic: 1,
+        max_nesting: 0,
+        length: 1,
+    }
+}
+
+fn analyze_braced(language: SourceLanguage, tokens: &[Token<'_>]) -> Vec<FunctionComplexity> {
+    let mut functions: Vec<FunctionComplexity> = Vec::new();
+    // One frame per open brace: `Some(index)` when it opens a function body.
+    let mut frames: Vec<Option<usize>> = Vec::new();
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token.text {
+            "{" => {
+                if let Some((name, line)) = function_header(language, tokens, index) {
+                    functions.push(new_function(name, line));
+                    frames.push(Some(functions.len() - 1));
+                } else {
+                    frames.push(None);
+                    if let Some((owner, depth)) = innermost_function(&frames) {
+                        let function = &mut functions[owner];
+                        function.max_nesting = function.max_nesting.max(depth);
+                    }
+                }
+            }
+            "}" => {
+                if let Some(Some(owner)) = frames.pop() {
+                    let function = &mut functions[owner];
+                    function.length = token.line - function.line + 1;
+                }
+            }
+            _ => {
+                if is_decision(language, tokens, index)
+                    && let Some((owner, _)) = innermost_function(&frames)
+                {
+                    functions[owner].cyclomatic += 1;
+                }
+            }
+        }
+    }
+
+    // Unbalanced input: close whatever is still open at the last token.
+    if let Some(last) = tokens.last() {
+        for owner in frames.into_iter().flatten() {
+            let function = &mut functions[owner];
+            function.length = last.line - function.line + 1;
+        }
+    }
+    functions
+}
+
+fn innermost_function(frames: &[Option<usize>]) -> Option<(usize, u32)> {
+    let mut depth = 0;
+    for frame in frames.iter().rev() {
+        match frame {
+            Some(owner) => return Some((*owner, depth)),
+            None => depth += 1,
+        }
+    }
+    None
+}
+
+/// Decide whether the `{` at `brace` opens a function body, returning the
+/// function name and the line it is declared on.
+fn function_header(
+    language: SourceLanguage,
+    tokens: &[Token<'_>],
+    brace: usize,
+) -> Option<(String, u32)> {
+    let start = tokens[..brace]
+        .iter()
+        .rposition(|token| matches!(token.text, ";" | "{" | "}"))
+        .map_or(0, |position| position + 1);
+    let header = &tokens[start..brace];
+    match language {
+        SourceLanguage::Rust => keyword_header(header, "fn"),
+        SourceLanguage::Go => go_header(header),
+        SourceLanguage::JavaScript | SourceLanguage::TypeScript => js_header(header),
+        SourceLanguage::C | SourceLanguage::Cpp => c_header(header),
+        SourceLanguage::Python => None,
+    }
+}
+
+fn keyword_header(header: &[Token<'_>], keyword: &str) -> Option<(String, u32)> {
+    let position = header.iter().position(|token| token.text == keyword)?;
+    let name = header
+        .get(position + 1)
+        .filter(|token| is_identifier(token.text))?;
+    Some((name.text.to_string(), header[position].line))
+}
+
+fn go_header(header: &[Token<'_>]) -> Option<(String, u32)> {
+    let position = header.iter().position(|token| token.text == "func")?;
+    let mut name_index = position + 1;
+    if header.get(name_index)?.text == "(" {
+        // Method receiver: `func (s *Server) Name(...)`.
+        name_index = matching_close(header, name_index)? + 1;
+    }
+    let name = header
+        .get(name_index)
+        .filter(|token| is_identifier(token.text))?;
+    if header.get(name_index + 1)?.text != "(" {
+        return None;
+    }
+    Some((name.text.to_string(), header[position].line))
+}
+
+const JS_MODIFIERS: &[&str] = &[
+    "async",
+    "static",
+    "get",
+    "set",
+    "public",
+    "private",
+    "protected",
+    "readonly",
+    "override",
+    "abstract",
+    "export",
+    "default",
+    "*",
+];
+
+fn js_header(header: &[Token<'_>]) -> Option<(String, u32)> {
+    if let Some(position) = header.iter().position(|token| token.text == "function") {
+        let name = header[position + 1..]
+            .iter()
+            .find(|token| token.text != "*")
+            .filter(|token| is_identifier(token.text));
+        if let Some(name) = name {
+            return Some((name.text.to_string(), header[position].line));
+        }
+        return assigned_name(header);
+    }
+    if header.last()?.text == "=>" {
+        return assigned_name(header);
+    }
+
+    // Class and object-literal methods: `name(args) {` or `name(args): T {`.
+    let close = header.iter().rposition(|token| token.text == ")")?;
+    if close + 1 < header.len() && header[close + 1].text != ":" {
+        return None;
+    }
+    let open = matching_open(header, close)?;
+    let name_index = open.checked_sub(1)?;
+    let name = header[name_index];
+    if !is_identifier(name.text) || is_control_keyword(name.text) {
+        return None;
+    }
+    if !header[..name_index]
+        .iter()
+        .all(|token| JS_MODIFIERS.contains(&token.text))
+    {
+        return None;
+    }
+    Some((name.text.to_string(), name.line))
+}
+
+/// Name of a function expression or arrow function bound with `name =` or `name:`.
+fn assigned_name(header: &[Token<'_>]) -> Option<(String, u32)> {
+    let position = header
+        .iter()
+        .position(|token| matches!(token.text, "=" | ":"))?;
+    let name = header[..position]
+        .last()
+        .filter(|token| is_identifier(token.text))?;
+    Some((name.text.to_string(), name.line))
+}
+
+const C_QUALIFIERS: &[&str] = &[
+    "const", "noexcept", "override", "final", "volatile", "&", "&&",
+];
+
+fn c_header(header: &[Token<'_>]) -> Option<(String, u32)> {
+    let close = header.iter().rposition(|token| token.text == ")")?;
+    if !header[close + 1..]
+        .iter()
+        .all(|token| C_QUALIFIERS.contains(&token.text))
+    {
+        return None;
+    }
+    let open = matching_open(header, close)?;
+    let name_index = open.checked_sub(1)?;
+    let name = header[name_index];
+    if !is_identifier(name.text) || is_control_keyword(name.text) {
+        return None;
+    }
+    if header[..name_index].iter().any(|token| token.text == "=") {
+        return None;
+    }
+
+    // Qualified names such as `Widget::draw` or `Widget::~Widget`.
+    let mut full_name = name.text.to_string();
+    let mut first = name_index;
+    if first >= 1 && header[first - 1].text == "~" {
+        full_name.insert(0, '~');
+        first -= 1;
+    }
+    while first >= 2 && header[first - 1].text == "::" && is_identifier(header[first - 2].text) {
+        full_name = format!("{}::{full_name}", header[first - 2].text);
+        first -= 2;
+    }
+    Some((full_name, name.line))
+}
+
+fn matching_open(tokens: &[Token<'_>], close: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for index in (0..=close).rev() {
+        match tokens[index].text {
+            ")" => depth += 1,
+            "(" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn matching_close(tokens: &[Token<'_>], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token.text {
+            "(" => depth += 1,
+            ")" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_identifier(text: &str) -> bool {
+    text.bytes().next().is_some_and(|byte| {
+        byte.is_ascii_alphabetic() || byte == b'_' || byte == b'$' || byte >= 0x80
+    })
+}
+
+fn is_control_keyword(text: &str) -> bool {
+    matches!(
+        text,
+        "if" | "for"
+            | "while"
+            | "switch"
+            | "catch"
+            | "return"
+            | "sizeof"
+            | "do"
+            | "else"
+            | "with"
+            | "function"
+            | "defined"
+    )
+}
+
+fn is_decision(language: SourceLanguage, tokens: &[Token<'_>], index: usize) -> bool {
+    match tokens[index].text {
+        "if" | "for" | "while" => true,
+        "case" => language != SourceLanguage::Rust,
+        "&&" | "||" => language != SourceLanguage::Python && follows_operand(tokens, index),
+        "=>" => language == SourceLanguage::Rust,
+        "catch" => matches!(
+            language,
+            SourceLanguage::Cpp | SourceLanguage::JavaScript | SourceLanguage::TypeScript
+        ),
+        "??" => language.is_js(),
+        "?" => {
+            matches!(
+                language,
+                SourceLanguage::C
+                    | SourceLanguage::Cpp
+                    | SourceLanguage::JavaScript
+                    | SourceLanguage::TypeScript
+            ) && !matches!(
+                tokens.get(index + 1).map(|token| token.text),
+                Some(":" | ")" | "," | ";" | "=") | None
+            )
+        }
+        "elif" | "except" | "and" | "or" => language == SourceLanguage::Python,
+        _ => false,
+    }
+}
+
+/// `&&`/`||` count as boolean operators only after an operand; this skips
+/// Rust closures (`|| expr`) and reference patterns (`&&x`).
+fn follows_operand(tokens: &[Token<'_>], index: usize) -> bool {
+    let Some(previous) = index.checked_sub(1).map(|previous| tokens[previous].text) else {
+        return false;
+    };
+    if matches!(previous, ")" | "]" | "?") {
+        return true;
+    }
+    let starts_operand = previous.bytes().next().is_some_and(|byte| {
+        byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'"' | b'\'' | b'`') || byte >= 0x80
+    });
+    starts_operand && !matches!(previous, "move" | "return" | "in" | "else")
+}
+
+const PYTHON_BLOCKS: &[&str] = &[
+    "if", "elif", "else", "for", "while", "try", "except", "finally", "with", "match", "case",
+];
+
+enum PythonBlock {
+    Function(usize),
+    Control,
+    Other,
+}
+
+fn analyze_indented(tokens: &[Token<'_>], source: &str) -> Vec<FunctionComplexity> {
+    let indents: Vec<usize> = source
+        .lines()
+        .map(|line| line.len() - line.trim_start().len())
+        .collect();
+    let mut functions: Vec<FunctionComplexity> = Vec::new();
+    let mut stack: Vec<(usize, PythonBlock)> = Vec::new();
+
+    for logical in python_logical_lines(tokens) {
+        let first = logical[0];
+        let last_line = logical[logical.len() - 1].line;
+        let indent = indents.get(first.line as usize - 1).copied().unwrap_or(0);
+        while stack.last().is_some_and(|(open, _)| *open >= indent) {
+            stack.pop();
+        }
+
+        let keyword_index = usize::from(first.text == "async");
+        let keyword = logical.get(keyword_index).map_or("", |token| token.text);
+        if keyword == "def" {
+            let name = logical
+                .get(keyword_index + 1)
+                .map_or("<anonymous>", |token| token.text);
+            functions.push(new_function(name.to_string(), first.line));
+            stack.push((indent, PythonBlock::Function(functions.len() - 1)));
+        } else if PYTHON_BLOCKS.contains(&keyword) {
+            stack.push((indent, PythonBlock::Control));
+        } else if keyword == "class" {
+            stack.push((indent, PythonBlock::Other));
+        }
+
+        let mut depth = 0;
+        for (_, block) in stack.iter().rev() {
+            match block {
+                PythonBlock::Function(owner) => {
+                    let function = &mut functions[*owner];
+                    function.max_nesting = function.max_nesting.max(depth);
+                    for index in 0..logical.len() {
+                        if is_decision(SourceLanguage::Python, logical, index) {
+                            function.cyclomatic += 1;
+                        }
+                    }
+                    break;
+                }
+                PythonBlock::Control => depth += 1,
+                PythonBlock::Other => {}
+            }
+        }
+        for (_, block) in &stack {
+            if let PythonBlock::Function(owner) = block {
+                let function = &mut functions[*owner];
+                function.length = last_line - function.line + 1;
+            }
+        }
+    }
+
+    functions
+}
+
+/// Group tokens into logical lines, joining bracketed and `\` continuations.
+fn python_logical_lines<'t, 'a>(tokens: &'t [Token<'a>]) -> Vec<&'t [Token<'a>]> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut depth = 0i32;
+    for index in 0..tokens.len() {
+        if index > start {
+            let previous = tokens[index - 1];
+            if depth <= 0 && tokens[index].line > previous.line && previous.text != "\\" {
+                lines.push(&tokens[start..index]);
+                start = index;
+                depth = 0;
+            }
+        }
+        match tokens[index].text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth -= 1,
+            _ => {}
+        }
+    }
+    if start < tokens.len() {
+        lines.push(&tokens[start..]);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MockFileSystem;
+    use std::path::PathBuf;
+
+    fn find<'a>(functions: &'a [FunctionComplexity], name: &str) -> &'a FunctionComplexity {
+        functions
+            .iter()
+            .find(|function| function.name == name)
+            .unwrap_or_else(|| panic!("missing function {name}"))
+    }
+
+    #[test]
+    fn detects_languages_from_extensions() {
+        assert_eq!(
+            SourceLanguage::from_path(Path::new("src/lib.rs")),
+            Some(SourceLanguage::Rust)
+        );
+        assert_eq!(
+            SourceLanguage::from_path(Path::new("web/app.TSX")),
+            Some(SourceLanguage::TypeScript)
+        );
+        assert_eq!(
+            SourceLanguage::from_path(Path::new("include/widget.hpp")),
+            Some(SourceLanguage::Cpp)
+        );
+        assert_eq!(SourceLanguage::from_path(Path::new("README.md")), None);
+    }
+
+    #[test]
+    fn measures_rust_functions() {
+        let source = r##"
+/// Docs mentioning if and while.
+pub fn classify(value: i32) -> &'static str {
+    let label = r#"if { while"#;
+    if value > 0 && value < 10 {
+        for _ in 0..value {
+            if value % 2 == 0 {
+                return "even";
+            }
+        }
+    }
+    match value {
+        0 => "zero",
+        _ => label,
+    }
+}
+
+fn simple() {
+    let add = |a: i32, b: i32| a + b;
+    let _ = add(1, 2);
+}
+"##;
+        let functions = analyze_source(SourceLanguage::Rust, source);
+
+        assert_eq!(functions.len(), 2);
+        let classify = find(&functions, "classify");
+        assert_eq!(classify.line, 3);
+        // 1 + if + && + for + if + two match arms.
+        assert_eq!(classify.cyclomatic, 7);
+        assert_eq!(classify.max_nesting, 3);
+        assert_eq!(classify.length, 14);
+        let simple = find(&functions, "simple");
+        assert_eq!(simple.cyclomatic, 1);
+        assert_eq!(simple.max_nesting, 0);
+        assert_eq!(simple.length, 4);
+    }
+
+    #[test]
+    fn measures_python_functions() {
+        let source = r##"
+class Parser:
+    def parse(self, text):
+        """Docstring with if and for."""
+        for line in text.splitlines():
+            if line.startswith("#") or not line:
+                continue
+            elif line == "end":
+                break
+        return [
+            item for item in text
+            if item
+        ]
+
+    async def helper(self):
+        return 1
+"##;
+        let functions = analyze_source(SourceLanguage::Python, source);
+
+        assert_eq!(functions.len(), 2);
+        let parse = find(&functions, "parse");
+        assert_eq!(parse.line, 3);
+        // 1 + for + if + or + elif + comprehension for + comprehension if.
+        assert_eq!(parse.cyclomatic, 7);
+        assert_eq!(parse.max_nesting, 2);
+        assert_eq!(parse.length, 11);
+        assert_eq!(find(&functions, "helper").cyclomatic, 1);
+    }
+
+    #[test]
+    fn measures_go_functions_and_methods() {
+        let source = r#"
+package demo
+
+func (s *Server) Handle(req Request) error {
+	if req.Path == "" || req.Method == "" {
+		return errBad
+	}
+	switch req.Method {
+	case "GET":
+		go func() {
+			if s.ready {
+				s.log("`quoted`")
+			}
+		}()
+	case "POST":
+		return nil
+	}
+	return nil
+}
+
+func helper() int { return 1 }
+"#;
+        let functions = analyze_source(SourceLanguage::Go, source);
+
+        assert_eq!(functions.len(), 2);
+        let handle = find(&functions, "Handle");
+        assert_eq!(handle.line, 4);
+        // 1 + if + || + case + if + case.
+        assert_eq!(handle.cyclomatic, 6);
+        assert_eq!(handle.max_nesting, 3);
+        assert_eq!(handle.length, 16);
+        assert_eq!(find(&functions, "helper").length, 1);
+    }
+
+    #[test]
+    fn measures_c_and_cpp_functions() {
+        let source = r#"
+#include <stdio.h>
+
+static int clamp(int value, int low, int high) {
+    /* if this were code */
+    return value < low ? low : (value > high ? high : value);
+}
+
+int Widget::draw(const Canvas &canvas) const {
+    for (int i = 0; i < 3; i++) {
+        while (busy()) {}
+    }
+    try { paint(canvas); } catch (const Error &err) { return -1; }
+    return 0;
+}
+
+struct point origin = { 0, 0 };
+"#;
+        let c = analyze_source(SourceLanguage::C, source);
+        let clamp = find(&c, "clamp");
+        assert_eq!(clamp.cyclomatic, 3);
+        assert_eq!(clamp.length, 4);
+
+        let cpp = analyze_source(SourceLanguage::Cpp, source);
+        assert_eq!(cpp.len(), 2);
+        let draw = find(&cpp, "Widget::draw");
+        // 1 + for + while + catch.
+        assert_eq!(draw.cyclomatic, 4);
+        assert_eq!(draw.max_nesting, 2);
+    }
+
+    #[test]
+    fn measures_javascript_and_typescript_functions() {
+        let source = r#"
+export async function load(url) {
+  const response = await fetch(`${url}?q=if`);
+  return response.ok ? response.json() : null;
+}
+
+const pick = (items, key) => {
+  return items.filter((item) => item[key] ?? false);
+};
+
+class Store {
+  constructor(state) {
+    this.state = state || {};
+  }
+
+  update(key: string, value?: number): void {
+    if (key && value) {
+      this.state[key] = value;
+    }
+  }
+}
+"#;
+        let functions = analyze_source(SourceLanguage::TypeScript, source);
+
+        assert_eq!(functions.len(), 4);
+        assert_eq!(find(&functions, "load").cyclomatic, 2);
+        assert_eq!(find(&functions, "pick").cyclomatic, 2);
+        assert_eq!(find(&functions, "constructor").cyclomatic, 2);
+        let update = find(&functions, "update");
+        assert_eq!(update.cyclomatic, 3);
+        assert_eq!(update.max_nesting, 1);
+        assert_eq!(update.line, 16);
+    }
+
+    #[test]
+    fn reports_hotspots_and_threshold_violations() {
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(|_| {
+            Ok(vec![
+                PathBuf::from("/repo/src/lib.rs"),
+                PathBuf::from("/repo/node_modules/pkg/index.js"),
+                PathBuf::from("/repo/README.md"),
+            ])
+        });
+        fs.expect_read_to_string()
+            .withf(|path| path == Path::new("/repo/src/lib.rs"))
+            .returning(|_| {
+                Ok("fn busy(a: bool, b: bool) {\n    if a && b {\n        if a {}\n    }\n}\n\nfn idle() {}\n".to_string())
+            });
+        let thresholds = ComplexityThresholds {
+            cyclomatic: 3,
+            nesting: 1,
+            length: 4,
+        };
+
+        let (report, violations) =
+            inspect_complexity(&fs, Path::new("/repo"), &thresholds).expect("complexity");
+
+        assert_eq!(report.files_analyzed, 1);
+        assert_eq!(report.functions_analyzed, 2);
+        assert_eq!(report.max_cyclomatic, 4);
+        assert!((report.average_cyclomatic - 2.5).abs() < f64::EPSILON);
+        assert_eq!(report.hotspots[0].name, "busy");
+        assert_eq!(report.hotspots[0].path, "src/lib.rs");
+
+        let ids: Vec<&str> = violations.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "complexity-cyclomatic",
+                "complexity-nesting",
+                "complexity-length"
+            ]
+        );
+        assert_eq!(violations[0].location().as_deref(), Some("src/lib.rs:1"));
+
+        let signals = complexity_signals(&report);
+        assert_eq!(signals[SIGNAL_MAX_CYCLOMATIC], 4.0);
+    }
+}
@@ -0,0 +1,702 @@
+//! Parsers for measured coverage artifacts.
+//!
+//! Supports lcov, Cobertura XML, Go `coverage.out`, JaCoCo XML, and llvm-cov
+//! JSON exports. Parsed results replace the file-count heuristics in
+//! [`CoverageReport`] whenever an artifact is available.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{CoverageReport, CoverageSource};
+use crate::error::{Result, ShipShapeError};
+use crate::fs::FileSystem;
+
+/// Supported coverage artifact formats.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageFormat {
+    /// `lcov` tracefiles (`lcov.info`).
+    Lcov,
+    /// Cobertura XML reports (`coverage.xml`).
+    Cobertura,
+    /// Go cover profiles (`coverage.out`).
+    GoCover,
+    /// JaCoCo XML reports (`jacoco.xml`).
+    Jacoco,
+    /// `llvm-cov export` JSON summaries.
+    LlvmCovJson,
+}
+
+/// Line and branch counters parsed from one or more coverage artifacts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeasuredCoverage {
+    /// Number of instrumented lines that were executed.
+    pub lines_covered: u64,
+    /// Number of instrumented lines.
+    pub lines_total: u64,
+    /// Number of branches that were taken.
+    pub branches_covered: u64,
+    /// Number of instrumented branches.
+    pub branches_total: u64,
+}
+
+impl MeasuredCoverage {
+    /// Line coverage ratio, or `None` if no lines were instrumented.
+    pub fn line_ratio(&self) -> Option<f64> {
+        ratio(self.lines_covered, self.lines_total)
+    }
+
+    /// Branch coverage ratio, or `None` if no branches were instrumented.
+    pub fn branch_ratio(&self) -> Option<f64> {
+        ratio(self.branches_covered, self.branches_total)
+    }
+
+    /// Add the counters from another measurement.
+    pub fn merge(&mut self, other: &MeasuredCoverage) {
+        self.lines_covered += other.lines_covered;
+        self.lines_total += other.lines_total;
+        self.branches_covered += other.branches_covered;
+        self.branches_total += other.branches_total;
+    }
+}
+
+/// Detect the artifact format from its file name and contents.
+pub fn detect_format(path: &Path, contents: &str) -> Option<CoverageFormat> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_lowercase())
+        .unwrap_or_default();
+    let head = contents.trim_start();
+
+    if file_name.ends_with(".json") {
+        return contents
+            .contains("llvm.coverage.json.export")
+            .then_some(CoverageFormat::LlvmCovJson);
+    }
+    if head.starts_with("mode:") {
+        return Some(CoverageFormat::GoCover);
+    }
+    if file_name.ends_with(".xml") || head.starts_with("<?xml") {
+        if contents.contains("<report") && contents.contains("<counter") {
+            return Some(CoverageFormat::Jacoco);
+        }
+        if contents.contains("<coverage") {
+            return Some(CoverageFormat::Cobertura);
+        }
+        return None;
+    }
+    if file_name.ends_with(".info")
+        || file_name.ends_with(".lcov")
+        || head.starts_with("TN:")
+        || head.starts_with("SF:")
+    {
+        return Some(CoverageFormat::Lcov);
+    }
+    None
+}
+
+/// Parse a coverage artifact in the given format.
+pub fn parse_coverage(format: CoverageFormat, contents: &str) -> Result<MeasuredCoverage> {
+    match format {
+        CoverageFormat::Lcov => parse_lcov(contents),
+        CoverageFormat::Cobertura => parse_cobertura(contents),
+        CoverageFormat::GoCover => parse_go_cover(contents),
+        CoverageFormat::Jacoco => parse_jacoco(contents),
+        CoverageFormat::LlvmCovJson => parse_llvm_cov_json(contents),
+    }
+}
+
+/// Locate well-known coverage artifacts inside a repository.
+pub fn find_coverage_artifacts<F: FileSystem>(fs: &F, root: &Path) -> Result<Vec<PathBuf>> {
+    let mut artifacts: Vec<PathBuf> = fs
+        .list_files(root)?
+        .into_iter()
+        .filter(|path| is_coverage_artifact(path))
+        .collect();
+    artifacts.sort();
+    Ok(artifacts)
+}
+
+/// Parse the given artifacts and merge their counters.
+///
+/// Files whose format cannot be detected are skipped. Returns the merged
+/// measurement together with the artifacts that contributed to it, or `None`
+/// when no artifact could be parsed.
+pub fn load_measured_coverage<F: FileSystem>(
+    fs: &F,
+    artifacts: &[PathBuf],
+) -> Result<Option<(MeasuredCoverage, Vec<PathBuf>)>> {
+    let mut merged = MeasuredCoverage::default();
+    let mut used = Vec::new();
+    let mut seen = BTreeSet::new();
+
+    for path in artifacts {
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        let contents = fs.read_to_string(path)?;
+        let Some(format) = detect_format(path, &contents) else {
+            continue;
+        };
+        let measured = parse_coverage(format, &contents).map_err(|err| {
+            ShipShapeError::Other(format!("coverage artifact {}: {err}", path.display()))
+        })?;
+        merged.merge(&measured);
+        used.push(path.clone());
+    }
+
+    if used.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((merged, used)))
+}
+
+/// Overlay measured coverage on a heuristic coverage report.
+pub fn apply_measured_coverage(
+    report: &mut CoverageReport,
+    measured: &MeasuredCoverage,
+    artifacts: &[PathBuf],
+) {
+    let Some(line_coverage) = measured.line_ratio() else {
+        return;
+    };
+    report.source = CoverageSource::Measured;
+    report.line_coverage = Some(line_coverage);
+    report.branch_coverage = measured.branch_ratio();
+    report.low_test_coverage = line_coverage < MEASURED_LOW_LINE_COVERAGE;
+    report.artifacts = artifacts
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+}
+
+/// Line coverage ratio below which measured test coverage is flagged as low.
+pub const MEASURED_LOW_LINE_COVERAGE: f64 = 0.60;
+
+fn ratio(covered: u64, total: u64) -> Option<f64> {
+    if total == 0 {
+        return None;
+    }
+    Some((covered as f64 / total as f64).min(1.0))
+}
+
+pub(crate) fn is_coverage_artifact(path: &Path) -> bool {
+    is_coverage_artifact_name(path) && !is_dependency_dir(path)
+}
+
+fn is_coverage_artifact_name(path: &Path) -> bool {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_lowercase())
+        .unwrap_or_default();
+    matches!(
+        file_name.as_str(),
+        "lcov.info"
+            | "coverage.info"
+            | "coverage.xml"
+            | "cobertura.xml"
+            | "cobertura-coverage.xml"
+            | "jacoco.xml"
+            | "jacocotestreport.xml"
+            | "coverage.out"
+            | "cover.out"
+            | "coverage.json"
+    ) || file_name.ends_with(".lcov")
+        || file_name.ends_with(".coverprofile")
+}
+
+pub(crate) fn is_dependency_dir(path: &Path) -> bool {
+    path.components().any(|component| {
+        let segment = component.as_os_str().to_string_lossy();
+        segment == "node_modules" || segment == "vendor"
+    })
+}
+
+fn parse_lcov(contents: &str) -> Result<MeasuredCoverage> {
+    let mut total = MeasuredCoverage::default();
+    let mut record = LcovRecord::default();
+    let mut saw_record = false;
+
+    for line in contents.lines().map(str::trim) {
+        if line == "end_of_record" {
+            total.merge(&record.finish());
+            record = LcovRecord::default();
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        saw_record = true;
+        match key {
+            "DA" => {
+                let hits = value.split(',').nth(1).unwrap_or("0");
+                record.da_total += 1;
+                if parse_count(hits)? > 0 {
+                    record.da_hit += 1;
+                }
+            }
+            "BRDA" => {
+                let taken = value.split(',').nth(3).unwrap_or("-");
+                record.brda_total += 1;
+                if taken != "-" && parse_count(taken)? > 0 {
+                    record.brda_hit += 1;
+                }
+            }
+            "LF" => record.lf = Some(parse_count(value)?),
+            "LH" => record.lh = Some(parse_count(value)?),
+            "BRF" => record.brf = Some(parse_count(value)?),
+            "BRH" => record.brh = Some(parse_count(value)?),
+            _ => {}
+        }
+    }
+    total.merge(&record.finish());
+
+    if !saw_record {
+        return Err(ShipShapeError::Other("lcov: no records found".to_string()));
+    }
+    Ok(total)
+}
+
+#[derive(Debug, Default)]
+struct LcovRecord {
+    da_total: u64,
+    da_hit: u64,
+    brda_total: u64,
+    brda_hit: u64,
+    lf: Option<u64>,
+    lh: Option<u64>,
+    brf: Option<u64>,
+    brh: Option<u64>,
+}
+
+impl LcovRecord {
+    fn finish(&self) -> MeasuredCoverage {
+        MeasuredCoverage {
+            lines_covered: self.lh.unwrap_or(self.da_hit),
+            lines_total: self.lf.unwrap_or(self.da_total),
+            branches_covered: self.brh.unwrap_or(self.brda_hit),
+            branches_total: self.brf.unwrap_or(self.brda_total),
+        }
+    }
+}
+
+fn parse_cobertura(contents: &str) -> Result<MeasuredCoverage> {
+    let elements = xml_elements(contents);
+    let root = elements
+        .iter()
+        .find(|element| element.name == "coverage")
+        .ok_or_else(|| ShipShapeError::Other("cobertura: missing <coverage>".to_string()))?;
+
+    if let (Some(lines_total), Some(lines_covered)) =
+        (root.count("lines-valid")?, root.count("lines-covered")?)
+    {
+        return Ok(MeasuredCoverage {
+            lines_covered,
+            lines_total,
+            branches_covered: root.count("branches-covered")?.unwrap_or(0),
+            branches_total: root.count("branches-valid")?.unwrap_or(0),
+        });
+    }
+
+    // Older writers omit the totals, so rebuild them from per-line entries.
+    // Lines are repeated under `<methods>`, so key them by file and number.
+    let mut lines: BTreeMap<(String, String), (u64, u64, u64)> = BTreeMap::new();
+    let mut filename = String::new();
+    for element in &elements {
+        match element.name.as_str() {
+            "class" => filename = element.attr("filename").unwrap_or_default().to_string(),
+            "line" => {
+                let number = element.attr("number").unwrap_or_default().to_string();
+                let hits = element.count("hits")?.unwrap_or(0);
+                let (covered, total) = element
+                    .attr("condition-coverage")
+                    .and_then(parse_condition_coverage)
+                    .unwrap_or((0, 0));
+                let entry = lines.entry((filename.clone(), number)).or_default();
+                entry.0 = entry.0.max(hits);
+                entry.1 = entry.1.max(covered);
+                entry.2 = entry.2.max(total);
+            }
+            _ => {}
+        }
+    }
+
+    let mut measured = MeasuredCoverage::default();
+    for (hits, branches_covered, branches_total) in lines.values() {
+        measured.lines_total += 1;
+        if *hits > 0 {
+            measured.lines_covered += 1;
+        }
+        measured.branches_covered += branches_covered;
+        measured.branches_total += branches_total;
+    }
+    Ok(measured)
+}
+
+fn parse_condition_coverage(value: &str) -> Option<(u64, u64)> {
+    let inner = value.split_once('(')?.1.split_once(')')?.0;
+    let (covered, total) = inner.split_once('/')?;
+    Some((covered.trim().parse().ok()?, total.trim().parse().ok()?))
+}
+
+fn parse_jacoco(contents: &str) -> Result<MeasuredCoverage> {
+    let mut line = None;
+    let mut branch = None;
+    // Report-level counters are written last, after every package.
+    for element in xml_elements(contents) {
+        if element.name != "counter" {
+            continue;
+        }
+        let missed = element.count("missed")?.unwrap_or(0);
+        let covered = element.count("covered")?.unwrap_or(0);
+        match element.attr("type") {
+            Some("LINE") => line = Some((covered, missed)),
+            Some("BRANCH") => branch = Some((covered, missed)),
+            _ => {}
+        }
+    }
+
+    let (lines_covered, lines_missed) =
+        line.ok_or_else(|| ShipShapeError::Other("jacoco: missing LINE counter".to_string()))?;
+    let (branches_covered, branches_missed) = branch.unwrap_or((0, 0));
+    Ok(MeasuredCoverage {
+        lines_covered,
+        lines_total: lines_covered + lines_missed,
+        branches_covered,
+        branches_total: branches_covered + branches_missed,
+    })
+}
+
+fn parse_go_cover(contents: &str) -> Result<MeasuredCoverage> {
+    let mut lines = contents.lines();
+    let header = lines.next().unwrap_or_default();
+    if !header.trim_start().starts_with("mode:") {
+        return Err(ShipShapeError::Other(
+            "go cover: missing mode header".to_string(),
+        ));
+    }
+
+    // Merged profiles repeat blocks; keep the highest hit count per block.
+    let mut blocks: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+    for line in lines.map(str::trim).filter(|line| !line.is_empty()) {
+        let mut fields = line.rsplitn(3, ' ');
+        let (Some(count), Some(statements), Some(block)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ShipShapeError::Other(format!(
+                "go cover: malformed line: {line}"
+            )));
+        };
+        let statements = parse_count(statements)?;
+        let count = parse_count(count)?;
+        let entry = blocks.entry(block).or_insert((statements, 0));
+        entry.1 = entry.1.max(count);
+    }
+
+    let mut measured = MeasuredCoverage::default();
+    for (statements, count) in blocks.values() {
+        measured.lines_total += statements;
+        if *count > 0 {
+            measured.lines_covered += statements;
+        }
+    }
+    Ok(measured)
+}
+
+fn parse_llvm_cov_json(contents: &str) -> Result<MeasuredCoverage> {
+    let payload: serde_json::Value = serde_json::from_str(contents)
+        .map_err(|err| ShipShapeError::Other(format!("llvm-cov: {err}")))?;
+    let data = payload
+        .get("data")
+        .and_then(|data| data.as_array())
+        .ok_or_else(|| ShipShapeError::Other("llvm-cov: missing data section".to_string()))?;
+
+    let mut measured = MeasuredCoverage::default();
+    for entry in data {
+        let totals = &entry["totals"];
+        measured.lines_covered += totals["lines"]["covered"].as_u64().unwrap_or(0);
+        measured.lines_total += totals["lines"]["count"].as_u64().unwrap_or(0);
+        measured.branches_covered += totals["branches"]["covered"].as_u64().unwrap_or(0);
+        measured.branches_total += totals["branches"]["count"].as_u64().unwrap_or(0);
+    }
+    Ok(measured)
+}
+
+fn parse_count(value: &str) -> Result<u64> {
+    let value = value.trim();
+    // Some writers emit hit counts as floats (e.g. "1.0e3").
+    value
+        .parse::<u64>()
+        .or_else(|_| value.parse::<f64>().map(|count| count.max(0.0) as u64))
+        .map_err(|_| ShipShapeError::Other(format!("invalid coverage count: {value}")))
+}
+
+#[derive(Debug)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+}
+
+impl XmlElement {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn count(&self, key: &str) -> Result<Option<u64>> {
+        self.attr(key).map(parse_count).transpose()
+    }
+}
+
+/// Collect opening tags and their attributes in document order.
+///
+/// Coverage reports only carry data in attributes, so text nodes, closing
+/// tags, comments and processing instructions are skipped.
+fn xml_elements(contents: &str) -> Vec<XmlElement> {
+    let mut elements = Vec::new();
+    let mut rest = contents;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with(['/', '?', '!']) {
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = rest[..end].trim_end_matches('/');
+        rest = &rest[end + 1..];
+
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        elements.push(XmlElement {
+            name: name.to_string(),
+            attributes: parse_xml_attributes(attrs),
+        });
+    }
+    elements
+}
+
+fn parse_xml_attributes(mut input: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    while let Some(eq) = input.find('=') {
+        let key = input[..eq].trim().to_string();
+        let after = input[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(close) = after[1..].find(quote) else {
+            break;
+        };
+        attributes.push((key, after[1..close + 1].to_string()));
+        input = &after[close + 2..];
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MockFileSystem;
+
+    const LCOV: &str = "TN:\nSF:src/lib.rs\nDA:1,1\nDA:2,0\nDA:3,4\nBRDA:2,0,0,1\nBRDA:2,0,1,-\nend_of_record\nSF:src/main.rs\nLF:10\nLH:5\nBRF:4\nBRH:1\nend_of_record\n";
+
+    #[test]
+    fn parses_lcov_records() {
+        let measured = parse_coverage(CoverageFormat::Lcov, LCOV).expect("lcov");
+        assert_eq!(measured.lines_covered, 7);
+        assert_eq!(measured.lines_total, 13);
+        assert_eq!(measured.branches_covered, 2);
+        assert_eq!(measured.branches_total, 6);
+    }
+
+    #[test]
+    fn parses_cobertura_totals() {
+        let xml = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.75" branch-rate="0.5" lines-covered="30" lines-valid="40" branches-covered="5" branches-valid="10">
+  <packages/>
+</coverage>"#;
+        let measured = parse_coverage(CoverageFormat::Cobertura, xml).expect("cobertura");
+        assert_eq!(measured.line_ratio(), Some(0.75));
+        assert_eq!(measured.branch_ratio(), Some(0.5));
+    }
+
+    #[test]
+    fn parses_cobertura_lines_without_totals() {
+        let xml = r#"<coverage line-rate="0.5">
+  <packages><package name="app"><classes>
+    <class name="a" filename="app/a.py">
+      <methods><method name="f"><lines><line number="1" hits="1"/></lines></method></methods>
+      <lines>
+        <line number="1" hits="1"/>
+        <line number="2" hits="0" branch="true" condition-coverage="50% (1/2)"/>
+      </lines>
+    </class>
+  </classes></package></packages>
+</coverage>"#;
+        let measured = parse_coverage(CoverageFormat::Cobertura, xml).expect("cobertura");
+        assert_eq!(measured.lines_total, 2);
+        assert_eq!(measured.lines_covered, 1);
+        assert_eq!(measured.branches_covered, 1);
+        assert_eq!(measured.branches_total, 2);
+    }
+
+    #[test]
+    fn parses_jacoco_report_counters() {
+        let xml = r#"<?xml version="1.0"?><!DOCTYPE report PUBLIC "-//JACOCO//DTD Report 1.1//EN" "report.dtd">
+<report name="demo">
+  <package name="a"><counter type="LINE" missed="9" covered="1"/></package>
+  <counter type="INSTRUCTION" missed="3" covered="7"/>
+  <counter type="BRANCH" missed="2" covered="2"/>
+  <counter type="LINE" missed="2" covered="8"/>
+</report>"#;
+        let measured = parse_coverage(CoverageFormat::Jacoco, xml).expect("jacoco");
+        assert_eq!(measured.lines_covered, 8);
+        assert_eq!(measured.lines_total, 10);
+        assert_eq!(measured.branches_covered, 2);
+        assert_eq!(measured.branches_total, 4);
+    }
+
+    #[test]
+    fn parses_go_cover_profiles_and_dedupes_blocks() {
+        let profile = "mode: count\nexample.com/a/a.go:3.2,5.3 2 1\nexample.com/a/a.go:7.2,9.3 3 0\nexample.com/a/a.go:7.2,9.3 3 2\nexample.com/a/b.go:1.1,2.2 5 0\n";
+        let measured = parse_coverage(CoverageFormat::GoCover, profile).expect("go");
+        assert_eq!(measured.lines_covered, 5);
+        assert_eq!(measured.lines_total, 10);
+        assert_eq!(measured.branch_ratio(), None);
+    }
+
+    #[test]
+    fn parses_llvm_cov_json_totals() {
+        let json = r#"{"type":"llvm.coverage.json.export","data":[{"totals":{"lines":{"count":200,"covered":150},"branches":{"count":40,"covered":30}}}]}"#;
+        let measured = parse_coverage(CoverageFormat::LlvmCovJson, json).expect("llvm");
+        assert_eq!(measured.line_ratio(), Some(0.75));
+        assert_eq!(measured.branch_ratio(), Some(0.75));
+    }
+
+    #[test]
+    fn rejects_malformed_artifacts() {
+        assert!(parse_coverage(CoverageFormat::Lcov, "").is_err());
+        assert!(parse_coverage(CoverageFormat::GoCover, "a.go:1.1,2.2 1 1").is_err());
+        assert!(parse_coverage(CoverageFormat::Jacoco, "<report></report>").is_err());
+        assert!(parse_coverage(CoverageFormat::Cobertura, "<root/>").is_err());
+        assert!(parse_coverage(CoverageFormat::LlvmCovJson, "{}").is_err());
+        assert!(parse_coverage(CoverageFormat::Lcov, "DA:1,x\n").is_err());
+    }
+
+    #[test]
+    fn detects_formats_from_name_and_contents() {
+        assert_eq!(
+            detect_format(Path::new("lcov.info"), LCOV),
+            Some(CoverageFormat::Lcov)
+        );
+        assert_eq!(
+            detect_format(Path::new("coverage.out"), "mode: set\n"),
+            Some(CoverageFormat::GoCover)
+        );
+        assert_eq!(
+            detect_format(Path::new("coverage.xml"), "<coverage line-rate=\"1\"/>"),
+            Some(CoverageFormat::Cobertura)
+        );
+        assert_eq!(
+            detect_format(
+                Path::new("jacoco.xml"),
+                "<report><counter type=\"LINE\"/></report>"
+            ),
+            Some(CoverageFormat::Jacoco)
+        );
+        assert_eq!(
+            detect_format(
+                Path::new("coverage.json"),
+                "{\"type\":\"llvm.coverage.json.export\"}"
+            ),
+            Some(CoverageFormat::LlvmCovJson)
+        );
+        assert_eq!(detect_format(Path::new("coverage.json"), "{}"), None);
+        assert_eq!(detect_format(Path::new("coverage.xml"), "<html/>"), None);
+        assert_eq!(detect_format(Path::new("notes.txt"), "hello"), None);
+    }
+
+    #[test]
+    fn finds_and_loads_artifacts() {
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(|_| {
+            Ok(vec![
+                PathBuf::from("/repo/src/lib.rs"),
+                PathBuf::from("/repo/lcov.info"),
+                PathBuf::from("/repo/coverage.json"),
+                PathBuf::from("/repo/node_modules/pkg/lcov.info"),
+            ])
+        });
+        fs.expect_read_to_string()
+            .withf(|path| path == Path::new("/repo/lcov.info"))
+            .returning(|_| Ok(LCOV.to_string()));
+        fs.expect_read_to_string()
+            .withf(|path| path == Path::new("/repo/coverage.json"))
+            .returning(|_| Ok("{\"istanbul\": true}".to_string()));
+
+        let artifacts = find_coverage_artifacts(&fs, Path::new("/repo")).expect("artifacts");
+        assert_eq!(
+            artifacts,
+            vec![
+                PathBuf::from("/repo/coverage.json"),
+                PathBuf::from("/repo/lcov.info"),
+            ]
+        );
+
+        let (measured, used) = load_measured_coverage(&fs, &artifacts)
+            .expect("load")
+            .expect("measured");
+        assert_eq!(used, vec![PathBuf::from("/repo/lcov.info")]);
+        assert_eq!(measured.lines_total, 13);
+    }
+
+    #[test]
+    fn load_returns_none_without_parsable_artifacts() {
+        let fs = MockFileSystem::new();
+        assert!(load_measured_coverage(&fs, &[]).expect("load").is_none());
+    }
+
+    #[test]
+    fn apply_marks_report_as_measured() {
+        let mut report = CoverageReport {
+            code_files: 4,
+            test_files: 0,
+            doc_files: 0,
+            test_coverage: 0.0,
+            doc_coverage: 0.0,
+            low_test_coverage: true,
+            low_doc_coverage: true,
+            source: CoverageSource::Heuristic,
+            line_coverage: None,
+            branch_coverage: None,
+            artifacts: Vec::new(),
+            public_items: 0,
+            documented_items: 0,
+            api_doc_coverage: None,
+        };
+        let measured = MeasuredCoverage {
+            lines_covered: 9,
+            lines_total: 10,
+            branches_covered: 0,
+            branches_total: 0,
+        };
+
+        apply_measured_coverage(&mut report, &measured, &[PathBuf::from("lcov.info")]);
+
+        assert_eq!(report.source, CoverageSource::Measured);
+        assert_eq!(report.line_coverage, Some(0.9));
+        assert_eq!(report.branch_coverage, None);
+        assert!(!report.low_test_coverage);
+        assert_eq!(report.artifacts, vec!["lcov.info".to_string()]);
+
+        let mut untouched = report.clone();
+        untouched.source = CoverageSource::Heuristic;
+        apply_measured_coverage(&mut untouched, &MeasuredCoverage::default(), &[]);
+        assert_eq!(untouched.source, CoverageSource::Heuristic);
+    }
+}
@@ -0,0 +1,299 @@
+//! Diff-scoped audits: limit findings to the lines a change touches.
+//!
+//! Changed files and line ranges come from `git diff --unified=0`. A violation
+//! is new when its line was added or modified, or when it has no line and its
+//! file is new. Other violations in changed files are pre-existing; findings
+//! outside changed files are out of scope.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::domain::{DiffReport, Violation};
+use crate::error::{Result, ShipShapeError};
+
+/// Added and modified lines of one file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ChangedFile {
+    added: bool,
+    ranges: Vec<(u32, u32)>,
+}
+
+/// Files and line ranges changed between a base revision and a head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedLines {
+    base: String,
+    head: Option<String>,
+    files: BTreeMap<String, ChangedFile>,
+}
+
+impl ChangedLines {
+    /// Diff `root` against `base`.
+    ///
+    /// With a `head` revision, changes are taken from the merge base of
+    /// `base` and `head` to `head`, as a pull request would show them.
+    /// Without one, the working tree (including untracked files) is compared
+    /// with the merge base of `base` and `HEAD`.
+    pub fn from_git(root: &Path, base: &str, head: Option<&str>) -> Result<Self> {
+        let range = match head {
+            Some(head) => vec![format!("{base}...{head}")],
+            None => vec!["--merge-base".to_string(), base.to_string()],
+        };
+        let mut args = vec![
+            "-c",
+            "core.quotePath=off",
+            "diff",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-prefix",
+            "--relative",
+        ];
+        args.extend(range.iter().map(String::as_str));
+        let diff = git_stdout(root, &args)?;
+        let mut changes = Self::from_unified_diff(base, head, &diff);
+        if head.is_none() {
+            let untracked = git_stdout(root, &["ls-files", "--others", "--exclude-standard"])?;
+            for path in untracked.lines().filter(|path| !path.is_empty()) {
+                let lines = std::fs::read_to_string(root.join(path))
+                    .map(|contents| contents.lines().count() as u32)
+                    .unwrap_or(0);
+                changes.files.insert(
+                    path.to_string(),
+                    ChangedFile {
+                        added: true,
+                        ranges: (lines > 0).then_some((1, lines)).into_iter().collect(),
+                    },
+                );
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Parse `git diff --unified=0 --no-prefix` output.
+    pub fn from_unified_diff(base: &str, head: Option<&str>, diff: &str) -> Self {
+        let mut files: BTreeMap<String, ChangedFile> = BTreeMap::new();
+        let mut current: Option<String> = None;
+        let mut in_header = false;
+        let mut from_nothing = false;
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                in_header = true;
+                current = None;
+                from_nothing = false;
+            } else if in_header && let Some(old) = line.strip_prefix("--- ") {
+                from_nothing = old == "/dev/null";
+            } else if in_header && let Some(new) = line.strip_prefix("+++ ") {
+                current = (new != "/dev/null").then(|| new.to_string());
+                if let Some(path) = &current {
+                    files.entry(path.clone()).or_default().added = from_nothing;
+                }
+            } else if line.starts_with("@@") {
+                in_header = false;
+                let (Some(path), Some(range)) = (&current, hunk_range(line)) else {
+                    continue;
+                };
+                if let Some(file) = files.get_mut(path) {
+                    file.ranges.push(range);
+                }
+            }
+        }
+        Self {
+            base: base.to_string(),
+            head: head.map(str::to_string),
+            files,
+        }
+    }
+
+    /// Changed file paths, relative to the audited root.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Number of added or modified lines.
+    pub fn line_count(&self) -> usize {
+        self.files
+            .values()
+            .flat_map(|file| &file.ranges)
+            .map(|(start, end)| (end - start + 1) as usize)
+            .sum()
+    }
+
+    /// Whether a finding at `path` (relative) and `line` is new, pre-existing
+    /// (`Some(false)`) or outside the changed files (`None`).
+    pub fn is_new(&self, path: &str, line: Option<u32>) -> Option<bool> {
+        let file = self.files.get(path)?;
+        Some(match line {
+            Some(line) => file
+                .ranges
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&line)),
+            None => file.added,
+        })
+    }
+
+    /// Split violations into new and pre-existing ones, dropping findings
+    /// outside the changed files.
+    pub fn partition(
+        &self,
+        root: &Path,
+        violations: Vec<Violation>,
+    ) -> (Vec<Violation>, Vec<Violation>) {
+        let root = root.to_string_lossy().replace('\\', "/");
+        let mut new = Vec::new();
+        let mut preexisting = Vec::new();
+        for violation in violations {
+            let Some((path, line)) = violation_location(&root, &violation) else {
+                continue;
+            };
+            match self.is_new(&path, line) {
+                Some(true) => new.push(violation),
+                Some(false) => preexisting.push(violation),
+                None => {}
+            }
+        }
+        (new, preexisting)
+    }
+
+    /// Summarise the scope for a report.
+    pub fn report(&self, preexisting_violations: Vec<Violation>) -> DiffReport {
+        DiffReport {
+            base: self.base.clone(),
+            head: self.head.clone(),
+            changed_files: self.files().map(str::to_string).collect(),
+            changed_lines: self.line_count(),
+            preexisting_violations,
+        }
+    }
+}
+
+/// Parse the `+start,count` side of a hunk header into an inclusive range.
+fn hunk_range(header: &str) -> Option<(u32, u32)> {
+    let added = header.split(' ').find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match added.split_once(',') {
+        Some((start, count)) => (start.parse::<u32>().ok()?, count.parse::<u32>().ok()?),
+        None => (added.parse::<u32>().ok()?, 1),
+    };
+    (count > 0).then(|| (start, start + count - 1))
+}
+
+/// Relative path and line of a violation, from its fields or a
+/// `path:line:` message prefix.
+fn violation_location(root: &str, violation: &Violation) -> Option<(String, Option<u32>)> {
+    let (location, line) = match &violation.path {
+        Some(path) => (path.clone(), violation.line),
+        None => {
+            let mut parts = violation.message.splitn(3, ':');
+            let path = parts.next()?.to_string();
+            let line = parts.next()?.trim().parse().ok();
+            (path, line)
+        }
+    };
+    let location = location.replace('\\', "/");
+    let relative = location
+        .strip_prefix(root)
+        .map(|rest| rest.trim_start_matches('/'))
+        .unwrap_or(&location)
+        .trim_start_matches("./");
+    Some((relative.to_string(), line))
+}
+
+fn git_stdout(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(ShipShapeError::Other(format!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChangedLines;
+    use crate::domain::{Severity, Violation};
+    use std::path::Path;
+
+    const DIFF: &str = "\
+diff --git src/lib.rs src/lib.rs
+index 1111111..2222222 100644
+--- src/lib.rs
++++ src/lib.rs
+@@ -3,0 +4,2 @@ fn helper() {
++--- not a header
++    call();
+@@ -10 +12 @@ fn other() {
+-    old();
++    new();
+@@ -20,3 +23,0 @@ fn removed() {
+diff --git src/new.rs src/new.rs
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ src/new.rs
+@@ -0,0 +1,3 @@
++fn a() {}
++fn b() {}
++fn c() {}
+diff --git src/gone.rs src/gone.rs
+deleted file mode 100644
+--- src/gone.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+";
+
+    fn violation(id: &str, path: Option<&str>, line: Option<u32>, message: &str) -> Violation {
+        Violation {
+            id: id.to_string(),
+            message: message.to_string(),
+            severity: Severity::Warning,
+            path: path.map(str::to_string),
+            line,
+        }
+    }
+
+    #[test]
+    fn parses_changed_line_ranges() {
+        let changes = ChangedLines::from_unified_diff("main", None, DIFF);
+
+        assert_eq!(
+            changes.files().collect::<Vec<_>>(),
+            vec!["src/lib.rs", "src/new.rs"]
+        );
+        assert_eq!(changes.line_count(), 6);
+        assert_eq!(changes.is_new("src/lib.rs", Some(5)), Some(true));
+        assert_eq!(changes.is_new("src/lib.rs", Some(12)), Some(true));
+        assert_eq!(changes.is_new("src/lib.rs", Some(13)), Some(false));
+        assert_eq!(changes.is_new("src/lib.rs", None), Some(false));
+        assert_eq!(changes.is_new("src/new.rs", None), Some(true));
+        assert_eq!(changes.is_new("src/gone.rs", Some(1)), None);
+    }
+
+    #[test]
+    fn partitions_violations_by_touched_lines() {
+        let changes = ChangedLines::from_unified_diff("main", Some("HEAD"), DIFF);
+        let violations = vec![
+            violation("touched", Some("/repo/src/lib.rs"), Some(4), "m"),
+            violation("old", Some("src/lib.rs"), Some(30), "m"),
+            violation("message", None, None, "src/lib.rs:12: unwrap"),
+            violation("elsewhere", Some("src/main.rs"), Some(1), "m"),
+            violation("repo-level", None, None, "no license file"),
+        ];
+
+        let (new, preexisting) = changes.partition(Path::new("/repo"), violations);
+
+        let ids = |violations: &[Violation]| -> Vec<String> {
+            violations.iter().map(|v| v.id.clone()).collect()
+        };
+        assert_eq!(ids(&new), vec!["touched", "message"]);
+        assert_eq!(ids(&preexisting), vec!["old"]);
+        let report = changes.report(preexisting);
+        assert_eq!(report.head.as_deref(), Some("HEAD"));
+        assert_eq!(report.changed_lines, 6);
+    }
+}
@@ -8,6 +8,17 @@ use utoipa::ToSchema;
 /// A mapping of language names to their percentage of total lines.
 pub type LanguageDistribution = BTreeMap<String, f64>;
 
+/// Origin of the test coverage numbers in a [`CoverageReport`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageSource {
+    /// Estimated from the ratio of test files to code files.
+    #[default]
+    Heuristic,
+    /// Parsed from coverage artifacts produced by a test run.
+    Measured,
+}
+
 /// Coverage heuristics for test and documentation files.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct CoverageReport {
@@ -25,6 +36,54 @@ pub struct CoverageReport {
     pub low_test_coverage: bool,
     /// Whether documentation coverage is below the heuristic threshold.
     pub low_doc_coverage: bool,
+    /// Whether test coverage was measured or estimated from file counts.
+    #[serde(default)]
+    pub source: CoverageSource,
+    /// Measured line coverage ratio, if a coverage artifact was ingested.
+    #[serde(default)]
+    pub line_coverage: Option<f64>,
+    /// Measured branch coverage ratio, if the artifact recorded branches.
+    #[serde(default)]
+    pub branch_coverage: Option<f64>,
+    /// Coverage artifacts that contributed measured data.
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+    /// Public API items found in source files.
+    #[serde(default)]
+    pub public_items: usize,
+    /// Public API items that carry documentation.
+    #[serde(default)]
+    pub documented_items: usize,
+    /// Documented share of public API items, if any public items were found.
+    #[serde(default)]
+    pub api_doc_coverage: Option<f64>,
+}
+
+impl CoverageReport {
+    /// Test coverage ratio, preferring measured line coverage over the heuristic.
+    pub fn effective_test_coverage(&self) -> f64 {
+        self.line_coverage.unwrap_or(self.test_coverage)
+    }
+
+    /// Documentation coverage ratio, preferring API docs over the doc-file ratio.
+    pub fn effective_doc_coverage(&self) -> f64 {
+        self.api_doc_coverage.unwrap_or(self.doc_coverage)
+    }
+}
+
+/// Severity of a violation, used to weight health score penalties.
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Informational finding.
+    Info,
+    /// Issue worth fixing.
+    #[default]
+    Warning,
+    /// Issue that blocks a healthy rating.
+    Error,
 }
 
 /// A code quality violation discovered during an audit.
@@ -34,6 +93,501 @@ pub struct Violation {
     pub id: String,
     /// Human-readable summary of the issue.
     pub message: String,
+    /// Violation severity.
+    #[serde(default)]
+    pub severity: Severity,
+    /// File the violation points at, relative to the repository root.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 1-based line within `path`, when known.
+    #[serde(default)]
+    pub line: Option<u32>,
+}
+
+impl Violation {
+    /// Location formatted as `path:line` (or just `path`), if the violation has one.
+    pub fn location(&self) -> Option<String> {
+        let path = self.path.as_deref()?;
+        Some(match self.line {
+            Some(line) => format!("{path}:{line}"),
+            None => path.to_string(),
+        })
+    }
+}
+
+/// Score category a health deduction belongs to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthCategory {
+    /// Test coverage penalties.
+    Tests,
+    /// Documentation coverage penalties.
+    Docs,
+    /// Violation penalties.
+    Violations,
+    /// Penalties from custom signals.
+    Signal,
+}
+
+/// A single deduction applied to a health score.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct HealthDeduction {
+    /// Category of the deduction.
+    pub category: HealthCategory,
+    /// Points removed from the score.
+    pub points: u32,
+    /// Human-readable explanation.
+    pub reason: String,
+}
+
+/// A health score together with the deductions that produced it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct HealthBreakdown {
+    /// Aggregate health score, 0-100.
+    pub score: u8,
+    /// Deductions applied to the maximum score, in evaluation order.
+    pub deductions: Vec<HealthDeduction>,
+}
+
+/// Ownership concentration for a top-level directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DirectoryOwnership {
+    /// Directory relative to the repository root (`.` for root-level files).
+    pub path: String,
+    /// Fewest authors accounting for more than half of the directory's changes.
+    pub bus_factor: usize,
+    /// Distinct authors who changed the directory.
+    pub authors: usize,
+    /// Commits that touched the directory.
+    pub commits: usize,
+}
+
+/// Change volume for a single file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FileChurn {
+    /// File path relative to the repository root.
+    pub path: String,
+    /// Commits that touched the file.
+    pub commits: usize,
+    /// Lines added across those commits.
+    pub lines_added: u64,
+    /// Lines deleted across those commits.
+    pub lines_deleted: u64,
+}
+
+/// Activity, ownership and churn metrics derived from git history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct HistoryReport {
+    /// Non-merge commits analysed (capped for very large histories).
+    pub commits_analyzed: usize,
+    /// Unix timestamp of the most recent commit.
+    pub last_commit_at: i64,
+    /// Whole days since the most recent commit.
+    pub last_commit_age_days: u64,
+    /// Length of the activity window, in days.
+    pub window_days: u32,
+    /// Commits inside the activity window.
+    pub commits_in_window: usize,
+    /// Average commits per week inside the activity window.
+    pub commits_per_week: f64,
+    /// Distinct authors inside the activity window.
+    pub active_contributors: usize,
+    /// Distinct authors across the analysed history.
+    pub total_contributors: usize,
+    /// Bus factor per top-level directory, sorted by path.
+    pub bus_factor: Vec<DirectoryOwnership>,
+    /// Files with the most churn, busiest first.
+    pub churn: Vec<FileChurn>,
+}
+
+/// Complexity metrics for a single function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FunctionComplexity {
+    /// File path relative to the repository root.
+    pub path: String,
+    /// Function name as written in the source.
+    pub name: String,
+    /// 1-based line where the function starts.
+    pub line: u32,
+    /// Approximate cyclomatic complexity (1 + decision points).
+    pub cyclomatic: u32,
+    /// Deepest block nesting inside the function body.
+    pub max_nesting: u32,
+    /// Function length in lines.
+    pub length: u32,
+}
+
+/// Repository-wide complexity summary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ComplexityReport {
+    /// Source files analysed.
+    pub files_analyzed: usize,
+    /// Functions found across those files.
+    pub functions_analyzed: usize,
+    /// Mean cyclomatic complexity per function.
+    pub average_cyclomatic: f64,
+    /// Highest cyclomatic complexity of any function.
+    pub max_cyclomatic: u32,
+    /// Most complex functions, worst first.
+    pub hotspots: Vec<FunctionComplexity>,
+}
+
+/// A contiguous range of lines in a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CodeSpan {
+    /// Repository the file belongs to, set for cross-repository matches.
+    #[serde(default)]
+    pub repo: Option<String>,
+    /// File path relative to the repository root.
+    pub path: String,
+    /// First line of the span (1-based).
+    pub start_line: u32,
+    /// Last line of the span (inclusive).
+    pub end_line: u32,
+}
+
+/// A block of code that appears in two places.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DuplicateBlock {
+    /// First occurrence.
+    pub first: CodeSpan,
+    /// Second occurrence.
+    pub second: CodeSpan,
+    /// Length of the match in normalised tokens.
+    pub tokens: usize,
+    /// Share of tokens that are identical before normalisation, 0.0-1.0.
+    /// `1.0` is a verbatim copy; lower values indicate renamed identifiers or
+    /// changed literals.
+    pub similarity: f64,
+}
+
+/// Duplicate code found in a repository.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DuplicationReport {
+    /// Source files analysed.
+    pub files_analyzed: usize,
+    /// Duplicate block pairs found inside the repository.
+    pub duplicate_blocks: usize,
+    /// Distinct source lines that take part in a duplicate block.
+    pub duplicated_lines: usize,
+    /// Duplicated lines as a share of all source lines, 0.0-1.0.
+    pub duplication_ratio: f64,
+    /// Largest duplicate blocks inside the repository.
+    pub blocks: Vec<DuplicateBlock>,
+    /// Blocks shared with other repositories in the same batch.
+    #[serde(default)]
+    pub cross_repo: Vec<DuplicateBlock>,
+}
+
+/// A source file paired with the tests that likely exercise it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TestMapping {
+    /// Source file, relative to the repository root.
+    pub source: String,
+    /// Test files (or the source itself, for inline tests) covering it.
+    pub tests: Vec<String>,
+}
+
+/// Which source files have associated tests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TestMappingReport {
+    /// Source files considered.
+    pub source_files: usize,
+    /// Source files with at least one associated test.
+    pub tested_files: usize,
+    /// Tested source files and their tests.
+    pub mappings: Vec<TestMapping>,
+    /// Source files without any associated test.
+    pub untested: Vec<String>,
+}
+
+/// A license file found at the repository root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LicenseFile {
+    /// File path, relative to the repository root.
+    pub path: String,
+    /// SPDX identifier, if the license text was recognised.
+    pub spdx: Option<String>,
+}
+
+/// A source file whose header declares a license.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct FileLicense {
+    /// File path, relative to the repository root.
+    pub path: String,
+    /// SPDX identifier declared or recognised in the header.
+    pub spdx: String,
+}
+
+/// Repository license and per-file license header findings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LicenseReport {
+    /// SPDX expression for the repository, e.g. `MIT OR Apache-2.0`.
+    pub license: Option<String>,
+    /// License files found at the repository root.
+    pub license_files: Vec<LicenseFile>,
+    /// Source files scanned for license headers.
+    pub files_scanned: usize,
+    /// Source files whose header declares a license.
+    pub files_with_headers: usize,
+    /// Number of source files per header license.
+    pub header_licenses: BTreeMap<String, usize>,
+    /// Source files whose license is incompatible with the repository license.
+    pub incompatible: Vec<FileLicense>,
+}
+
+/// Why a file is excluded from language stats, coverage and mechanics.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExcludedKind {
+    /// Produced by a tool: lockfiles, protobuf output, minified bundles.
+    Generated,
+    /// Third-party code checked into the repository.
+    Vendored,
+}
+
+/// A generated or vendored file excluded from the audit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ExcludedFile {
+    /// File path, relative to the repository root.
+    pub path: String,
+    /// Whether the file is generated or vendored.
+    pub kind: ExcludedKind,
+    /// Rule that matched, e.g. `lockfile` or `linguist-vendored`.
+    pub reason: String,
+}
+
+/// Build ecosystem of a project, in drydock detection priority order.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectKind {
+    /// `pyproject.toml`, `setup.py`, `setup.cfg`, `Pipfile`, or
+    /// `requirements.txt` at the repository root.
+    Python,
+    /// `package.json`.
+    Node,
+    /// `Cargo.toml`.
+    Rust,
+    /// `go.mod`.
+    Go,
+    /// `CMakeLists.txt`.
+    CMake,
+}
+
+impl ProjectKind {
+    /// Short lowercase name used in job names and reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Python => "python",
+            Self::Node => "node",
+            Self::Rust => "rust",
+            Self::Go => "go",
+            Self::CMake => "cmake",
+        }
+    }
+}
+
+/// A project root discovered inside a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Project {
+    /// Directory relative to the repository root, `.` for the root itself.
+    pub path: String,
+    /// Name from the project manifest, or the directory name.
+    pub name: String,
+    /// Ecosystems whose marker files are present, highest priority first.
+    pub kinds: Vec<ProjectKind>,
+    /// Directory of the workspace that declares this project as a member.
+    #[serde(default)]
+    pub workspace: Option<String>,
+}
+
+/// A build stack detected for drydock, with the files that revealed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DetectedStack {
+    /// Build ecosystem.
+    pub kind: ProjectKind,
+    /// Directory the stack is built from, `.` for the repository root.
+    pub path: String,
+    /// Marker files, relative to the repository root, that identified the stack.
+    pub evidence: Vec<String>,
+    /// Toolchain version the repository asks for, if it sets one.
+    #[serde(default)]
+    pub toolchain: Option<ToolchainRequirement>,
+    /// Package manager that installs the stack's dependencies.
+    #[serde(default)]
+    pub package_manager: Option<DetectedPackageManager>,
+    /// Tool that runs the stack's tests.
+    #[serde(default)]
+    pub test_runner: Option<TestRunner>,
+}
+
+/// A toolchain version constraint declared by a repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ToolchainRequirement {
+    /// Exact version (`1.76.0`) or version range (`>=3.9`, `^18`).
+    pub version: String,
+    /// File and field the constraint was read from, e.g. `.nvmrc` or
+    /// `pyproject.toml (requires-python)`.
+    pub source: String,
+}
+
+/// Dependency installer of a Python or Node stack.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageManager {
+    /// `package-lock.json`, or no lockfile at all.
+    Npm,
+    /// `yarn.lock`.
+    Yarn,
+    /// `pnpm-lock.yaml`.
+    Pnpm,
+    /// `requirements.txt`, or a package installed from its own metadata.
+    Pip,
+    /// `poetry.lock` or `[tool.poetry]`.
+    Poetry,
+    /// `uv.lock` or `[tool.uv]`.
+    Uv,
+    /// `Pipfile` or `Pipfile.lock`.
+    Pipenv,
+}
+
+impl PackageManager {
+    /// Command name, as used in `packageManager` fields and reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Yarn => "yarn",
+            Self::Pnpm => "pnpm",
+            Self::Pip => "pip",
+            Self::Poetry => "poetry",
+            Self::Uv => "uv",
+            Self::Pipenv => "pipenv",
+        }
+    }
+}
+
+/// A package manager detected for a stack, with the file that chose it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DetectedPackageManager {
+    /// Package manager that installs dependencies.
+    pub manager: PackageManager,
+    /// Version pinned by the `packageManager` field of `package.json`.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// File, and field if any, the choice was read from, e.g. `yarn.lock`
+    /// or `pyproject.toml (tool.poetry)`.
+    pub source: String,
+    /// Lockfile or requirements file the install follows, relative to the
+    /// root the pipeline is generated for; `None` installs unlocked.
+    #[serde(default)]
+    pub lockfile: Option<String>,
+}
+
+/// Tool that runs a stack's tests.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TestRunner {
+    /// `pytest`, configured or listed as a dependency.
+    Pytest,
+    /// The standard library `unittest` discovery, when nothing else is set up.
+    Unittest,
+    /// `tox.ini` or `[tool.tox]`.
+    Tox,
+    /// `noxfile.py`.
+    Nox,
+    /// The `test` script of `package.json`.
+    NpmScript,
+}
+
+impl TestRunner {
+    /// Short name used in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pytest => "pytest",
+            Self::Unittest => "unittest",
+            Self::Tox => "tox",
+            Self::Nox => "nox",
+            Self::NpmScript => "package.json test script",
+        }
+    }
+}
+
+/// Outcome of one drydock job run locally.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobOutcome {
+    /// Every command exited successfully.
+    Passed,
+    /// A command exited with a failure status.
+    Failed,
+    /// The job ran past its timeout and was killed.
+    TimedOut,
+    /// The job could not be started, e.g. the container runtime is missing.
+    Error,
+}
+
+impl JobOutcome {
+    /// Short name used in reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Passed => "passed",
+            Self::Failed => "failed",
+            Self::TimedOut => "timed out",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Result of running one drydock job against a copy of the repository.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct JobVerification {
+    /// Job name, as in the generated pipelines.
+    pub job: String,
+    /// Directory the job ran in, relative to the repository root.
+    pub path: String,
+    /// How the job ended.
+    pub outcome: JobOutcome,
+    /// Exit code of the job's shell, when it exited on its own.
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+    /// Wall-clock run time in milliseconds.
+    pub duration_ms: u64,
+    /// Last lines of combined output, or the reason the job could not start.
+    #[serde(default)]
+    pub log_tail: Vec<String>,
+}
+
+/// Local verification of a repository's drydock jobs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Verification {
+    /// Where the jobs ran: `host`, or the container runtime command.
+    pub runtime: String,
+    /// Whether every job passed.
+    pub passed: bool,
+    /// Per-job results in pipeline order.
+    pub jobs: Vec<JobVerification>,
+}
+
+/// Scope of a diff-limited audit and the violations it set aside.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DiffReport {
+    /// Base revision the changes are measured from.
+    pub base: String,
+    /// Head revision, or `None` for the working tree.
+    #[serde(default)]
+    pub head: Option<String>,
+    /// Files added or modified since `base`, relative to the repository root.
+    pub changed_files: Vec<String>,
+    /// Number of added or modified lines.
+    pub changed_lines: usize,
+    /// Violations in changed files that predate the change.
+    pub preexisting_violations: Vec<Violation>,
 }
 
 /// Represents the health status of a repository.
@@ -47,4 +601,7 @@ pub struct FleetReport {
     pub coverage: CoverageReport,
     /// Aggregate health score, 0-100.
     pub health_score: u8,
+    /// Deductions that explain the health score.
+    #[serde(default)]
+    pub health_breakdown: Vec<HealthDeduction>,
 }
@@ -1,61 +1,1208 @@
 //! CI configuration generation for ShipShape.
+//!
+//! Every build stack in the repository is detected, including the stacks of
+//! nested projects, and each gets its own CI job and Dockerfile stage. Members
+//! of a Cargo workspace share one job at the workspace root. Images follow the
+//! toolchain versions the repository pins; see [`crate::toolchain`]. Install
+//! and test commands follow the package manager and test runner each stack
+//! uses; see [`crate::packaging`]. Jobs are rendered for each requested
+//! [`CiTarget`], with dependency caches keyed on lockfiles unless
+//! [`DrydockOptions::cache`] turns them off.
 
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
 use std::path::Path;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::domain::{DetectedStack, PackageManager, Project, ProjectKind, TestRunner};
+use crate::error::{Result, ShipShapeError};
+use crate::fs::{FileSystem, StdFileSystem};
+use crate::packaging::{detect_package_manager, detect_test_runner};
+use crate::projects::{ROOT_PROJECT, discover_projects, in_dir, kind_markers, relative_to};
+use crate::toolchain::{ToolchainDefaults, detect_toolchain};
+
+/// CI stages in pipeline order.
+const STAGES: &[&str] = &["build", "test"];
+
+/// Repository settings file; its `[drydock]` table overrides launch options.
+pub const REPO_CONFIG_FILE: &str = ".shipshape.toml";
+
+/// First Dockerfile line, enabling BuildKit cache mounts.
+const DOCKERFILE_SYNTAX: &str = "# syntax=docker/dockerfile:1";
+
+/// Unprivileged user that generated Dockerfiles install and test as.
+const DOCKER_USER: &str = "shipshape";
+
+/// User id of [`DOCKER_USER`], outside the range base images use.
+const DOCKER_UID: u32 = 10001;
+
+/// Home directory of [`DOCKER_USER`], where tool caches are mounted.
+const DOCKER_HOME: &str = "/home/shipshape";
+
+/// Triggers and header shared by every generated GitHub Actions workflow.
+const GITHUB_HEADER: &str = "name: ShipShape\n\non:\n  push:\n  pull_request:\n\njobs:\n";
+
+/// CI systems drydock renders pipelines for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CiTarget {
+    /// GitLab CI, written to `.gitlab-ci.yml`.
+    GitLab,
+    /// GitHub Actions, written to `.github/workflows/shipshape.yml`.
+    GitHub,
+}
+
+impl CiTarget {
+    /// Every supported target.
+    pub const ALL: &'static [CiTarget] = &[CiTarget::GitLab, CiTarget::GitHub];
+
+    /// Short name used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::GitLab => "gitlab",
+            Self::GitHub => "github",
+        }
+    }
+
+    /// Path of the generated file, relative to the repository root.
+    pub fn path(self) -> &'static str {
+        match self {
+            Self::GitLab => ".gitlab-ci.yml",
+            Self::GitHub => ".github/workflows/shipshape.yml",
+        }
+    }
+}
+
+impl FromStr for CiTarget {
+    type Err = ShipShapeError;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|target| target.name().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| {
+                ShipShapeError::Other(format!(
+                    "unknown CI target `{value}` (expected gitlab or github)"
+                ))
+            })
+    }
+}
+
+/// Settings for generated drydock output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrydockOptions {
+    /// Images and fallback versions for stacks without a pinned toolchain.
+    pub toolchains: ToolchainDefaults,
+    /// CI systems to render pipelines for.
+    pub targets: Vec<CiTarget>,
+    /// Cache dependencies and build output between runs: GitLab `cache:`
+    /// blocks, GitHub Actions cache inputs and BuildKit cache mounts.
+    pub cache: bool,
+}
+
+impl Default for DrydockOptions {
+    fn default() -> Self {
+        Self {
+            toolchains: ToolchainDefaults::default(),
+            targets: CiTarget::ALL.to_vec(),
+            cache: true,
+        }
+    }
+}
+
+/// The `[drydock]` table of [`REPO_CONFIG_FILE`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RepoConfig {
+    drydock: RepoDrydockConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RepoDrydockConfig {
+    cache: Option<bool>,
+}
+
+impl DrydockOptions {
+    /// Apply the `[drydock]` settings of the repository or project at `root`,
+    /// if it has a [`REPO_CONFIG_FILE`]:
+    ///
+    /// ```toml
+    /// [drydock]
+    /// cache = false
+    /// ```
+    pub fn for_repo<F: FileSystem>(&self, fs: &F, root: &Path) -> Result<Self> {
+        let Ok(contents) = fs.read_to_string(&root.join(REPO_CONFIG_FILE)) else {
+            return Ok(self.clone());
+        };
+        let config: RepoConfig = toml::from_str(&contents)
+            .map_err(|err| ShipShapeError::Other(format!("invalid {REPO_CONFIG_FILE}: {err}")))?;
+        Ok(Self {
+            cache: config.drydock.cache.unwrap_or(self.cache),
+            ..self.clone()
+        })
+    }
+}
+
+/// Generated drydock files for a repository or project.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Drydock {
+    /// Dockerfile contents.
+    pub dockerfile: String,
+    /// `.dockerignore` contents for the Dockerfile's build context.
+    pub dockerignore: String,
+    /// CI configuration contents keyed by [`CiTarget::path`].
+    pub ci_files: BTreeMap<String, String>,
+    /// The jobs every CI file runs, in pipeline order.
+    pub jobs: Vec<DrydockJob>,
+}
+
+/// One drydock job as data, for running it outside CI; see
+/// [`crate::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DrydockJob {
+    /// Job name, also its Dockerfile stage in multi-stage output.
+    pub name: String,
+    /// Directory the job runs in, relative to the repository root.
+    pub path: String,
+    /// Image the job runs in.
+    pub image: String,
+    /// Distribution packages installed with apt before any command.
+    pub packages: Vec<String>,
+    /// Setup, install and test commands in order.
+    pub commands: Vec<String>,
+}
+
+/// How one stack is installed and tested.
+struct Recipe {
+    image: String,
+    /// Toolchain version the image and setup actions are pinned to.
+    version: String,
+    stage: &'static str,
+    /// Distribution packages installed with apt before anything else.
+    packages: &'static [&'static str],
+    /// Installs or enables the package manager itself.
+    setup: Option<String>,
+    install: Vec<String>,
+    /// Dockerfile dependency layer, when dependencies install from the
+    /// manifests alone.
+    layer: Option<DependencyLayer>,
+    /// Variables the Dockerfile sets for every later step.
+    env: &'static [(&'static str, &'static str)],
+    /// Test command, or `None` when the stack defines no tests.
+    test: Option<String>,
+    /// Run `test` while building the Dockerfile, not only as its command.
+    test_in_build: bool,
+    /// Use `test` as the Dockerfile `CMD`.
+    test_as_cmd: bool,
+    /// Caches to keep between runs, or `None` when caching is off.
+    cache: Option<Cache>,
+}
+
+/// Dockerfile steps that install dependencies before the source is copied.
+struct DependencyLayer {
+    /// Manifests and lockfiles copied first, relative to the pipeline root.
+    /// Globs such as `go.sum*` tolerate optional files.
+    files: Vec<String>,
+    /// Commands that install dependencies from `files` alone.
+    install: Vec<String>,
+    /// Commands that finish the install once the source is copied.
+    finish: Vec<String>,
+}
+
+/// Dependency and build caches of one stack.
+struct Cache {
+    /// Files whose hash keys the cache, relative to the pipeline root.
+    lockfiles: Vec<String>,
+    /// Tool cache directories under the CI project directory, with the
+    /// variable that points the tool at them.
+    variables: &'static [(&'static str, &'static str)],
+    /// Build output directories, relative to the stack directory.
+    build_dirs: &'static [&'static str],
+    /// Tool cache directories inside the image, mounted by BuildKit; `~/`
+    /// is the home directory of the Dockerfile user.
+    mounts: &'static [&'static str],
+}
+
+fn cache(stack: &DetectedStack) -> Cache {
+    let manager = stack.package_manager.as_ref();
+    let lockfiles = |defaults: &[&str]| match manager.and_then(|m| m.lockfile.clone()) {
+        Some(lockfile) => vec![lockfile],
+        None => defaults
+            .iter()
+            .map(|name| in_dir(&stack.path, name))
+            .collect(),
+    };
+    let cache = |lockfiles, variables, build_dirs, mounts| Cache {
+        lockfiles,
+        variables,
+        build_dirs,
+        mounts,
+    };
+    match stack.kind {
+        ProjectKind::Python => {
+            let lockfiles = lockfiles(&["requirements.txt", "pyproject.toml"]);
+            match manager.map(|m| m.manager) {
+                Some(PackageManager::Poetry) => cache(
+                    lockfiles,
+                    &[
+                        ("POETRY_CACHE_DIR", ".cache/pypoetry"),
+                        ("PIP_CACHE_DIR", ".cache/pip"),
+                    ],
+                    &[],
+                    &["~/.cache/pypoetry", "~/.cache/pip"],
+                ),
+                Some(PackageManager::Uv) => cache(
+                    lockfiles,
+                    &[("UV_CACHE_DIR", ".cache/uv")],
+                    &[],
+                    &["~/.cache/uv"],
+                ),
+                Some(PackageManager::Pipenv) => cache(
+                    lockfiles,
+                    &[
+                        ("PIPENV_CACHE_DIR", ".cache/pipenv"),
+                        ("PIP_CACHE_DIR", ".cache/pip"),
+                    ],
+                    &[],
+                    &["~/.cache/pipenv", "~/.cache/pip"],
+                ),
+                _ => cache(
+                    lockfiles,
+                    &[("PIP_CACHE_DIR", ".cache/pip")],
+                    &[],
+                    &["~/.cache/pip"],
+                ),
+            }
+        }
+        ProjectKind::Node => {
+            let lockfiles = lockfiles(&["package.json"]);
+            match manager.map(|m| m.manager) {
+                Some(PackageManager::Yarn) => cache(
+                    lockfiles,
+                    &[("YARN_CACHE_FOLDER", ".yarn-cache")],
+                    &[],
+                    &["~/.cache/yarn", "~/.yarn/berry/cache"],
+                ),
+                Some(PackageManager::Pnpm) => cache(
+                    lockfiles,
+                    &[("npm_config_store_dir", ".pnpm-store")],
+                    &[],
+                    &["~/.local/share/pnpm/store"],
+                ),
+                _ => cache(lockfiles, &[("npm_config_cache", ".npm")], &[], &["~/.npm"]),
+            }
+        }
+        ProjectKind::Rust => cache(
+            lockfiles(&["Cargo.lock"]),
+            &[("CARGO_HOME", ".cargo")],
+            &["target"],
+            &["/usr/local/cargo/registry", "/usr/local/cargo/git"],
+        ),
+        ProjectKind::Go => cache(
+            lockfiles(&["go.sum"]),
+            &[("GOPATH", ".go"), ("GOCACHE", ".cache/go-build")],
+            &[],
+            &["/go/pkg/mod", "~/.cache/go-build"],
+        ),
+        ProjectKind::CMake => cache(lockfiles(&["CMakeLists.txt"]), &[], &["build"], &[]),
+    }
+}
+
+fn recipe(stack: &DetectedStack, options: &DrydockOptions) -> Recipe {
+    let toolchains = &options.toolchains;
+    let base = Recipe {
+        image: toolchains.image(stack.kind, stack.toolchain.as_ref()),
+        version: toolchains.resolve(stack.kind, stack.toolchain.as_ref()),
+        stage: "test",
+        packages: &[],
+        setup: None,
+        install: Vec::new(),
+        layer: None,
+        env: &[],
+        test: None,
+        test_in_build: true,
+        test_as_cmd: true,
+        cache: options.cache.then(|| cache(stack)),
+    };
+    let manager = stack.package_manager.as_ref();
+    let locked = manager.is_some_and(|m| m.lockfile.is_some());
+    match stack.kind {
+        ProjectKind::Python => {
+            let pyproject = in_dir(&stack.path, "pyproject.toml");
+            let pipfile = in_dir(&stack.path, "Pipfile");
+            let (setup, install, run, prefetch) = match manager.map(|m| m.manager) {
+                Some(PackageManager::Poetry) => (
+                    "python -m pip install poetry",
+                    "poetry install",
+                    "poetry run ",
+                    Some((
+                        pyproject,
+                        "poetry install --no-root",
+                        Some("poetry install"),
+                    )),
+                ),
+                Some(PackageManager::Uv) if locked => (
+                    "python -m pip install uv",
+                    "uv sync --locked",
+                    "uv run ",
+                    Some((
+                        pyproject,
+                        "uv sync --locked --no-install-project",
+                        Some("uv sync --locked"),
+                    )),
+                ),
+                Some(PackageManager::Uv) => (
+                    "python -m pip install uv",
+                    "uv sync",
+                    "uv run ",
+                    Some((pyproject, "uv sync --no-install-project", Some("uv sync"))),
+                ),
+                Some(PackageManager::Pipenv) if locked => (
+                    "python -m pip install pipenv",
+                    "pipenv install --dev --deploy",
+                    "pipenv run ",
+                    Some((pipfile, "pipenv install --dev --deploy", None)),
+                ),
+                Some(PackageManager::Pipenv) => (
+                    "python -m pip install pipenv",
+                    "pipenv install --dev",
+                    "pipenv run ",
+                    Some((pipfile, "pipenv install --dev", None)),
+                ),
+                _ => (
+                    "python -m pip install --upgrade pip",
+                    if locked {
+                        "python -m pip install -r requirements.txt"
+                    } else {
+                        "python -m pip install .[test]"
+                    },
+                    "",
+                    None,
+                ),
+            };
+            let mut tools = Vec::new();
+            let pip = manager.is_none_or(|m| m.manager == PackageManager::Pip);
+            let test = match stack.test_runner {
+                Some(TestRunner::Tox) => {
+                    tools.push("python -m pip install tox".to_string());
+                    "tox -e py".to_string()
+                }
+                Some(TestRunner::Nox) => {
+                    tools.push("python -m pip install nox".to_string());
+                    "nox".to_string()
+                }
+                Some(TestRunner::Pytest) => {
+                    if pip {
+                        tools.push("python -m pip install pytest".to_string());
+                    }
+                    format!("{run}pytest -q")
+                }
+                _ => format!("{run}python -m unittest discover"),
+            };
+            let lockfile = manager.and_then(|m| m.lockfile.clone());
+            let layer = match prefetch {
+                Some((manifest, prefetch, finish)) => Some(DependencyLayer {
+                    files: std::iter::once(manifest).chain(lockfile).collect(),
+                    install: std::iter::once(prefetch.to_string())
+                        .chain(tools.iter().cloned())
+                        .collect(),
+                    finish: finish.iter().map(|step| step.to_string()).collect(),
+                }),
+                None if locked => Some(DependencyLayer {
+                    files: lockfile.into_iter().collect(),
+                    install: std::iter::once(install.to_string())
+                        .chain(tools.iter().cloned())
+                        .collect(),
+                    finish: Vec::new(),
+                }),
+                None => None,
+            };
+            let env: &'static [(&'static str, &'static str)] = match manager.map(|m| m.manager) {
+                Some(PackageManager::Poetry) => &[
+                    ("PATH", "/home/shipshape/.local/bin:$PATH"),
+                    ("POETRY_VIRTUALENVS_IN_PROJECT", "true"),
+                ],
+                Some(PackageManager::Pipenv) => &[
+                    ("PATH", "/home/shipshape/.local/bin:$PATH"),
+                    ("PIPENV_VENV_IN_PROJECT", "1"),
+                ],
+                _ => &[("PATH", "/home/shipshape/.local/bin:$PATH")],
+            };
+            Recipe {
+                setup: Some(setup.to_string()),
+                install: std::iter::once(install.to_string()).chain(tools).collect(),
+                layer,
+                env,
+                test: Some(test),
+                test_in_build: false,
+                ..base
+            }
+        }
+        ProjectKind::Node => {
+            let lockfile = manager.and_then(|m| m.lockfile.clone());
+            let manager = manager.map_or(PackageManager::Npm, |m| m.manager);
+            let install = match (manager, locked) {
+                (PackageManager::Npm, true) => "npm ci".to_string(),
+                (_, true) => format!("{} install --frozen-lockfile", manager.name()),
+                (_, false) => format!("{} install", manager.name()),
+            };
+            let lockfile_dir = lockfile.as_deref().map(|lockfile| {
+                lockfile
+                    .rsplit_once('/')
+                    .map_or(ROOT_PROJECT, |(dir, _)| dir)
+                    .to_string()
+            });
+            let layer = lockfile_dir
+                .is_none_or(|dir| dir == stack.path)
+                .then(|| DependencyLayer {
+                    files: std::iter::once(in_dir(&stack.path, "package.json"))
+                        .chain(lockfile)
+                        .collect(),
+                    install: vec![install.clone()],
+                    finish: Vec::new(),
+                });
+            Recipe {
+                setup: (manager != PackageManager::Npm).then(|| "corepack enable".to_string()),
+                install: vec![install],
+                layer,
+                test: (stack.test_runner == Some(TestRunner::NpmScript))
+                    .then(|| format!("{} test", manager.name())),
+                test_in_build: false,
+                ..base
+            }
+        }
+        ProjectKind::Rust => Recipe {
+            test: Some("cargo test --all".to_string()),
+            ..base
+        },
+        ProjectKind::Go => Recipe {
+            layer: Some(DependencyLayer {
+                files: vec![
+                    in_dir(&stack.path, "go.mod"),
+                    in_dir(&stack.path, "go.sum*"),
+                ],
+                install: vec!["go mod download".to_string()],
+                finish: Vec::new(),
+            }),
+            test: Some("go test ./...".to_string()),
+            ..base
+        },
+        ProjectKind::CMake => Recipe {
+            stage: "build",
+            packages: &["cmake", "build-essential"],
+            install: vec!["cmake -S . -B build".to_string()],
+            test: Some("cmake --build build".to_string()),
+            test_as_cmd: false,
+            ..base
+        },
+    }
+}
+
+impl Recipe {
+    /// Setup, install and, if defined, test commands in order.
+    fn commands(&self) -> impl Iterator<Item = &String> {
+        self.setup
+            .iter()
+            .chain(&self.install)
+            .chain(self.test.as_ref())
+    }
+}
+
+/// One CI job: a stack, its recipe and the name its job and stage use.
+struct Job<'a> {
+    name: String,
+    stack: &'a DetectedStack,
+    recipe: Recipe,
+}
 
 /// Generate a Dockerfile and `.gitlab-ci.yml` configuration for a repository.
 ///
-/// The generated output is based on build marker files found at the repository root.
+/// Every detected stack gets a CI job with the built-in toolchain defaults;
+/// see [`generate_drydock`].
 pub fn generate_ci_config(path: &Path) -> (String, String) {
-    if detect_notebook_only(path) {
-        return (notebook_only_dockerfile(), notebook_only_ci());
+    let stacks = detect_stacks(&StdFileSystem::new(), path).unwrap_or_default();
+    let options = DrydockOptions {
+        targets: vec![CiTarget::GitLab],
+        ..DrydockOptions::default()
+    };
+    let mut drydock = generate_drydock(path, &stacks, &options);
+    let ci = drydock
+        .ci_files
+        .remove(CiTarget::GitLab.path())
+        .unwrap_or_default();
+    (drydock.dockerfile, ci)
+}
+
+/// Detect every build stack under `root`, with the marker files that
+/// revealed each one, the toolchain version it pins and its package manager
+/// and test runner, sorted by directory.
+pub fn detect_stacks<F: FileSystem>(fs: &F, root: &Path) -> Result<Vec<DetectedStack>> {
+    let files: BTreeSet<String> = fs
+        .list_files(root)?
+        .iter()
+        .map(|path| {
+            path.strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/")
+        })
+        .collect();
+    let mut stacks: Vec<DetectedStack> = Vec::new();
+    let mut cargo_jobs: BTreeMap<String, usize> = BTreeMap::new();
+    for project in discover_projects(fs, root)? {
+        for kind in project.kinds {
+            let evidence: Vec<String> = kind_markers(kind)
+                .map(|marker| in_dir(&project.path, marker))
+                .filter(|path| files.contains(path))
+                .collect();
+            let workspace = project
+                .workspace
+                .as_ref()
+                .filter(|dir| files.contains(&in_dir(dir, "Cargo.toml")));
+            if kind == ProjectKind::Rust {
+                let dir = workspace.unwrap_or(&project.path);
+                if let Some(&index) = cargo_jobs.get(dir) {
+                    stacks[index].evidence.extend(evidence);
+                    continue;
+                }
+                cargo_jobs.insert(dir.clone(), stacks.len());
+                let mut evidence = evidence;
+                if dir != &project.path {
+                    evidence.insert(0, in_dir(dir, "Cargo.toml"));
+                }
+                stacks.push(detected_stack(fs, root, kind, dir, dir.clone(), evidence));
+                continue;
+            }
+            stacks.push(detected_stack(
+                fs,
+                root,
+                kind,
+                &project.path,
+                project.path.clone(),
+                evidence,
+            ));
+        }
     }
-    match detect_language(path) {
-        Some(Language::Python) => (python_dockerfile(), python_ci()),
-        Some(Language::Node) => (node_dockerfile(), node_ci()),
-        Some(Language::Rust) => (rust_dockerfile(), rust_ci()),
-        Some(Language::Go) => (go_dockerfile(), go_ci()),
-        Some(Language::CMake) => (cmake_dockerfile(), cmake_ci()),
-        None => (generic_dockerfile(), generic_ci()),
+    for stack in &mut stacks {
+        stack.evidence.sort();
+        stack.evidence.dedup();
     }
+    stacks.sort_by(|a, b| (&a.path, a.kind).cmp(&(&b.path, b.kind)));
+    Ok(stacks)
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Language {
-    Python,
-    Node,
-    Rust,
-    Go,
-    CMake,
+/// Stacks of one discovered project on its own, built from its directory.
+///
+/// Used for per-service output, where nested projects and shared workspace
+/// jobs are out of scope. Evidence stays relative to the repository root;
+/// lockfiles become relative to the project, or are dropped when they live
+/// outside it.
+pub fn project_stacks<F: FileSystem>(fs: &F, root: &Path, project: &Project) -> Vec<DetectedStack> {
+    project
+        .kinds
+        .iter()
+        .map(|&kind| {
+            let evidence = kind_markers(kind)
+                .map(|marker| in_dir(&project.path, marker))
+                .filter(|path| fs.read(&root.join(path)).is_ok())
+                .collect();
+            let mut stack = detected_stack(
+                fs,
+                root,
+                kind,
+                &project.path,
+                ROOT_PROJECT.to_string(),
+                evidence,
+            );
+            if let Some(manager) = &mut stack.package_manager {
+                manager.lockfile = manager
+                    .lockfile
+                    .as_deref()
+                    .and_then(|lockfile| relative_to(lockfile, &project.path));
+            }
+            stack
+        })
+        .collect()
 }
 
-fn detect_language(path: &Path) -> Option<Language> {
-    if has_file(path, "pyproject.toml") || has_file(path, "setup.py") {
-        return Some(Language::Python);
+/// A `kind` stack with the toolchain, package manager and test runner found
+/// in `dir`, built from `path`.
+fn detected_stack<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    kind: ProjectKind,
+    dir: &str,
+    path: String,
+    evidence: Vec<String>,
+) -> DetectedStack {
+    DetectedStack {
+        kind,
+        path,
+        evidence,
+        toolchain: detect_toolchain(fs, root, dir, kind),
+        package_manager: detect_package_manager(fs, root, dir, kind),
+        test_runner: detect_test_runner(fs, root, dir, kind),
     }
-    if has_file(path, "package.json") {
-        return Some(Language::Node);
+}
+
+/// Generate a Dockerfile and one CI file per target, with one job per stack.
+///
+/// A single stack at the root yields a plain Dockerfile. Several stacks yield
+/// one Dockerfile stage per job, selectable with `docker build --target`, and
+/// a final `all` stage that builds and tests every one of them. Images come
+/// from each stack's toolchain requirement, or from the options' toolchain
+/// table when the repository sets none.
+pub fn generate_drydock(
+    root: &Path,
+    stacks: &[DetectedStack],
+    options: &DrydockOptions,
+) -> Drydock {
+    if stacks.is_empty() {
+        let notebook = contains_notebook(root);
+        let (dockerfile, fallback): (String, fn(CiTarget) -> String) = if notebook {
+            (notebook_only_dockerfile(), notebook_only_ci)
+        } else {
+            (generic_dockerfile(), generic_ci)
+        };
+        return Drydock {
+            dockerfile,
+            dockerignore: generate_dockerignore(&StdFileSystem::new(), root, stacks),
+            ci_files: options
+                .targets
+                .iter()
+                .map(|target| (target.path().to_string(), fallback(*target)))
+                .collect(),
+            jobs: vec![fallback_job(notebook)],
+        };
+    }
+    let single = matches!(stacks, [stack] if stack.path == ROOT_PROJECT);
+    let jobs: Vec<Job> = stacks
+        .iter()
+        .map(|stack| {
+            let recipe = recipe(stack, options);
+            let name = if single {
+                recipe.stage.to_string()
+            } else {
+                job_name(stack)
+            };
+            Job {
+                name,
+                stack,
+                recipe,
+            }
+        })
+        .collect();
+    let mut dockerfile = if single {
+        stage_dockerfile(&jobs[0].recipe, None)
+    } else {
+        multi_stage_dockerfile(&jobs)
+    };
+    if dockerfile.contains("--mount=type=cache") {
+        dockerfile.insert_str(0, &format!("{DOCKERFILE_SYNTAX}\n"));
+    }
+    let ci_files = options
+        .targets
+        .iter()
+        .map(|target| {
+            let contents = match target {
+                CiTarget::GitLab => gitlab_ci(&jobs),
+                CiTarget::GitHub => github_workflow(&jobs),
+            };
+            (target.path().to_string(), contents)
+        })
+        .collect();
+    Drydock {
+        dockerfile,
+        dockerignore: generate_dockerignore(&StdFileSystem::new(), root, stacks),
+        ci_files,
+        jobs: jobs
+            .iter()
+            .map(|job| DrydockJob {
+                name: job.name.clone(),
+                path: job.stack.path.clone(),
+                image: job.recipe.image.clone(),
+                packages: job.recipe.packages.iter().map(|p| p.to_string()).collect(),
+                commands: job.recipe.commands().cloned().collect(),
+            })
+            .collect(),
+    }
+}
+
+/// Build a `.dockerignore` for the repository or project at `root`.
+///
+/// Patterns come from the `.gitignore` at `root` and in each stack
+/// directory, rewritten to Docker's root-relative syntax (`dist/` in
+/// `web/.gitignore` becomes `web/**/dist`), followed by the dependency and
+/// build directories of every detected stack. `.git` is always excluded.
+pub fn generate_dockerignore<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    stacks: &[DetectedStack],
+) -> String {
+    let mut output =
+        String::from("# Generated by ShipShape from .gitignore and the detected stacks.\n.git\n");
+    let mut seen: BTreeSet<String> = BTreeSet::from([".git".to_string()]);
+    let mut section = |output: &mut String, title: &str, patterns: Vec<String>| {
+        let patterns: Vec<String> = patterns
+            .into_iter()
+            .filter(|pattern| seen.insert(pattern.clone()))
+            .collect();
+        if patterns.is_empty() {
+            return;
+        }
+        let _ = writeln!(output, "\n# {title}");
+        for pattern in patterns {
+            let _ = writeln!(output, "{pattern}");
+        }
+    };
+    let dirs: BTreeSet<&str> = std::iter::once(ROOT_PROJECT)
+        .chain(stacks.iter().map(|stack| stack.path.as_str()))
+        .collect();
+    for dir in dirs {
+        let path = in_dir(dir, ".gitignore");
+        let Ok(contents) = fs.read_to_string(&root.join(&path)) else {
+            continue;
+        };
+        let patterns = contents
+            .lines()
+            .filter_map(|line| docker_pattern(dir, line))
+            .collect();
+        section(&mut output, &path, patterns);
+    }
+    for stack in stacks {
+        let patterns = match stack.kind {
+            ProjectKind::Python => [
+                "__pycache__",
+                "*.py[cod]",
+                ".venv",
+                "venv",
+                ".pytest_cache",
+                ".tox",
+                ".nox",
+                "*.egg-info",
+            ]
+            .iter()
+            .map(|name| format!("**/{name}"))
+            .collect(),
+            ProjectKind::Node => [
+                "node_modules",
+                ".pnpm-store",
+                "npm-debug.log*",
+                "yarn-error.log*",
+            ]
+            .iter()
+            .map(|name| format!("**/{name}"))
+            .collect(),
+            ProjectKind::Rust => vec![in_dir(&stack.path, "target")],
+            ProjectKind::CMake => vec![in_dir(&stack.path, "build")],
+            ProjectKind::Go => Vec::new(),
+        };
+        section(&mut output, stack.kind.name(), patterns);
     }
-    if has_file(path, "Cargo.toml") {
-        return Some(Language::Rust);
+    output
+}
+
+/// A `.gitignore` line of the directory `dir` as a `.dockerignore` pattern.
+///
+/// Patterns without an inner slash match at any depth below `dir`; the rest
+/// are anchored to it. Trailing slashes are dropped, since Docker matches
+/// files and directories alike.
+fn docker_pattern(dir: &str, line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
     }
-    if has_file(path, "go.mod") {
-        return Some(Language::Go);
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(pattern) => (true, pattern),
+        None => (false, line),
+    };
+    let pattern = pattern.trim_end_matches('/');
+    if pattern.is_empty() {
+        return None;
     }
-    if has_file(path, "CMakeLists.txt") {
-        return Some(Language::CMake);
+    let pattern = if pattern.contains('/') {
+        in_dir(dir, pattern.trim_start_matches('/'))
+    } else {
+        in_dir(dir, &format!("**/{pattern}"))
+    };
+    Some(if negated {
+        format!("!{pattern}")
+    } else {
+        pattern
+    })
+}
+
+fn job_name(stack: &DetectedStack) -> String {
+    if stack.path == ROOT_PROJECT {
+        return stack.kind.name().to_string();
     }
-    None
+    let slug: String = stack
+        .path
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}-{}", stack.kind.name(), slug.trim_matches('-'))
 }
 
-fn has_file(root: &Path, name: &str) -> bool {
-    root.join(name).is_file()
+/// Dockerfile lines for one stack; `stage` names it and builds it from a
+/// subdirectory inside a multi-stage Dockerfile.
+///
+/// A dependency stage creates [`DOCKER_USER`], installs the package manager
+/// and, where the stack allows it, its dependencies from the manifests alone,
+/// so source edits keep that layer cached. The stage built on top copies the
+/// source and runs the rest as the unprivileged user.
+fn stage_dockerfile(recipe: &Recipe, stage: Option<(&str, &str)>) -> String {
+    let path = stage.map(|(_, path)| path).unwrap_or(ROOT_PROJECT);
+    let deps = match stage {
+        Some((name, _)) => format!("{name}-deps"),
+        None => "deps".to_string(),
+    };
+    let workdir = match path {
+        ROOT_PROJECT => "/app".to_string(),
+        path => format!("/app/{path}"),
+    };
+    let mut lines = vec![format!("FROM {} AS {deps}", recipe.image)];
+    if !recipe.packages.is_empty() {
+        lines.push(format!(
+            "RUN apt-get update && apt-get install -y --no-install-recommends {} && rm -rf /var/lib/apt/lists/*",
+            recipe.packages.join(" ")
+        ));
+    }
+    lines.push(format!(
+        "RUN useradd --create-home --uid {DOCKER_UID} {DOCKER_USER} 2>/dev/null || adduser -D -u {DOCKER_UID} {DOCKER_USER}"
+    ));
+    lines.extend(
+        recipe
+            .env
+            .iter()
+            .map(|(variable, value)| format!("ENV {variable}={value}")),
+    );
+    lines.push(format!("WORKDIR {workdir}"));
+    let mut owned = vec!["/app".to_string()];
+    if workdir != "/app" {
+        owned.push(workdir.clone());
+    }
+    if stage.is_some() {
+        owned.push("/shipshape".to_string());
+        lines.push(format!(
+            "RUN mkdir -p /shipshape && chown {DOCKER_USER} {}",
+            owned.join(" ")
+        ));
+    } else {
+        lines.push(format!("RUN chown {DOCKER_USER} {}", owned.join(" ")));
+    }
+    if let Some(setup) = &recipe.setup {
+        lines.push(format!("RUN {setup}"));
+    }
+    lines.push(format!("USER {DOCKER_USER}"));
+    let run = cache_mounts(recipe, path);
+    let remaining = match &recipe.layer {
+        Some(layer) => {
+            lines.push(format!(
+                "COPY --chown={DOCKER_USER}:{DOCKER_USER} {} ./",
+                layer.files.join(" ")
+            ));
+            lines.extend(layer.install.iter().map(|step| format!("{run}{step}")));
+            &layer.finish
+        }
+        None => &recipe.install,
+    };
+    lines.push(String::new());
+    match stage {
+        Some((name, _)) => lines.push(format!("FROM {deps} AS {name}")),
+        None => lines.push(format!("FROM {deps}")),
+    }
+    lines.push(format!("COPY --chown={DOCKER_USER}:{DOCKER_USER} . /app/"));
+    lines.extend(remaining.iter().map(|step| format!("{run}{step}")));
+    match (stage, &recipe.test) {
+        (Some((name, _)), Some(test)) => {
+            lines.push(format!("{run}{test} && touch /shipshape/{name}.passed"))
+        }
+        (Some((name, _)), None) => lines.push(format!("RUN touch /shipshape/{name}.passed")),
+        (None, Some(test)) if recipe.test_in_build => lines.push(format!("{run}{test}")),
+        (None, _) => {}
+    }
+    if recipe.test_as_cmd
+        && let Some(test) = &recipe.test
+    {
+        let args: Vec<String> = test
+            .split_whitespace()
+            .map(|arg| format!("\"{arg}\""))
+            .collect();
+        lines.push(format!("CMD [{}]", args.join(", ")));
+    }
+    lines.push(String::new());
+    lines.join("\n")
 }
 
-fn detect_notebook_only(root: &Path) -> bool {
-    if has_python_packaging(root) {
-        return false;
+/// `RUN` with a BuildKit cache mount, owned by [`DOCKER_USER`], per cache
+/// directory of the stack built from `path`.
+fn cache_mounts(recipe: &Recipe, path: &str) -> String {
+    let mut run = String::from("RUN ");
+    let Some(cache) = &recipe.cache else {
+        return run;
+    };
+    let build_dirs = cache
+        .build_dirs
+        .iter()
+        .map(|dir| format!("/app/{}", in_dir(path, dir)));
+    let mounts = cache
+        .mounts
+        .iter()
+        .map(|mount| match mount.strip_prefix("~/") {
+            Some(rest) => format!("{DOCKER_HOME}/{rest}"),
+            None => mount.to_string(),
+        });
+    for target in mounts.chain(build_dirs) {
+        let _ = write!(run, "--mount=type=cache,target={target},uid={DOCKER_UID} ");
+    }
+    run
+}
+
+fn multi_stage_dockerfile(jobs: &[Job]) -> String {
+    let mut output = String::from(
+        "# One stage per detected stack; build one with `docker build --target <stage> .`\n",
+    );
+    for job in jobs {
+        output.push_str(&stage_dockerfile(
+            &job.recipe,
+            Some((&job.name, &job.stack.path)),
+        ));
+        output.push('\n');
+    }
+    output.push_str("FROM scratch AS all\n");
+    for job in jobs {
+        let _ = writeln!(output, "COPY --from={} /shipshape/ /shipshape/", job.name);
+    }
+    output
+}
+
+fn gitlab_ci(jobs: &[Job]) -> String {
+    let stages: Vec<&str> = STAGES
+        .iter()
+        .copied()
+        .filter(|stage| jobs.iter().any(|job| job.recipe.stage == *stage))
+        .collect();
+    let mut output = String::from("stages:\n");
+    for stage in stages {
+        let _ = writeln!(output, "  - {stage}");
+    }
+    for Job {
+        name,
+        stack,
+        recipe,
+    } in jobs
+    {
+        let _ = writeln!(output, "\n{name}:");
+        let _ = writeln!(output, "  stage: {}", recipe.stage);
+        let _ = writeln!(output, "  image: {}", recipe.image);
+        if let Some(cache) = &recipe.cache {
+            append_gitlab_cache(&mut output, name, &stack.path, cache);
+        }
+        let _ = writeln!(output, "  script:");
+        if stack.path != ROOT_PROJECT {
+            let _ = writeln!(output, "    - cd {}", stack.path);
+        }
+        if !recipe.packages.is_empty() {
+            let _ = writeln!(output, "    - apt-get update");
+            let _ = writeln!(
+                output,
+                "    - apt-get install -y {}",
+                recipe.packages.join(" ")
+            );
+        }
+        for step in recipe.commands() {
+            let _ = writeln!(output, "    - {step}");
+        }
+    }
+    output
+}
+
+/// `variables:` pointing tools at cache directories, and a `cache:` block
+/// keyed on the stack's lockfiles.
+fn append_gitlab_cache(output: &mut String, name: &str, path: &str, cache: &Cache) {
+    if !cache.variables.is_empty() {
+        let _ = writeln!(output, "  variables:");
+        for (variable, dir) in cache.variables {
+            let _ = writeln!(output, "    {variable}: $CI_PROJECT_DIR/{dir}");
+        }
+    }
+    let _ = writeln!(output, "  cache:");
+    let _ = writeln!(output, "    key:");
+    let _ = writeln!(output, "      files:");
+    for lockfile in &cache.lockfiles {
+        let _ = writeln!(output, "        - {lockfile}");
+    }
+    let _ = writeln!(output, "      prefix: {name}");
+    let _ = writeln!(output, "    paths:");
+    for (_, dir) in cache.variables {
+        let _ = writeln!(output, "      - {dir}");
+    }
+    for dir in cache.build_dirs {
+        let _ = writeln!(output, "      - {}", in_dir(path, dir));
+    }
+}
+
+/// A GitHub Actions workflow running each job on a hosted runner, with the
+/// stack's setup action pinned to its toolchain version and, unless caching
+/// is off, dependency caching enabled.
+fn github_workflow(jobs: &[Job]) -> String {
+    let mut output = String::from(GITHUB_HEADER);
+    for (index, job) in jobs.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        let _ = writeln!(output, "  {}:", job.name);
+        let _ = writeln!(output, "    runs-on: ubuntu-latest");
+        if job.stack.path != ROOT_PROJECT {
+            let _ = writeln!(output, "    defaults:");
+            let _ = writeln!(output, "      run:");
+            let _ = writeln!(output, "        working-directory: {}", job.stack.path);
+        }
+        let _ = writeln!(output, "    steps:");
+        let _ = writeln!(output, "      - uses: actions/checkout@v4");
+        for line in github_setup(job) {
+            let _ = writeln!(output, "      {line}");
+        }
+        let recipe = &job.recipe;
+        if !recipe.packages.is_empty() {
+            let _ = writeln!(
+                output,
+                "      - run: sudo apt-get update && sudo apt-get install -y {}",
+                recipe.packages.join(" ")
+            );
+        }
+        let setup = recipe
+            .setup
+            .iter()
+            .filter(|_| !github_installs_manager(job));
+        for step in setup.chain(&recipe.install).chain(recipe.test.as_ref()) {
+            let _ = writeln!(output, "      - run: {step}");
+        }
+    }
+    output
+}
+
+/// Whether [`github_setup`] installs the job's package manager with an
+/// action, replacing the recipe's own setup command.
+fn github_installs_manager(job: &Job) -> bool {
+    matches!(
+        job.stack.package_manager.as_ref().map(|d| d.manager),
+        Some(PackageManager::Poetry | PackageManager::Uv | PackageManager::Pnpm)
+    )
+}
+
+/// Setup and cache steps for a job, as lines relative to the step list.
+fn github_setup(job: &Job) -> Vec<String> {
+    let path = &job.stack.path;
+    let version = &job.recipe.version;
+    let cached = job.recipe.cache.is_some();
+    let detected = job.stack.package_manager.as_ref();
+    let manager = detected.map(|d| d.manager);
+    let lockfile = detected.and_then(|d| d.lockfile.clone());
+    let action = |uses: &str, inputs: &[(&str, String)]| {
+        let mut lines = vec![format!("- uses: {uses}")];
+        if !inputs.is_empty() {
+            lines.push("  with:".to_string());
+            lines.extend(
+                inputs
+                    .iter()
+                    .map(|(key, value)| format!("    {key}: {value}")),
+            );
+        }
+        lines
+    };
+    match job.stack.kind {
+        ProjectKind::Python => {
+            let mut lines = Vec::new();
+            if manager == Some(PackageManager::Poetry) {
+                lines.push("- run: pipx install poetry".to_string());
+            }
+            let mut inputs = vec![("python-version", format!("\"{version}\""))];
+            let dependency_path = match (manager, lockfile.clone()) {
+                (Some(PackageManager::Pip) | None, None) => {
+                    let source = detected.map_or("pyproject.toml", |d| d.source.as_str());
+                    Some(in_dir(path, source.rsplit('/').next().unwrap_or(source)))
+                }
+                (Some(PackageManager::Uv), _) => None,
+                (_, lockfile) => lockfile,
+            };
+            if cached && let Some(dependency_path) = dependency_path {
+                let cache = manager.unwrap_or(PackageManager::Pip).name().to_string();
+                inputs.push(("cache", cache));
+                inputs.push(("cache-dependency-path", dependency_path));
+            }
+            lines.extend(action("actions/setup-python@v5", &inputs));
+            if manager == Some(PackageManager::Uv) {
+                let mut inputs = Vec::new();
+                if cached {
+                    inputs.push(("enable-cache", "true".to_string()));
+                    inputs.push((
+                        "cache-dependency-glob",
+                        lockfile.unwrap_or_else(|| in_dir(path, "pyproject.toml")),
+                    ));
+                }
+                lines.extend(action("astral-sh/setup-uv@v5", &inputs));
+            }
+            lines
+        }
+        ProjectKind::Node => {
+            let mut lines = Vec::new();
+            if manager == Some(PackageManager::Pnpm) {
+                let pinned = detected.is_some_and(|d| d.version.is_some());
+                let inputs = if pinned {
+                    Vec::new()
+                } else {
+                    vec![("version", "latest".to_string())]
+                };
+                lines.extend(action("pnpm/action-setup@v4", &inputs));
+            }
+            let mut inputs = vec![("node-version", format!("\"{version}\""))];
+            if cached && let Some(lockfile) = lockfile {
+                let cache = manager.unwrap_or(PackageManager::Npm).name().to_string();
+                inputs.push(("cache", cache));
+                inputs.push(("cache-dependency-path", lockfile));
+            }
+            lines.extend(action("actions/setup-node@v4", &inputs));
+            lines
+        }
+        ProjectKind::Rust => {
+            let mut lines = action(
+                "dtolnay/rust-toolchain@master",
+                &[("toolchain", format!("\"{version}\""))],
+            );
+            if cached {
+                let workspaces = if path == ROOT_PROJECT {
+                    Vec::new()
+                } else {
+                    vec![("workspaces", path.clone())]
+                };
+                lines.extend(action("Swatinem/rust-cache@v2", &workspaces));
+            }
+            lines
+        }
+        ProjectKind::Go => {
+            let cache = if cached {
+                ("cache-dependency-path", in_dir(path, "go.sum"))
+            } else {
+                ("cache", "false".to_string())
+            };
+            action(
+                "actions/setup-go@v5",
+                &[("go-version", format!("\"{version}\"")), cache],
+            )
+        }
+        ProjectKind::CMake => Vec::new(),
     }
-    contains_notebook(root)
 }
 
 fn contains_notebook(root: &Path) -> bool {
@@ -98,150 +1245,6 @@ fn is_notebook(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn has_python_packaging(root: &Path) -> bool {
-    ["pyproject.toml", "setup.py", "setup.cfg"]
-        .iter()
-        .any(|name| root.join(name).is_file())
-}
-
-fn python_dockerfile() -> String {
-    [
-        "FROM python:3.11-slim",
-        "WORKDIR /app",
-        "COPY . .",
-        "RUN python -m pip install --upgrade pip",
-        "RUN python -m pip install .[test]",
-        "CMD [\"pytest\", \"-q\"]",
-        "",
-    ]
-    .join("\n")
-}
-
-fn python_ci() -> String {
-    [
-        "stages:",
-        "  - test",
-        "",
-        "test:",
-        "  image: python:3.11-slim",
-        "  script:",
-        "    - python -m pip install --upgrade pip",
-        "    - python -m pip install .[test]",
-        "    - pytest -q",
-        "",
-    ]
-    .join("\n")
-}
-
-fn node_dockerfile() -> String {
-    [
-        "FROM node:20-alpine",
-        "WORKDIR /app",
-        "COPY . .",
-        "RUN npm ci",
-        "CMD [\"npm\", \"test\"]",
-        "",
-    ]
-    .join("\n")
-}
-
-fn node_ci() -> String {
-    [
-        "stages:",
-        "  - test",
-        "",
-        "test:",
-        "  image: node:20-alpine",
-        "  script:",
-        "    - npm ci",
-        "    - npm test",
-        "",
-    ]
-    .join("\n")
-}
-
-fn rust_dockerfile() -> String {
-    [
-        "FROM rust:1.76",
-        "WORKDIR /app",
-        "COPY . .",
-        "RUN cargo test --all",
-        "CMD [\"cargo\", \"test\", \"--all\"]",
-        "",
-    ]
-    .join("\n")
-}
-
-fn rust_ci() -> String {
-    [
-        "stages:",
-        "  - test",
-        "",
-        "test:",
-        "  image: rust:1.76",
-        "  script:",
-        "    - cargo test --all",
-        "",
-    ]
-    .join("\n")
-}
-
-fn go_dockerfile() -> String {
-    [
-        "FROM golang:1.21",
-        "WORKDIR /app",
-        "COPY . .",
-        "RUN go test ./...",
-        "CMD [\"go\", \"test\", \"./...\"]",
-        "",
-    ]
-    .join("\n")
-}
-
-fn go_ci() -> String {
-    [
-        "stages:",
-        "  - test",
-        "",
-        "test:",
-        "  image: golang:1.21",
-        "  script:",
-        "    - go test ./...",
-        "",
-    ]
-    .join("\n")
-}
-
-fn cmake_dockerfile() -> String {
-    [
-        "FROM ubuntu:22.04",
-        "WORKDIR /app",
-        "COPY . .",
-        "RUN apt-get update && apt-get install -y cmake build-essential",
-        "RUN cmake -S . -B build",
-        "RUN cmake --build build",
-        "",
-    ]
-    .join("\n")
-}
-
-fn cmake_ci() -> String {
-    [
-        "stages:",
-        "  - build",
-        "",
-        "build:",
-        "  image: ubuntu:22.04",
-        "  script:",
-        "    - apt-get update",
-        "    - apt-get install -y cmake build-essential",
-        "    - cmake -S . -B build",
-        "    - cmake --build build",
-        "",
-    ]
-    .join("\n")
-}
-
 fn notebook_only_dockerfile() -> String {
     [
         "FROM ubuntu:22.04",
@@ -253,19 +1256,58 @@ fn notebook_only_dockerfile() -> String {
     .join("\n")
 }
 
-fn notebook_only_ci() -> String {
-    [
-        "stages:",
-        "  - validate",
-        "",
-        "validate:",
-        "  image: ubuntu:22.04",
-        "  script:",
-        "    - echo \"Notebook-only repository detected; add packaging metadata.\"",
-        "    - exit 1",
-        "",
-    ]
-    .join("\n")
+fn notebook_only_ci(target: CiTarget) -> String {
+    match target {
+        CiTarget::GitLab => [
+            "stages:",
+            "  - validate",
+            "",
+            "validate:",
+            "  image: ubuntu:22.04",
+            "  script:",
+            "    - echo \"Notebook-only repository detected; add packaging metadata.\"",
+            "    - exit 1",
+            "",
+        ]
+        .join("\n"),
+        CiTarget::GitHub => format!(
+            "{GITHUB_HEADER}{}",
+            [
+                "  validate:",
+                "    runs-on: ubuntu-latest",
+                "    steps:",
+                "      - run: echo \"Notebook-only repository detected; add packaging metadata.\"",
+                "      - run: exit 1",
+                "",
+            ]
+            .join("\n")
+        ),
+    }
+}
+
+/// The job the notebook-only or generic pipeline runs.
+fn fallback_job(notebook: bool) -> DrydockJob {
+    let (name, commands) = if notebook {
+        (
+            "validate",
+            vec![
+                "echo \"Notebook-only repository detected; add packaging metadata.\"".to_string(),
+                "exit 1".to_string(),
+            ],
+        )
+    } else {
+        (
+            "test",
+            vec!["echo \"No build markers detected.\"".to_string()],
+        )
+    };
+    DrydockJob {
+        name: name.to_string(),
+        path: ROOT_PROJECT.to_string(),
+        image: "ubuntu:22.04".to_string(),
+        packages: Vec::new(),
+        commands,
+    }
 }
 
 fn generic_dockerfile() -> String {
@@ -279,23 +1321,43 @@ fn generic_dockerfile() -> String {
     .join("\n")
 }
 
-fn generic_ci() -> String {
-    [
-        "stages:",
-        "  - test",
-        "",
-        "test:",
-        "  image: ubuntu:22.04",
-        "  script:",
-        "    - echo \"No build markers detected.\"",
-        "",
-    ]
-    .join("\n")
+fn generic_ci(target: CiTarget) -> String {
+    match target {
+        CiTarget::GitLab => [
+            "stages:",
+            "  - test",
+            "",
+            "test:",
+            "  image: ubuntu:22.04",
+            "  script:",
+            "    - echo \"No build markers detected.\"",
+            "",
+        ]
+        .join("\n"),
+        CiTarget::GitHub => format!(
+            "{GITHUB_HEADER}{}",
+            [
+                "  test:",
+                "    runs-on: ubuntu-latest",
+                "    steps:",
+                "      - uses: actions/checkout@v4",
+                "      - run: echo \"No build markers detected.\"",
+                "",
+            ]
+            .join("\n")
+        ),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::generate_ci_config;
+    use super::{
+        CiTarget, DrydockOptions, detect_stacks, generate_ci_config, generate_dockerignore,
+        generate_drydock,
+    };
+    use crate::domain::ProjectKind;
+    use crate::fs::{MemoryFileSystem, StdFileSystem};
+    use crate::toolchain::ToolchainDefaults;
     use std::path::{Path, PathBuf};
     use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -307,7 +1369,7 @@ mod tests {
         let (dockerfile, ci) = generate_ci_config(&root);
 
         assert!(dockerfile.contains("python:3.11-slim"));
-        assert!(ci.contains("pytest -q"));
+        assert!(ci.contains("python -m unittest discover"));
 
         cleanup_dir(&root);
     }
@@ -318,7 +1380,8 @@ mod tests {
         let (dockerfile, ci) = generate_ci_config(&root);
 
         assert!(dockerfile.contains("node:20-alpine"));
-        assert!(ci.contains("npm test"));
+        assert!(ci.contains("npm install"));
+        assert!(!ci.contains("npm test"));
 
         cleanup_dir(&root);
     }
@@ -374,7 +1437,7 @@ mod tests {
         let (dockerfile, ci) = generate_ci_config(&root);
 
         assert!(dockerfile.contains("python:3.11-slim"));
-        assert!(ci.contains("pytest -q"));
+        assert!(ci.contains("python -m unittest discover"));
 
         cleanup_dir(&root);
     }
@@ -390,6 +1453,334 @@ mod tests {
         cleanup_dir(&root);
     }
 
+    #[test]
+    fn generates_one_job_per_stack() {
+        let root = temp_dir_with_marker("Cargo.toml");
+        std::fs::write(root.join("package.json"), "{}").expect("write package.json");
+
+        let stacks = detect_stacks(&StdFileSystem::new(), &root).expect("detect");
+        let summary: Vec<(ProjectKind, &str, Vec<&str>)> = stacks
+            .iter()
+            .map(|stack| {
+                let evidence = stack.evidence.iter().map(String::as_str).collect();
+                (stack.kind, stack.path.as_str(), evidence)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ProjectKind::Node, ".", vec!["package.json"]),
+                (ProjectKind::Rust, ".", vec!["Cargo.toml"]),
+            ]
+        );
+
+        let (dockerfile, ci) = generate_ci_config(&root);
+        assert!(ci.contains("node:\n  stage: test\n  image: node:20-alpine"));
+        assert!(ci.contains("rust:\n  stage: test\n  image: rust:1.76"));
+        assert!(dockerfile.contains("FROM node:20-alpine AS node"));
+        assert!(dockerfile.contains("FROM rust:1.76 AS rust"));
+        assert!(dockerfile.contains("COPY --from=rust /shipshape/ /shipshape/"));
+
+        cleanup_dir(&root);
+    }
+
+    #[test]
+    fn monorepo_jobs_run_in_project_directories() {
+        let root = temp_dir_with_marker("Cargo.toml");
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        )
+        .expect("write workspace");
+        for (path, contents) in [
+            ("crates/api/Cargo.toml", "[package]\nname = \"api\"\n"),
+            ("crates/cli/Cargo.toml", "[package]\nname = \"cli\"\n"),
+            ("web/package.json", "{\"name\": \"web\"}"),
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().expect("parent")).expect("create dir");
+            std::fs::write(path, contents).expect("write manifest");
+        }
+
+        let stacks = detect_stacks(&StdFileSystem::new(), &root).expect("detect");
+        assert_eq!(stacks.len(), 2);
+        assert_eq!(
+            stacks[0].evidence,
+            vec![
+                "Cargo.toml",
+                "crates/api/Cargo.toml",
+                "crates/cli/Cargo.toml"
+            ]
+        );
+
+        let (dockerfile, ci) = generate_ci_config(&root);
+        assert!(ci.contains("rust:\n  stage: test\n  image: rust:1.76\n"));
+        assert!(ci.contains("  script:\n    - cargo test --all\n"));
+        assert!(ci.contains("node-web:\n  stage: test\n  image: node:20-alpine\n"));
+        assert!(ci.contains("  script:\n    - cd web\n    - npm install\n"));
+        assert!(dockerfile.contains(
+            "WORKDIR /app/web\nRUN mkdir -p /shipshape && chown shipshape /app /app/web /shipshape\nUSER shipshape\nCOPY --chown=shipshape:shipshape web/package.json ./\n"
+        ));
+        assert!(dockerfile.contains(
+            "FROM node-web-deps AS node-web\nCOPY --chown=shipshape:shipshape . /app/\n"
+        ));
+
+        cleanup_dir(&root);
+    }
+
+    #[test]
+    fn images_follow_pinned_toolchains() {
+        let root = temp_dir_with_marker("Cargo.toml");
+        std::fs::write(
+            root.join("rust-toolchain.toml"),
+            "[toolchain]\nchannel = \"1.80.0\"\n",
+        )
+        .expect("write toolchain");
+        std::fs::write(
+            root.join("package.json"),
+            "{\"engines\": {\"node\": \">=18 <20\"}}",
+        )
+        .expect("write package.json");
+        std::fs::write(root.join("go.mod"), "module demo\n").expect("write go.mod");
+
+        let stacks = detect_stacks(&StdFileSystem::new(), &root).expect("detect");
+        let options = DrydockOptions {
+            toolchains: ToolchainDefaults::from_toml_str("[versions]\ngo = \"1.23\"\n")
+                .expect("defaults"),
+            ..DrydockOptions::default()
+        };
+        let drydock = generate_drydock(&root, &stacks, &options);
+        let (dockerfile, ci) = (&drydock.dockerfile, &drydock.ci_files[".gitlab-ci.yml"]);
+
+        assert!(dockerfile.contains("FROM rust:1.80.0 AS rust"));
+        assert!(dockerfile.contains("FROM node:18-alpine AS node"));
+        assert!(dockerfile.contains("FROM golang:1.23 AS go"));
+        assert!(ci.contains("go:\n  stage: test\n  image: golang:1.23"));
+
+        cleanup_dir(&root);
+    }
+
+    #[test]
+    fn renders_github_actions_workflows() {
+        let root = temp_dir_with_marker("pyproject.toml");
+        std::fs::write(root.join(".python-version"), "3.10\n").expect("write pin");
+        std::fs::create_dir_all(root.join("web")).expect("create web");
+        std::fs::write(root.join("web/package.json"), "{}").expect("write package.json");
+
+        let stacks = detect_stacks(&StdFileSystem::new(), &root).expect("detect");
+        let drydock = generate_drydock(&root, &stacks, &DrydockOptions::default());
+
+        assert_eq!(
+            drydock.ci_files.keys().collect::<Vec<_>>(),
+            vec![".github/workflows/shipshape.yml", ".gitlab-ci.yml"]
+        );
+        let workflow = &drydock.ci_files[".github/workflows/shipshape.yml"];
+        assert!(
+            workflow.starts_with(
+                "name: ShipShape\n\non:\n  push:\n  pull_request:\n\njobs:\n  python:\n"
+            )
+        );
+        assert!(workflow.contains(
+            "      - uses: actions/setup-python@v5\n        with:\n          python-version: \"3.10\"\n          cache: pip\n          cache-dependency-path: pyproject.toml\n"
+        ));
+        assert!(workflow.contains(
+            "  node-web:\n    runs-on: ubuntu-latest\n    defaults:\n      run:\n        working-directory: web\n"
+        ));
+        assert!(workflow.contains("node-version: \"20\"\n      - run: npm install\n"));
+        assert_eq!("github".parse::<CiTarget>().ok(), Some(CiTarget::GitHub));
+        assert!("jenkins".parse::<CiTarget>().is_err());
+
+        let github = DrydockOptions {
+            targets: vec![CiTarget::GitHub],
+            ..DrydockOptions::default()
+        };
+        let empty = generate_drydock(Path::new("/nonexistent"), &[], &github);
+        assert!(
+            empty.ci_files[".github/workflows/shipshape.yml"].contains("No build markers detected")
+        );
+
+        cleanup_dir(&root);
+    }
+
+    #[test]
+    fn caches_dependencies_unless_the_repo_opts_out() {
+        let root = temp_dir_with_marker("Cargo.toml");
+        std::fs::create_dir_all(root.join("web")).expect("create web");
+        std::fs::write(root.join("web/package.json"), "{}").expect("write package.json");
+        let fs = StdFileSystem::new();
+        let stacks = detect_stacks(&fs, &root).expect("detect");
+
+        let drydock = generate_drydock(&root, &stacks, &DrydockOptions::default());
+        let gitlab = &drydock.ci_files[".gitlab-ci.yml"];
+        assert!(gitlab.contains(
+            "node-web:\n  stage: test\n  image: node:20-alpine\n  variables:\n    npm_config_cache: $CI_PROJECT_DIR/.npm\n  cache:\n    key:\n      files:\n        - web/package.json\n      prefix: node-web\n    paths:\n      - .npm\n  script:\n"
+        ));
+        assert!(gitlab.contains("    paths:\n      - .cargo\n      - target\n"));
+        assert!(
+            drydock
+                .dockerfile
+                .starts_with("# syntax=docker/dockerfile:1\n")
+        );
+        assert!(drydock.dockerfile.contains(
+            "RUN --mount=type=cache,target=/home/shipshape/.npm,uid=10001 npm install\n"
+        ));
+        assert!(drydock.dockerfile.contains(
+            "RUN --mount=type=cache,target=/usr/local/cargo/registry,uid=10001 --mount=type=cache,target=/usr/local/cargo/git,uid=10001 --mount=type=cache,target=/app/target,uid=10001 cargo test --all && "
+        ));
+        let workflow = &drydock.ci_files[".github/workflows/shipshape.yml"];
+        assert!(workflow.contains("Swatinem/rust-cache@v2"));
+
+        std::fs::write(root.join(".shipshape.toml"), "[drydock]\ncache = false\n")
+            .expect("write config");
+        let options = DrydockOptions::default()
+            .for_repo(&fs, &root)
+            .expect("repo options");
+        assert!(!options.cache);
+        let drydock = generate_drydock(&root, &stacks, &options);
+        assert!(!drydock.ci_files[".gitlab-ci.yml"].contains("cache:"));
+        assert!(!drydock.dockerfile.contains("--mount"));
+        let workflow = &drydock.ci_files[".github/workflows/shipshape.yml"];
+        assert!(!workflow.contains("cache"));
+
+        std::fs::write(root.join(".shipshape.toml"), "[drydock]\ncache = \"no\"\n")
+            .expect("write config");
+        assert!(DrydockOptions::default().for_repo(&fs, &root).is_err());
+
+        cleanup_dir(&root);
+    }
+
+    #[test]
+    fn installs_and_tests_with_the_detected_tools() {
+        let fs = MemoryFileSystem::new()
+            .with_file(
+                "/repo/package.json",
+                "{\"packageManager\": \"pnpm@9.1.0\", \"workspaces\": [\"web\"]}",
+            )
+            .with_file("/repo/pnpm-lock.yaml", "lockfileVersion: '9.0'\n")
+            .with_file(
+                "/repo/web/package.json",
+                "{\"scripts\": {\"test\": \"vitest run\"}}",
+            )
+            .with_file(
+                "/repo/api/pyproject.toml",
+                "[tool.poetry]\nname = \"api\"\n\n[tool.pytest.ini_options]\n",
+            )
+            .with_file("/repo/api/poetry.lock", "")
+            .with_file("/repo/tools/uv.lock", "")
+            .with_file(
+                "/repo/tools/pyproject.toml",
+                "[project]\nname = \"tools\"\n",
+            )
+            .with_file("/repo/tools/tox.ini", "[tox]\n");
+        let root = Path::new("/repo");
+        let stacks = detect_stacks(&fs, root).expect("detect");
+        let drydock = generate_drydock(root, &stacks, &DrydockOptions::default());
+        let gitlab = &drydock.ci_files[".gitlab-ci.yml"];
+
+        assert!(gitlab.contains(
+            "    - cd api\n    - python -m pip install poetry\n    - poetry install\n    - poetry run pytest -q\n"
+        ));
+        assert!(gitlab.contains(
+            "    - cd tools\n    - python -m pip install uv\n    - uv sync --locked\n    - python -m pip install tox\n    - tox -e py\n"
+        ));
+        assert!(gitlab.contains(
+            "    - cd web\n    - corepack enable\n    - pnpm install --frozen-lockfile\n    - pnpm test\n"
+        ));
+        assert!(
+            gitlab.contains("      files:\n        - pnpm-lock.yaml\n      prefix: node-web\n")
+        );
+        assert!(gitlab.contains("node:\n  stage: test\n  image: node:20-alpine\n"));
+        assert!(gitlab.contains("    - corepack enable\n    - pnpm install --frozen-lockfile\n\n"));
+
+        let workflow = &drydock.ci_files[".github/workflows/shipshape.yml"];
+        assert!(workflow.contains(
+            "      - run: pipx install poetry\n      - uses: actions/setup-python@v5\n        with:\n          python-version: \"3.11\"\n          cache: poetry\n          cache-dependency-path: api/poetry.lock\n      - run: poetry install\n"
+        ));
+        assert!(workflow.contains(
+            "      - uses: astral-sh/setup-uv@v5\n        with:\n          enable-cache: true\n          cache-dependency-glob: tools/uv.lock\n      - run: uv sync --locked\n"
+        ));
+        assert!(workflow.contains(
+            "      - uses: pnpm/action-setup@v4\n      - uses: actions/setup-node@v4\n        with:\n          node-version: \"20\"\n          cache: pnpm\n          cache-dependency-path: pnpm-lock.yaml\n      - run: pnpm install --frozen-lockfile\n      - run: pnpm test\n"
+        ));
+        assert!(!workflow.contains("corepack enable\n      - run: pnpm"));
+    }
+
+    #[test]
+    fn dockerfiles_install_dependencies_first_as_an_unprivileged_user() {
+        let fs = MemoryFileSystem::new()
+            .with_file("/repo/requirements.txt", "flask\npytest\n")
+            .with_file("/repo/.gitignore", "# build\n/dist/\n*.log\n!keep.log\n")
+            .with_file("/repo/svc/go.mod", "module svc\n")
+            .with_file("/repo/svc/.gitignore", "bin/\ncoverage/report.html\n");
+        let root = Path::new("/repo");
+        let stacks = detect_stacks(&fs, root).expect("detect");
+        let python: Vec<_> = stacks
+            .iter()
+            .filter(|stack| stack.kind == ProjectKind::Python)
+            .cloned()
+            .collect();
+        let options = DrydockOptions {
+            cache: false,
+            ..DrydockOptions::default()
+        };
+        let drydock = generate_drydock(root, &python, &options);
+
+        assert_eq!(
+            drydock.dockerfile,
+            [
+                "FROM python:3.11-slim AS deps",
+                "RUN useradd --create-home --uid 10001 shipshape 2>/dev/null || adduser -D -u 10001 shipshape",
+                "ENV PATH=/home/shipshape/.local/bin:$PATH",
+                "WORKDIR /app",
+                "RUN chown shipshape /app",
+                "RUN python -m pip install --upgrade pip",
+                "USER shipshape",
+                "COPY --chown=shipshape:shipshape requirements.txt ./",
+                "RUN python -m pip install -r requirements.txt",
+                "RUN python -m pip install pytest",
+                "",
+                "FROM deps",
+                "COPY --chown=shipshape:shipshape . /app/",
+                "CMD [\"pytest\", \"-q\"]",
+                "",
+            ]
+            .join("\n")
+        );
+        let go = generate_drydock(root, &stacks, &options);
+        assert!(go.dockerfile.contains(
+            "COPY --chown=shipshape:shipshape svc/go.mod svc/go.sum* ./\nRUN go mod download\n"
+        ));
+
+        assert_eq!(
+            generate_dockerignore(&fs, root, &stacks),
+            [
+                "# Generated by ShipShape from .gitignore and the detected stacks.",
+                ".git",
+                "",
+                "# .gitignore",
+                "dist",
+                "**/*.log",
+                "!**/keep.log",
+                "",
+                "# svc/.gitignore",
+                "svc/**/bin",
+                "svc/coverage/report.html",
+                "",
+                "# python",
+                "**/__pycache__",
+                "**/*.py[cod]",
+                "**/.venv",
+                "**/venv",
+                "**/.pytest_cache",
+                "**/.tox",
+                "**/.nox",
+                "**/*.egg-info",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
     fn temp_dir_with_marker(marker: &str) -> PathBuf {
         let root = std::env::temp_dir().join(unique_dir_name());
         std::fs::create_dir_all(&root).expect("create temp dir");
@@ -0,0 +1,708 @@
+//! Token-based duplicate code detection.
+//!
+//! Files classified by [`TokeiInspector`] are tokenised with comments removed
+//! and identifiers and literals normalised, so renamed copies still match.
+//! Fixed-size token windows are hashed with a rolling (Rabin-Karp) hash;
+//! windows that share a hash are verified and extended into maximal duplicate
+//! blocks, either inside one repository or across a batch of repositories.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokei::LanguageType;
+
+use crate::Result;
+use crate::coverage::is_dependency_dir;
+use crate::domain::{CodeSpan, DuplicateBlock, DuplicationReport};
+use crate::fs::FileSystem;
+use crate::health::HealthSignals;
+use crate::inspector::TokeiInspector;
+
+/// Number of duplicate blocks listed in a [`DuplicationReport`].
+pub const DUPLICATE_BLOCKS_REPORTED: usize = 20;
+/// Signal name for [`DuplicationReport::duplication_ratio`].
+pub const SIGNAL_DUPLICATION_RATIO: &str = "duplication_ratio";
+
+/// Windows shared by more locations than this are treated as boilerplate.
+const MAX_BUCKET: usize = 32;
+const HASH_BASE: u64 = 1_099_511_628_211;
+
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "and",
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "defer",
+    "do",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "extern",
+    "false",
+    "finally",
+    "fn",
+    "for",
+    "from",
+    "func",
+    "function",
+    "go",
+    "if",
+    "impl",
+    "import",
+    "in",
+    "interface",
+    "lambda",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "new",
+    "nil",
+    "None",
+    "not",
+    "null",
+    "or",
+    "package",
+    "pass",
+    "private",
+    "protected",
+    "pub",
+    "public",
+    "raise",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "true",
+    "True",
+    "False",
+    "try",
+    "type",
+    "typedef",
+    "use",
+    "var",
+    "void",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Tuning for duplicate detection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuplicationConfig {
+    /// Minimum length of a duplicate block, in normalised tokens.
+    pub min_tokens: usize,
+    /// Minimum length of a duplicate block, in lines.
+    pub min_lines: u32,
+}
+
+impl Default for DuplicationConfig {
+    fn default() -> Self {
+        Self {
+            min_tokens: 50,
+            min_lines: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token {
+    norm: u64,
+    raw: u64,
+    line: u32,
+}
+
+#[derive(Debug, Clone)]
+struct CorpusFile {
+    repo: Option<String>,
+    path: String,
+    tokens: Vec<Token>,
+}
+
+/// Tokenised source files from one or more repositories.
+#[derive(Debug, Clone, Default)]
+pub struct SourceCorpus {
+    files: Vec<CorpusFile>,
+}
+
+impl SourceCorpus {
+    /// Create an empty corpus.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tokenise the source files of a repository into the corpus.
+    ///
+    /// `repo` labels the files so cross-repository matches can be attributed.
+    /// Data formats, prose and dependency directories are skipped.
+    pub fn add_repository<F: FileSystem>(
+        &mut self,
+        inspector: &TokeiInspector<F>,
+        root: &Path,
+        repo: Option<&str>,
+    ) -> Result<()> {
+        for source in inspector.read_source_files(root)? {
+            let relative = source.path.strip_prefix(root).unwrap_or(&source.path);
+            if is_dependency_dir(relative) {
+                continue;
+            }
+            let path = relative.to_string_lossy().replace('\\', "/");
+            self.add_source(repo, &path, source.language, &source.contents);
+        }
+        Ok(())
+    }
+
+    /// Tokenise a single file into the corpus.
+    pub fn add_source(
+        &mut self,
+        repo: Option<&str>,
+        path: &str,
+        language: LanguageType,
+        contents: &str,
+    ) {
+        if !is_code_language(language) {
+            return;
+        }
+        self.files.push(CorpusFile {
+            repo: repo.map(str::to_string),
+            path: path.to_string(),
+            tokens: tokenize(language, contents),
+        });
+    }
+
+    /// Number of files in the corpus.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether the corpus has no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Find duplicate blocks inside a single repository.
+pub fn inspect_duplication<F: FileSystem>(
+    inspector: &TokeiInspector<F>,
+    root: &Path,
+    config: &DuplicationConfig,
+) -> Result<DuplicationReport> {
+    let mut corpus = SourceCorpus::new();
+    corpus.add_repository(inspector, root, None)?;
+    Ok(summarize_duplication(&corpus, config))
+}
+
+/// Find blocks shared between different repositories of a corpus.
+///
+/// Duplicates within a single repository are ignored; every returned span has
+/// its `repo` set. Blocks are ordered largest first.
+pub fn find_cross_repo_duplicates(
+    corpus: &SourceCorpus,
+    config: &DuplicationConfig,
+) -> Vec<DuplicateBlock> {
+    let mut blocks: Vec<DuplicateBlock> = find_matches(&corpus.files, config, true)
+        .iter()
+        .filter_map(|found| to_block(&corpus.files, found, config))
+        .collect();
+    sort_blocks(&mut blocks);
+    blocks
+}
+
+/// Expose duplication metrics as health model signals.
+pub fn duplication_signals(report: &DuplicationReport) -> HealthSignals {
+    let mut signals = HealthSignals::new();
+    signals.insert(
+        SIGNAL_DUPLICATION_RATIO.to_string(),
+        report.duplication_ratio,
+    );
+    signals
+}
+
+fn summarize_duplication(corpus: &SourceCorpus, config: &DuplicationConfig) -> DuplicationReport {
+    let files = &corpus.files;
+    let mut duplicated = BTreeSet::new();
+    let mut blocks = Vec::new();
+    for found in find_matches(files, config, false) {
+        let Some(block) = to_block(files, &found, config) else {
+            continue;
+        };
+        for (file, start) in [
+            (found.first, found.first_start),
+            (found.second, found.second_start),
+        ] {
+            for token in &files[file].tokens[start..start + found.len] {
+                duplicated.insert((file, token.line));
+            }
+        }
+        blocks.push(block);
+    }
+
+    let total_lines: usize = files
+        .iter()
+        .map(|file| {
+            file.tokens
+                .iter()
+                .map(|token| token.line)
+                .collect::<BTreeSet<_>>()
+                .len()
+        })
+        .sum();
+    let duplicated_lines = duplicated.len();
+    let duplication_ratio = if total_lines == 0 {
+        0.0
+    } else {
+        duplicated_lines as f64 / total_lines as f64
+    };
+
+    sort_blocks(&mut blocks);
+    let duplicate_blocks = blocks.len();
+    blocks.truncate(DUPLICATE_BLOCKS_REPORTED);
+    DuplicationReport {
+        files_analyzed: files.len(),
+        duplicate_blocks,
+        duplicated_lines,
+        duplication_ratio,
+        blocks,
+        cross_repo: Vec::new(),
+    }
+}
+
+fn sort_blocks(blocks: &mut [DuplicateBlock]) {
+    blocks.sort_by(|a, b| {
+        b.tokens
+            .cmp(&a.tokens)
+            .then_with(|| a.first.path.cmp(&b.first.path))
+            .then_with(|| a.first.start_line.cmp(&b.first.start_line))
+    });
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Match {
+    first: usize,
+    first_start: usize,
+    second: usize,
+    second_start: usize,
+    len: usize,
+}
+
+fn find_matches(files: &[CorpusFile], config: &DuplicationConfig, cross_repo: bool) -> Vec<Match> {
+    let window = config.min_tokens.max(1);
+    let power = (1..window).fold(1u64, |power, _| power.wrapping_mul(HASH_BASE));
+
+    let mut buckets: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+    // Windows that overlap themselves come from runs of repeated statements
+    // (tables, padding); they are repetition rather than copied code.
+    let mut repetitive = HashSet::new();
+    for (file_index, file) in files.iter().enumerate() {
+        let tokens = &file.tokens;
+        let mut hash = 0u64;
+        for (index, token) in tokens.iter().enumerate() {
+            if index >= window {
+                hash = hash.wrapping_sub(tokens[index - window].norm.wrapping_mul(power));
+            }
+            hash = hash.wrapping_mul(HASH_BASE).wrapping_add(token.norm);
+            if index + 1 >= window {
+                let start = index + 1 - window;
+                let bucket = buckets.entry(hash).or_default();
+                if bucket
+                    .last()
+                    .is_some_and(|last| last.0 == file_index && start < last.1 + window)
+                {
+                    repetitive.insert(hash);
+                }
+                if bucket.len() < MAX_BUCKET {
+                    bucket.push((file_index, start));
+                }
+            }
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for (hash, bucket) in &buckets {
+        if repetitive.contains(hash) {
+            continue;
+        }
+        for (position, first) in bucket.iter().enumerate() {
+            for second in &bucket[position + 1..] {
+                let same_repo = files[first.0].repo == files[second.0].repo;
+                if same_repo == cross_repo {
+                    continue;
+                }
+                if first.0 == second.0 && second.1 < first.1 + window {
+                    continue;
+                }
+                candidates.push((*first, *second));
+            }
+        }
+    }
+    // Process in file/offset order so each diagonal is extended from its start.
+    candidates.sort_unstable();
+
+    let mut covered: HashMap<(usize, usize, isize), usize> = HashMap::new();
+    let mut matches = Vec::new();
+    for ((first, first_start), (second, second_start)) in candidates {
+        let diagonal = second_start as isize - first_start as isize;
+        if covered
+            .get(&(first, second, diagonal))
+            .is_some_and(|end| first_start < *end)
+        {
+            continue;
+        }
+        let a = &files[first].tokens;
+        let b = &files[second].tokens;
+        if (0..window).any(|offset| a[first_start + offset].norm != b[second_start + offset].norm) {
+            continue;
+        }
+        let mut len = window;
+        while first_start + len < a.len()
+            && second_start + len < b.len()
+            && a[first_start + len].norm == b[second_start + len].norm
+            && !(first == second && first_start + len >= second_start)
+        {
+            len += 1;
+        }
+        covered.insert((first, second, diagonal), first_start + len);
+        matches.push(Match {
+            first,
+            first_start,
+            second,
+            second_start,
+            len,
+        });
+    }
+    matches
+}
+
+fn to_block(
+    files: &[CorpusFile],
+    found: &Match,
+    config: &DuplicationConfig,
+) -> Option<DuplicateBlock> {
+    let a = &files[found.first].tokens[found.first_start..found.first_start + found.len];
+    let b = &files[found.second].tokens[found.second_start..found.second_start + found.len];
+    let first = span(&files[found.first], a);
+    let second = span(&files[found.second], b);
+    let lines =
+        (first.end_line - first.start_line + 1).min(second.end_line - second.start_line + 1);
+    if lines < config.min_lines {
+        return None;
+    }
+    let identical = a.iter().zip(b).filter(|(x, y)| x.raw == y.raw).count();
+    Some(DuplicateBlock {
+        first,
+        second,
+        tokens: found.len,
+        similarity: identical as f64 / found.len as f64,
+    })
+}
+
+fn span(file: &CorpusFile, tokens: &[Token]) -> CodeSpan {
+    CodeSpan {
+        repo: file.repo.clone(),
+        path: file.path.clone(),
+        start_line: tokens.first().map_or(0, |token| token.line),
+        end_line: tokens.last().map_or(0, |token| token.line),
+    }
+}
+
+fn is_code_language(language: LanguageType) -> bool {
+    !language.is_literate()
+        && !matches!(
+            language,
+            LanguageType::Json
+                | LanguageType::Toml
+                | LanguageType::Yaml
+                | LanguageType::Xml
+                | LanguageType::Svg
+                | LanguageType::Text
+                | LanguageType::Html
+                | LanguageType::Ini
+        )
+}
+
+fn tokenize(language: LanguageType, contents: &str) -> Vec<Token> {
+    let line_comments = language.line_comments();
+    let block_comments: Vec<(&str, &str)> = language
+        .multi_line_comments()
+        .iter()
+        .chain(language.nested_comments())
+        .copied()
+        .collect();
+    // Longest openers first so `r#"` wins over `"`; only verbatim quotes
+    // treat backslashes literally.
+    let mut quotes: Vec<(&str, &str, bool)> = language
+        .doc_quotes()
+        .iter()
+        .chain(language.quotes())
+        .map(|(open, close)| (*open, *close, true))
+        .chain(
+            language
+                .verbatim_quotes()
+                .iter()
+                .map(|(open, close)| (*open, *close, false)),
+        )
+        .collect();
+    quotes.sort_by_key(|quote| std::cmp::Reverse(quote.0.len()));
+
+    let literal = fnv("$lit");
+    let identifier = fnv("$id");
+    let mut tokens = Vec::new();
+    let mut line = 1u32;
+    let mut index = 0;
+
+    while index < contents.len() {
+        let rest = &contents[index..];
+        let Some(ch) = rest.chars().next() else {
+            break;
+        };
+        if ch == '\n' {
+            line += 1;
+            index += 1;
+        } else if ch.is_whitespace() {
+            index += ch.len_utf8();
+        } else if let Some((open, close)) = block_comments
+            .iter()
+            .find(|(open, _)| rest.starts_with(open))
+        {
+            index += open.len();
+            index += skip_to(&contents[index..], close, false, &mut line);
+        } else if line_comments.iter().any(|open| rest.starts_with(open)) {
+            index += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some((open, close, escapes)) =
+            quotes.iter().find(|(open, _, _)| rest.starts_with(open))
+        {
+            let start_line = line;
+            let start = index;
+            index += open.len();
+            index += skip_to(&contents[index..], close, *escapes, &mut line);
+            tokens.push(Token {
+                norm: literal,
+                raw: fnv(&contents[start..index]),
+                line: start_line,
+            });
+        } else if ch.is_alphanumeric() || ch == '_' || ch == '$' {
+            let start = index;
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            index += end;
+            let word = &contents[start..index];
+            let norm = if ch.is_ascii_digit() {
+                literal
+            } else if KEYWORDS.contains(&word) {
+                fnv(word)
+            } else {
+                identifier
+            };
+            tokens.push(Token {
+                norm,
+                raw: fnv(word),
+                line,
+            });
+        } else {
+            let text = &rest[..ch.len_utf8()];
+            let hash = fnv(text);
+            tokens.push(Token {
+                norm: hash,
+                raw: hash,
+                line,
+            });
+            index += ch.len_utf8();
+        }
+    }
+
+    tokens
+}
+
+/// Length of `text` up to and including `close`, counting newlines.
+fn skip_to(text: &str, close: &str, escapes: bool, line: &mut u32) -> usize {
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        if rest.starts_with(close) {
+            return index + close.len();
+        }
+        let Some(ch) = rest.chars().next() else {
+            break;
+        };
+        if ch == '\n' {
+            *line += 1;
+        }
+        if escapes && ch == '\\' {
+            index += 1;
+            if let Some(next) = text[index..].chars().next() {
+                if next == '\n' {
+                    *line += 1;
+                }
+                index += next.len_utf8();
+            }
+            continue;
+        }
+        index += ch.len_utf8();
+    }
+    text.len()
+}
+
+fn fnv(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MockFileSystem;
+    use std::path::PathBuf;
+
+    const ORIGINAL: &str = r#"// Parse a config line.
+fn parse_line(line: &str) -> Option<(String, String)> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    let (key, value) = trimmed.split_once('=')?;
+    Some((key.trim().to_string(), value.trim().to_string()))
+}
+"#;
+
+    const RENAMED: &str = r#"fn unrelated() -> u32 {
+    42
+}
+
+/* Copied from the config module. */
+fn read_entry(entry: &str) -> Option<(String, String)> {
+    let cleaned = entry.trim();
+    if cleaned.is_empty() || cleaned.starts_with('#') {
+        return None;
+    }
+    let (name, setting) = cleaned.split_once('=')?;
+    Some((name.trim().to_string(), setting.trim().to_string()))
+}
+"#;
+
+    fn config() -> DuplicationConfig {
+        DuplicationConfig {
+            min_tokens: 20,
+            min_lines: 3,
+        }
+    }
+
+    fn norms(language: LanguageType, contents: &str) -> Vec<u64> {
+        tokenize(language, contents)
+            .iter()
+            .map(|token| token.norm)
+            .collect()
+    }
+
+    #[test]
+    fn normalises_identifiers_literals_and_comments() {
+        let a = norms(
+            LanguageType::Rust,
+            "let total = count + 1; // sum\nlet s = \"a // b\";",
+        );
+        let b = norms(
+            LanguageType::Rust,
+            "/* note */ let sum = items + 99;\nlet label = \"other\";",
+        );
+        assert_eq!(a, b);
+
+        let python = tokenize(
+            LanguageType::Python,
+            "x = 1  # comment\n\"\"\"doc\nstring\"\"\"\ny = 2\n",
+        );
+        assert_eq!(python.len(), 7);
+        assert_eq!(python.last().map(|token| token.line), Some(4));
+    }
+
+    #[test]
+    fn finds_renamed_copies_within_a_repository() {
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(|_| {
+            Ok(vec![
+                PathBuf::from("/repo/src/config.rs"),
+                PathBuf::from("/repo/src/entries.rs"),
+                PathBuf::from("/repo/vendor/copy/config.rs"),
+                PathBuf::from("/repo/data.json"),
+            ])
+        });
+        fs.expect_read_to_string().returning(|path| {
+            Ok(if path.ends_with("entries.rs") {
+                RENAMED.to_string()
+            } else if path.ends_with("data.json") {
+                "{}".to_string()
+            } else {
+                ORIGINAL.to_string()
+            })
+        });
+        let inspector = TokeiInspector::new(fs);
+
+        let report =
+            inspect_duplication(&inspector, Path::new("/repo"), &config()).expect("report");
+
+        assert_eq!(report.files_analyzed, 2);
+        assert_eq!(report.duplicate_blocks, 1);
+        let block = &report.blocks[0];
+        assert_eq!(block.first.path, "src/config.rs");
+        assert_eq!((block.first.start_line, block.first.end_line), (2, 9));
+        assert_eq!(block.second.path, "src/entries.rs");
+        assert_eq!((block.second.start_line, block.second.end_line), (6, 13));
+        assert!(block.similarity > 0.5 && block.similarity < 1.0);
+        assert_eq!(report.duplicated_lines, 16);
+        assert!((report.duplication_ratio - 16.0 / 19.0).abs() < 1e-9);
+
+        let signals = duplication_signals(&report);
+        assert_eq!(signals[SIGNAL_DUPLICATION_RATIO], report.duplication_ratio);
+    }
+
+    #[test]
+    fn ignores_short_and_overlapping_repeats() {
+        let mut corpus = SourceCorpus::new();
+        corpus.add_source(None, "a.rs", LanguageType::Rust, "fn a() { let x = 1; }\n");
+        corpus.add_source(None, "b.rs", LanguageType::Rust, "fn b() { let y = 2; }\n");
+        corpus.add_source(None, "c.rs", LanguageType::Rust, &"x();\n".repeat(40));
+
+        let report = summarize_duplication(&corpus, &config());
+
+        assert_eq!(report.duplicate_blocks, 0);
+        assert_eq!(report.duplication_ratio, 0.0);
+    }
+
+    #[test]
+    fn finds_blocks_shared_across_repositories() {
+        let mut corpus = SourceCorpus::new();
+        corpus.add_source(Some("alpha"), "src/config.rs", LanguageType::Rust, ORIGINAL);
+        corpus.add_source(Some("alpha"), "src/copy.rs", LanguageType::Rust, ORIGINAL);
+        corpus.add_source(Some("beta"), "lib/entries.rs", LanguageType::Rust, RENAMED);
+        assert_eq!(corpus.len(), 3);
+
+        let blocks = find_cross_repo_duplicates(&corpus, &config());
+
+        assert_eq!(blocks.len(), 2);
+        for block in &blocks {
+            assert_eq!(block.first.repo.as_deref(), Some("alpha"));
+            assert_eq!(block.second.repo.as_deref(), Some("beta"));
+            assert_eq!(block.second.path, "lib/entries.rs");
+        }
+    }
+}
@@ -38,7 +38,7 @@ mod tests {
 
     #[test]
     fn io_error_formats_message() {
-        let error = ShipShapeError::Io(io::Error::new(io::ErrorKind::Other, "boom"));
+        let error = ShipShapeError::Io(io::Error::other("boom"));
         assert_eq!(format!("{error}"), "io error: boom");
     }
 
@@ -1,6 +1,8 @@
 //! Filesystem abstractions used for inspection.
 
-use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 
 use crate::error::Result;
 
@@ -11,6 +13,10 @@ pub trait FileSystem {
     fn list_files(&self, root: &Path) -> Result<Vec<PathBuf>>;
     /// Read a file into a string.
     fn read_to_string(&self, path: &Path) -> Result<String>;
+    /// Read a file as raw bytes, including non-UTF-8 content.
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        self.read_to_string(path).map(String::into_bytes)
+    }
 }
 
 /// Default filesystem implementation backed by `std::fs`.
@@ -51,6 +57,95 @@ impl FileSystem for StdFileSystem {
     fn read_to_string(&self, path: &Path) -> Result<String> {
         Ok(std::fs::read_to_string(path)?)
     }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        Ok(std::fs::read(path)?)
+    }
+}
+
+/// A filesystem held entirely in memory, for embedding and tests.
+///
+/// Listing mirrors [`StdFileSystem`]: files under hidden directories and
+/// hidden files are skipped, but can still be read by path.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryFileSystem {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+impl MemoryFileSystem {
+    /// Create an empty in-memory filesystem.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a file.
+    pub fn insert(&mut self, path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) {
+        self.files.insert(path.into(), contents.into());
+    }
+
+    /// Add or replace a file, returning the filesystem for chaining.
+    pub fn with_file(mut self, path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) -> Self {
+        self.insert(path, contents);
+        self
+    }
+
+    /// Number of files held, including hidden ones.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether no files are held.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn list_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        Ok(self
+            .files
+            .keys()
+            .filter(|path| {
+                path.strip_prefix(root).is_ok_and(|relative| {
+                    relative.components().all(|component| match component {
+                        Component::Normal(name) => !name.to_string_lossy().starts_with('.'),
+                        _ => true,
+                    })
+                })
+            })
+            .cloned()
+            .collect())
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {err}", path.display()),
+            )
+            .into()
+        })
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{}: no such file", path.display()),
+            )
+            .into()
+        })
+    }
+}
+
+impl<P: Into<PathBuf>, C: Into<Vec<u8>>> FromIterator<(P, C)> for MemoryFileSystem {
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(iter: I) -> Self {
+        let mut fs = Self::new();
+        for (path, contents) in iter {
+            fs.insert(path, contents);
+        }
+        fs
+    }
 }
 
 fn is_hidden(path: &Path) -> bool {
@@ -62,7 +157,7 @@ fn is_hidden(path: &Path) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::StdFileSystem;
+    use super::{MemoryFileSystem, StdFileSystem};
     use crate::fs::FileSystem;
     use std::path::PathBuf;
 
@@ -83,6 +178,39 @@ mod tests {
         std::fs::remove_dir_all(&root).expect("cleanup temp dir");
     }
 
+    #[test]
+    fn memory_filesystem_mirrors_std_listing() {
+        let fs = MemoryFileSystem::new()
+            .with_file("/repo/src/main.rs", "fn main() {}\n")
+            .with_file("/repo/.gitattributes", "*.pb.go linguist-generated\n")
+            .with_file("/repo/.git/HEAD", "ref: refs/heads/main\n")
+            .with_file("/repo/logo.png", vec![0x89, 0x50, 0xff])
+            .with_file("/other/README.md", "other");
+
+        let files = fs.list_files(&PathBuf::from("/repo")).expect("list files");
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("/repo/logo.png"),
+                PathBuf::from("/repo/src/main.rs")
+            ]
+        );
+        let attributes = fs.read_to_string(&PathBuf::from("/repo/.gitattributes"));
+        assert!(
+            attributes
+                .expect("hidden files stay readable")
+                .contains("linguist")
+        );
+        assert_eq!(
+            fs.read(&PathBuf::from("/repo/logo.png"))
+                .expect("bytes")
+                .len(),
+            3
+        );
+        assert!(fs.read_to_string(&PathBuf::from("/repo/logo.png")).is_err());
+        assert!(fs.read(&PathBuf::from("/repo/missing.rs")).is_err());
+    }
+
     fn unique_dir_name() -> PathBuf {
         let nanos = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
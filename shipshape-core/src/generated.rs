@@ -0,0 +1,494 @@
+//! Generated and vendored file detection.
+//!
+//! Lockfiles, protobuf outputs, minified bundles and third-party folders are
+//! not code the repository owns. Files are classified from `.gitattributes`
+//! `linguist-generated`/`linguist-vendored` entries first, then path
+//! conventions, then "generated" header markers and minification. An explicit
+//! `linguist-*=false` attribute keeps a file in the audit.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+use tokei::{Config, LanguageType};
+
+use crate::domain::{ExcludedFile, ExcludedKind, Violation};
+use crate::error::Result;
+use crate::fs::FileSystem;
+
+/// Lines at the top of a file searched for a generated marker.
+const HEADER_LINES: usize = 5;
+/// Average line length above which a script or stylesheet counts as minified.
+const MINIFIED_LINE_LENGTH: usize = 250;
+/// Files smaller than this are never considered minified.
+const MINIFIED_MIN_BYTES: usize = 1000;
+
+const VENDOR_DIRS: &[&str] = &[
+    "vendor",
+    "vendored",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "node_modules",
+    "bower_components",
+];
+const GENERATED_DIRS: &[&str] = &["generated", "__generated__"];
+const LOCKFILES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "pdm.lock",
+    "go.sum",
+    "composer.lock",
+    "Gemfile.lock",
+    "flake.lock",
+    "packages.lock.json",
+];
+const PROTOBUF_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    ".pb.rs",
+    "_pb2.py",
+    "_pb2.pyi",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    "_grpc_pb.js",
+];
+const GENERATED_SUFFIXES: &[&str] = &[".g.dart", ".freezed.dart", ".designer.cs", ".generated.cs"];
+const HEADER_MARKERS: &[&str] = &[
+    "@generated",
+    "code generated",
+    "do not edit",
+    "autogenerated",
+    "auto-generated",
+    "automatically generated",
+];
+
+/// Classifies files as generated or vendored for one repository.
+#[derive(Debug, Clone, Default)]
+pub struct GeneratedFiles {
+    attributes: Vec<AttributeRule>,
+}
+
+#[derive(Debug, Clone)]
+struct AttributeRule {
+    matcher: GlobMatcher,
+    generated: Option<bool>,
+    vendored: Option<bool>,
+}
+
+/// Outcome of the checks that need no file contents.
+enum PathVerdict {
+    Excluded(ExcludedKind, &'static str),
+    Owned,
+    Unknown,
+}
+
+impl GeneratedFiles {
+    /// Load the `.gitattributes` file at `root`, if any.
+    pub fn load<F: FileSystem>(fs: &F, root: &Path) -> Self {
+        fs.read_to_string(&root.join(".gitattributes"))
+            .map(|contents| Self::from_gitattributes(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Build a classifier from `.gitattributes` contents.
+    ///
+    /// Only `linguist-generated` and `linguist-vendored` are read; later lines
+    /// override earlier ones, as in git.
+    pub fn from_gitattributes(contents: &str) -> Self {
+        let attributes = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let matcher = attribute_matcher(fields.next()?)?;
+                let mut rule = AttributeRule {
+                    matcher,
+                    generated: None,
+                    vendored: None,
+                };
+                for field in fields {
+                    let (name, value) = match field.split_once('=') {
+                        Some((name, value)) => (name, value != "false"),
+                        None => match field.strip_prefix(['-', '!']) {
+                            Some(name) => (name, false),
+                            None => (field, true),
+                        },
+                    };
+                    match name {
+                        "linguist-generated" => rule.generated = Some(value),
+                        "linguist-vendored" => rule.vendored = Some(value),
+                        _ => {}
+                    }
+                }
+                (rule.generated.is_some() || rule.vendored.is_some()).then_some(rule)
+            })
+            .collect();
+        Self { attributes }
+    }
+
+    /// Classify a file from its path and `.gitattributes` alone.
+    pub fn classify_path(&self, relative: &Path) -> Option<ExcludedFile> {
+        match self.path_verdict(relative) {
+            PathVerdict::Excluded(kind, reason) => Some(excluded(relative, kind, reason)),
+            PathVerdict::Owned | PathVerdict::Unknown => None,
+        }
+    }
+
+    /// Classify a file from its path, then its header and line lengths.
+    pub fn classify(&self, relative: &Path, contents: &str) -> Option<ExcludedFile> {
+        match self.path_verdict(relative) {
+            PathVerdict::Excluded(kind, reason) => Some(excluded(relative, kind, reason)),
+            PathVerdict::Owned => None,
+            PathVerdict::Unknown => {
+                let reason = if has_generated_header(contents) {
+                    "generated header"
+                } else if is_minified(relative, contents) {
+                    "minified"
+                } else {
+                    return None;
+                };
+                Some(excluded(relative, ExcludedKind::Generated, reason))
+            }
+        }
+    }
+
+    fn path_verdict(&self, relative: &Path) -> PathVerdict {
+        let mut generated = None;
+        let mut vendored = None;
+        for rule in &self.attributes {
+            if rule.matcher.is_match(relative) {
+                generated = rule.generated.or(generated);
+                vendored = rule.vendored.or(vendored);
+            }
+        }
+        if vendored == Some(true) {
+            return PathVerdict::Excluded(ExcludedKind::Vendored, "linguist-vendored");
+        }
+        if generated == Some(true) {
+            return PathVerdict::Excluded(ExcludedKind::Generated, "linguist-generated");
+        }
+        if vendored.is_none() && is_vendored_path(relative) {
+            return PathVerdict::Excluded(ExcludedKind::Vendored, "vendor directory");
+        }
+        if generated.is_none()
+            && let Some(reason) = generated_path_reason(relative)
+        {
+            return PathVerdict::Excluded(ExcludedKind::Generated, reason);
+        }
+        if generated.is_some() || vendored.is_some() {
+            PathVerdict::Owned
+        } else {
+            PathVerdict::Unknown
+        }
+    }
+}
+
+/// List generated and vendored files under `root`, sorted by path.
+///
+/// Contents are only read for files `tokei` recognises and that no path
+/// rule already classified.
+pub fn detect_generated_files<F: FileSystem>(fs: &F, root: &Path) -> Result<Vec<ExcludedFile>> {
+    let classifier = GeneratedFiles::load(fs, root);
+    let config = Config::default();
+    let mut files = fs.list_files(root)?;
+    files.sort();
+    let mut found = Vec::new();
+    for path in files {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let file = match classifier.path_verdict(relative) {
+            PathVerdict::Excluded(kind, reason) => Some(excluded(relative, kind, reason)),
+            PathVerdict::Owned => None,
+            PathVerdict::Unknown if LanguageType::from_path(&path, &config).is_some() => fs
+                .read_to_string(&path)
+                .ok()
+                .and_then(|contents| classifier.classify(relative, &contents)),
+            PathVerdict::Unknown => None,
+        };
+        found.extend(file);
+    }
+    Ok(found)
+}
+
+/// Drop violations pointing at excluded files.
+///
+/// Violations without a `path` are matched on a leading `file:` in the
+/// message, which is how external mechanics report locations.
+pub fn drop_excluded_violations(
+    violations: &mut Vec<Violation>,
+    root: &Path,
+    excluded: &[ExcludedFile],
+) {
+    if excluded.is_empty() {
+        return;
+    }
+    let paths: BTreeSet<&str> = excluded.iter().map(|file| file.path.as_str()).collect();
+    let root = root.to_string_lossy().replace('\\', "/");
+    let is_excluded = |location: &str| {
+        let location = location.replace('\\', "/");
+        let relative = location
+            .strip_prefix(&root)
+            .map(|rest| rest.trim_start_matches('/'))
+            .unwrap_or(&location)
+            .trim_start_matches("./");
+        paths.contains(relative)
+    };
+    violations.retain(|violation| match &violation.path {
+        Some(path) => !is_excluded(path),
+        None => !violation
+            .message
+            .split_once(':')
+            .is_some_and(|(location, _)| is_excluded(location)),
+    });
+}
+
+/// A filesystem view that hides excluded files from `list_files`.
+#[derive(Debug, Clone)]
+pub struct SkipExcluded<F: FileSystem> {
+    fs: F,
+    excluded: BTreeSet<PathBuf>,
+}
+
+impl<F: FileSystem> SkipExcluded<F> {
+    /// Wrap `fs`, hiding `excluded` files (relative to `root`).
+    pub fn new(fs: F, root: &Path, excluded: &[ExcludedFile]) -> Self {
+        Self {
+            fs,
+            excluded: excluded.iter().map(|file| root.join(&file.path)).collect(),
+        }
+    }
+}
+
+impl<F: FileSystem> FileSystem for SkipExcluded<F> {
+    fn list_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = self.fs.list_files(root)?;
+        files.retain(|path| !self.excluded.contains(path));
+        Ok(files)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.fs.read_to_string(path)
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        self.fs.read(path)
+    }
+}
+
+/// Match patterns without a slash against any directory, as git does.
+fn attribute_matcher(pattern: &str) -> Option<GlobMatcher> {
+    let pattern = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if !pattern.trim_end_matches('/').contains('/') => format!("**/{pattern}"),
+        None => pattern.to_string(),
+    };
+    let pattern = match pattern.strip_suffix('/') {
+        Some(dir) => format!("{dir}/**"),
+        None => pattern,
+    };
+    GlobBuilder::new(&pattern)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+fn is_vendored_path(relative: &Path) -> bool {
+    relative.parent().is_some_and(|parent| {
+        parent.components().any(|component| {
+            let segment = component.as_os_str().to_string_lossy();
+            VENDOR_DIRS.contains(&segment.as_ref())
+        })
+    })
+}
+
+fn generated_path_reason(relative: &Path) -> Option<&'static str> {
+    let name = relative
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    if LOCKFILES.contains(&name.as_ref()) {
+        return Some("lockfile");
+    }
+    if PROTOBUF_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        return Some("protobuf output");
+    }
+    let lower = name.to_lowercase();
+    if [".min.js", ".min.mjs", ".min.css"]
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+    {
+        return Some("minified");
+    }
+    if GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| lower.ends_with(suffix))
+        || lower.contains("_generated.")
+        || lower.contains(".generated.")
+    {
+        return Some("generated name");
+    }
+    let in_generated_dir = relative.parent().is_some_and(|parent| {
+        parent.components().any(|component| {
+            GENERATED_DIRS.contains(&component.as_os_str().to_string_lossy().as_ref())
+        })
+    });
+    in_generated_dir.then_some("generated directory")
+}
+
+fn has_generated_header(contents: &str) -> bool {
+    contents.lines().take(HEADER_LINES).any(|line| {
+        let line = line.to_lowercase();
+        HEADER_MARKERS.iter().any(|marker| line.contains(marker))
+    })
+}
+
+fn is_minified(relative: &Path, contents: &str) -> bool {
+    let is_bundle = relative
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext, "js" | "mjs" | "cjs" | "css"));
+    if !is_bundle || contents.len() < MINIFIED_MIN_BYTES {
+        return false;
+    }
+    let lines = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count();
+    lines == 0 || contents.len() / lines > MINIFIED_LINE_LENGTH
+}
+
+fn excluded(relative: &Path, kind: ExcludedKind, reason: &str) -> ExcludedFile {
+    ExcludedFile {
+        path: relative.to_string_lossy().replace('\\', "/"),
+        kind,
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+    use crate::fs::MockFileSystem;
+
+    fn kinds(files: &[ExcludedFile]) -> Vec<(&str, ExcludedKind, &str)> {
+        files
+            .iter()
+            .map(|file| (file.path.as_str(), file.kind, file.reason.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn detects_generated_and_vendored_files() {
+        let minified = format!("var a={};\n", "b".repeat(1200));
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(|_| {
+            Ok([
+                "Cargo.lock",
+                "api/service.pb.go",
+                "api/service_pb2.py",
+                "src/main.rs",
+                "src/schema.rs",
+                "third_party/zlib/inflate.c",
+                "vendor/keep.go",
+                "web/app.js",
+                "web/bundle.js",
+                "web/lib/jquery.min.js",
+                "web/static/build.js",
+            ]
+            .iter()
+            .map(|path| Path::new("/repo").join(path))
+            .collect())
+        });
+        fs.expect_read_to_string().returning(move |path| {
+            let relative = path.strip_prefix("/repo").expect("relative");
+            Ok(match relative.to_str().expect("utf-8") {
+                ".gitattributes" => {
+                    "# linguist overrides\nweb/static/** linguist-generated\nvendor/keep.go -linguist-vendored\n"
+                        .to_string()
+                }
+                "src/schema.rs" => "// @generated by diesel CLI\npub mod schema {}\n".to_string(),
+                "web/bundle.js" => minified.clone(),
+                _ => "fn main() {}\n".to_string(),
+            })
+        });
+
+        let files = detect_generated_files(&fs, Path::new("/repo")).expect("detect");
+
+        assert_eq!(
+            kinds(&files),
+            vec![
+                ("Cargo.lock", ExcludedKind::Generated, "lockfile"),
+                (
+                    "api/service.pb.go",
+                    ExcludedKind::Generated,
+                    "protobuf output"
+                ),
+                (
+                    "api/service_pb2.py",
+                    ExcludedKind::Generated,
+                    "protobuf output"
+                ),
+                ("src/schema.rs", ExcludedKind::Generated, "generated header"),
+                (
+                    "third_party/zlib/inflate.c",
+                    ExcludedKind::Vendored,
+                    "vendor directory"
+                ),
+                ("web/bundle.js", ExcludedKind::Generated, "minified"),
+                ("web/lib/jquery.min.js", ExcludedKind::Generated, "minified"),
+                (
+                    "web/static/build.js",
+                    ExcludedKind::Generated,
+                    "linguist-generated"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_violations_on_excluded_files() {
+        let excluded = vec![excluded(
+            Path::new("vendor/lib.go"),
+            ExcludedKind::Vendored,
+            "vendor directory",
+        )];
+        let violation = |path: Option<&str>, message: &str| Violation {
+            id: "go-err".to_string(),
+            message: message.to_string(),
+            severity: Severity::Warning,
+            path: path.map(str::to_string),
+            line: None,
+        };
+        let mut violations = vec![
+            violation(Some("vendor/lib.go"), "unchecked error"),
+            violation(None, "/repo/vendor/lib.go:12: unchecked error"),
+            violation(None, "main.go:3: unchecked error"),
+            violation(Some("main.go"), "unchecked error"),
+        ];
+
+        drop_excluded_violations(&mut violations, Path::new("/repo"), &excluded);
+
+        assert_eq!(violations.len(), 2);
+        assert!(
+            violations
+                .iter()
+                .all(|violation| !violation.message.contains("vendor")
+                    && violation.path.as_deref() != Some("vendor/lib.go"))
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Filesystem view of a git tree at any revision.
+//!
+//! Like [`history`](crate::history), objects are read through the `git` CLI.
+//! The tree is listed with `git ls-tree` and every blob is loaded with a
+//! single `git cat-file --batch` process, so historical revisions can be
+//! audited without touching the working copy.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::error::{Result, ShipShapeError};
+use crate::fs::{FileSystem, MemoryFileSystem};
+
+/// Git file mode of symbolic links, which are not followed.
+const SYMLINK_MODE: &str = "120000";
+
+/// The files of one commit, presented under the repository path.
+///
+/// File paths are `repo.join(path_in_tree)`, so reports read the same as for
+/// a checkout. Symbolic links and submodules are skipped.
+#[derive(Debug, Clone)]
+pub struct GitTreeFileSystem {
+    root: PathBuf,
+    commit: String,
+    files: MemoryFileSystem,
+}
+
+impl GitTreeFileSystem {
+    /// Load the tree of `rev` (a commit, branch, tag or any revision
+    /// expression) from the repository at `repo`.
+    pub fn open(repo: &Path, rev: &str) -> Result<Self> {
+        let commit = git_output(
+            repo,
+            &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+        )
+        .map_err(|err| ShipShapeError::Other(format!("unknown revision {rev}: {err}")))?;
+        let commit = String::from_utf8_lossy(&commit).trim().to_string();
+        let listing = git_output(repo, &["ls-tree", "-r", "-z", "--full-tree", &commit])?;
+        let blobs = parse_ls_tree(&listing);
+        let contents = read_blobs(repo, blobs.iter().map(|(object, _)| object.as_str()))?;
+        let files = blobs
+            .into_iter()
+            .zip(contents)
+            .map(|((_, path), contents)| (repo.join(path), contents))
+            .collect();
+        Ok(Self {
+            root: repo.to_path_buf(),
+            commit,
+            files,
+        })
+    }
+
+    /// Repository path the files are presented under.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Full id of the commit that was loaded.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+}
+
+impl FileSystem for GitTreeFileSystem {
+    fn list_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        self.files.list_files(root)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.files.read_to_string(path)
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        self.files.read(path)
+    }
+}
+
+/// Parse `git ls-tree -r -z` output into `(object id, path)` pairs of
+/// regular blobs.
+fn parse_ls_tree(output: &[u8]) -> Vec<(String, String)> {
+    output
+        .split(|byte| *byte == 0)
+        .filter_map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            let (meta, path) = entry.split_once('\t')?;
+            let mut fields = meta.split(' ');
+            let mode = fields.next()?;
+            let kind = fields.next()?;
+            let object = fields.next()?;
+            (kind == "blob" && mode != SYMLINK_MODE).then(|| (object.to_string(), path.to_string()))
+        })
+        .collect()
+}
+
+fn read_blobs<'a>(repo: &Path, objects: impl Iterator<Item = &'a str>) -> Result<Vec<Vec<u8>>> {
+    let request: String = objects.map(|object| format!("{object}\n")).collect();
+    let count = request.lines().count();
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| ShipShapeError::Other("git cat-file: no stdin".to_string()))?;
+    // Feed requests from another thread so a full stdout pipe cannot block us.
+    let writer = std::thread::spawn(move || stdin.write_all(request.as_bytes()));
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| ShipShapeError::Other("git cat-file: no stdout".to_string()))?;
+    let mut reader = BufReader::new(stdout);
+    let mut blobs = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let size = header
+            .split(' ')
+            .nth(2)
+            .and_then(|size| size.trim().parse::<usize>().ok())
+            .ok_or_else(|| {
+                ShipShapeError::Other(format!("git cat-file: unexpected header {header:?}"))
+            })?;
+        let mut contents = vec![0; size];
+        reader.read_exact(&mut contents)?;
+        let mut newline = [0; 1];
+        reader.read_exact(&mut newline)?;
+        blobs.push(contents);
+    }
+    writer
+        .join()
+        .map_err(|_| ShipShapeError::Other("git cat-file: writer panicked".to_string()))??;
+    child.wait()?;
+    Ok(blobs)
+}
+
+fn git_output(repo: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(ShipShapeError::Other(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GitTreeFileSystem;
+    use crate::fs::FileSystem;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    #[test]
+    fn reads_files_at_earlier_commits() {
+        let repo = std::env::temp_dir().join(format!(
+            "shipshape_git_tree_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system time")
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(repo.join("src")).expect("create repo");
+        git(&repo, &["init", "-q"]);
+        std::fs::write(repo.join("src/lib.rs"), "pub fn v1() {}\n").expect("write v1");
+        std::fs::write(repo.join(".gitignore"), "target/\n").expect("write ignore");
+        commit(&repo, "v1");
+        git(&repo, &["tag", "v1"]);
+        std::fs::write(repo.join("src/lib.rs"), "pub fn v2() {}\n").expect("write v2");
+        std::fs::write(repo.join("README.md"), "# demo\n").expect("write readme");
+        commit(&repo, "v2");
+
+        let old = GitTreeFileSystem::open(&repo, "v1").expect("open v1");
+        assert_eq!(old.commit().len(), 40);
+        assert_eq!(
+            old.list_files(&repo).expect("list"),
+            vec![repo.join("src/lib.rs")]
+        );
+        let source = old.read_to_string(&repo.join("src/lib.rs")).expect("read");
+        assert_eq!(source, "pub fn v1() {}\n");
+        assert!(old.read(&repo.join(".gitignore")).is_ok());
+
+        let head = GitTreeFileSystem::open(&repo, "HEAD").expect("open head");
+        let files: Vec<PathBuf> = head.list_files(&repo).expect("list");
+        assert_eq!(files, vec![repo.join("README.md"), repo.join("src/lib.rs")]);
+        assert!(GitTreeFileSystem::open(&repo, "no-such-rev").is_err());
+
+        std::fs::remove_dir_all(&repo).expect("cleanup repo");
+    }
+
+    fn commit(repo: &Path, message: &str) {
+        git(repo, &["add", "."]);
+        git(
+            repo,
+            &[
+                "-c",
+                "user.name=ShipShape",
+                "-c",
+                "user.email=shipshape@example.com",
+                "commit",
+                "-q",
+                "-m",
+                message,
+            ],
+        );
+    }
+
+    fn git(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(args)
+            .status()
+            .expect("git");
+        assert!(status.success(), "git {args:?}");
+    }
+}
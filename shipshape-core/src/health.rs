@@ -0,0 +1,652 @@
+//! Configurable health score model.
+//!
+//! A [`HealthModel`] turns coverage, violations and custom signals into a
+//! [`HealthBreakdown`]: a 0-100 score plus every deduction that produced it.
+//! The default model reproduces the built-in heuristic penalties and flags
+//! repositories without commits for a year; teams can override weights,
+//! thresholds and penalties from a TOML or JSON file.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api_docs::UNDOCUMENTED_API_RULE;
+use crate::domain::{
+    CoverageReport, HealthBreakdown, HealthCategory, HealthDeduction, Severity, Violation,
+};
+use crate::error::{Result, ShipShapeError};
+use crate::history::SIGNAL_LAST_COMMIT_AGE_DAYS;
+
+/// Named numeric signals (e.g. `last_commit_age_days`) fed into a health model.
+pub type HealthSignals = BTreeMap<String, f64>;
+
+/// Maximum health score before deductions.
+pub const MAX_HEALTH_SCORE: u32 = 100;
+
+/// Scoring model used to compute a [`HealthBreakdown`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthModel {
+    /// Multipliers applied to each category's penalty.
+    pub weights: HealthWeights,
+    /// Penalties for heuristic test coverage (test files / code files).
+    pub test_thresholds: Vec<Threshold>,
+    /// Penalties for measured line coverage.
+    pub line_thresholds: Vec<Threshold>,
+    /// Penalties for measured branch coverage.
+    pub branch_thresholds: Vec<Threshold>,
+    /// Penalties for documentation coverage (doc files / code files).
+    pub doc_thresholds: Vec<Threshold>,
+    /// Penalties for documented public API items.
+    pub api_doc_thresholds: Vec<Threshold>,
+    /// Penalties for violations.
+    pub violations: ViolationPenalties,
+    /// Penalties driven by custom signals.
+    pub signals: Vec<SignalRule>,
+}
+
+/// Category multipliers for a [`HealthModel`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthWeights {
+    /// Multiplier for test coverage penalties.
+    pub tests: f64,
+    /// Multiplier for documentation coverage penalties.
+    pub docs: f64,
+    /// Multiplier for violation penalties.
+    pub violations: f64,
+    /// Multiplier for signal penalties.
+    pub signals: f64,
+}
+
+/// A coverage threshold: ratios strictly below `below` cost `penalty` points.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Threshold {
+    /// Upper bound (exclusive) of the coverage ratio, 0.0-1.0.
+    pub below: f64,
+    /// Points deducted when the ratio falls below the bound.
+    pub penalty: u32,
+}
+
+/// Violation penalties, resolved per rule, then per severity, then by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ViolationPenalties {
+    /// Points per violation without a rule or severity override.
+    pub default: u32,
+    /// Points per violation keyed by violation id.
+    pub rules: BTreeMap<String, u32>,
+    /// Points per violation keyed by severity.
+    pub severities: BTreeMap<Severity, u32>,
+    /// Maximum total violation penalty, if any.
+    pub cap: Option<u32>,
+}
+
+/// A penalty applied when a named signal crosses a bound.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignalRule {
+    /// Signal name to evaluate.
+    pub signal: String,
+    /// Apply the penalty when the signal is strictly above this value.
+    #[serde(default)]
+    pub above: Option<f64>,
+    /// Apply the penalty when the signal is strictly below this value.
+    #[serde(default)]
+    pub below: Option<f64>,
+    /// Points deducted when the rule matches.
+    pub penalty: u32,
+    /// Optional explanation shown in the breakdown.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Default for HealthModel {
+    fn default() -> Self {
+        Self {
+            weights: HealthWeights::default(),
+            test_thresholds: thresholds(&[(0.10, 35), (0.20, 25), (0.40, 10)]),
+            line_thresholds: thresholds(&[(0.40, 35), (0.60, 25), (0.80, 10)]),
+            branch_thresholds: Vec::new(),
+            doc_thresholds: thresholds(&[(0.05, 25), (0.10, 15), (0.20, 5)]),
+            api_doc_thresholds: thresholds(&[(0.25, 25), (0.50, 15), (0.75, 5)]),
+            violations: ViolationPenalties::default(),
+            signals: vec![SignalRule {
+                signal: SIGNAL_LAST_COMMIT_AGE_DAYS.to_string(),
+                above: Some(365.0),
+                below: None,
+                penalty: 10,
+                reason: Some("no commits in over a year".to_string()),
+            }],
+        }
+    }
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        Self {
+            tests: 1.0,
+            docs: 1.0,
+            violations: 1.0,
+            signals: 1.0,
+        }
+    }
+}
+
+impl Default for ViolationPenalties {
+    fn default() -> Self {
+        Self {
+            default: 2,
+            // Undocumented items are already scored through API doc coverage.
+            rules: BTreeMap::from([(UNDOCUMENTED_API_RULE.to_string(), 0)]),
+            severities: BTreeMap::new(),
+            cap: Some(30),
+        }
+    }
+}
+
+impl HealthModel {
+    /// Load a model from a `.toml` or `.json` file.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let is_json = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        if is_json {
+            Self::from_json_str(&contents)
+        } else {
+            Self::from_toml_str(&contents)
+        }
+    }
+
+    /// Parse a model from TOML.
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        toml::from_str(contents)
+            .map_err(|err| ShipShapeError::Other(format!("invalid health model: {err}")))
+    }
+
+    /// Parse a model from JSON.
+    pub fn from_json_str(contents: &str) -> Result<Self> {
+        serde_json::from_str(contents)
+            .map_err(|err| ShipShapeError::Other(format!("invalid health model: {err}")))
+    }
+
+    /// Score a repository and explain every deduction.
+    pub fn evaluate(
+        &self,
+        coverage: &CoverageReport,
+        violations: &[Violation],
+        signals: &HealthSignals,
+    ) -> HealthBreakdown {
+        let mut deductions = Vec::new();
+        self.push_test_deductions(coverage, &mut deductions);
+        self.push_doc_deductions(coverage, &mut deductions);
+        self.push_violation_deductions(violations, &mut deductions);
+        self.push_signal_deductions(signals, &mut deductions);
+
+        let total: u32 = deductions.iter().map(|deduction| deduction.points).sum();
+        HealthBreakdown {
+            score: MAX_HEALTH_SCORE.saturating_sub(total) as u8,
+            deductions,
+        }
+    }
+
+    fn push_test_deductions(&self, coverage: &CoverageReport, out: &mut Vec<HealthDeduction>) {
+        let weight = self.weights.tests;
+        if let Some(line_coverage) = coverage.line_coverage {
+            if let Some(penalty) = threshold_penalty(&self.line_thresholds, line_coverage) {
+                push(
+                    out,
+                    HealthCategory::Tests,
+                    penalty,
+                    weight,
+                    format!("measured line coverage {}", percent(line_coverage)),
+                );
+            }
+            if let Some(branch_coverage) = coverage.branch_coverage
+                && let Some(penalty) = threshold_penalty(&self.branch_thresholds, branch_coverage)
+            {
+                push(
+                    out,
+                    HealthCategory::Tests,
+                    penalty,
+                    weight,
+                    format!("measured branch coverage {}", percent(branch_coverage)),
+                );
+            }
+            return;
+        }
+        if coverage.code_files == 0 {
+            return;
+        }
+        if let Some(penalty) = threshold_penalty(&self.test_thresholds, coverage.test_coverage) {
+            push(
+                out,
+                HealthCategory::Tests,
+                penalty,
+                weight,
+                format!(
+                    "test file ratio {} ({}/{})",
+                    percent(coverage.test_coverage),
+                    coverage.test_files,
+                    coverage.code_files
+                ),
+            );
+        }
+    }
+
+    fn push_doc_deductions(&self, coverage: &CoverageReport, out: &mut Vec<HealthDeduction>) {
+        if let Some(api_doc_coverage) = coverage.api_doc_coverage {
+            if let Some(penalty) = threshold_penalty(&self.api_doc_thresholds, api_doc_coverage) {
+                push(
+                    out,
+                    HealthCategory::Docs,
+                    penalty,
+                    self.weights.docs,
+                    format!(
+                        "API doc coverage {} ({}/{} public items)",
+                        percent(api_doc_coverage),
+                        coverage.documented_items,
+                        coverage.public_items
+                    ),
+                );
+            }
+            return;
+        }
+        if coverage.code_files == 0 {
+            return;
+        }
+        if let Some(penalty) = threshold_penalty(&self.doc_thresholds, coverage.doc_coverage) {
+            push(
+                out,
+                HealthCategory::Docs,
+                penalty,
+                self.weights.docs,
+                format!(
+                    "doc coverage {} ({}/{})",
+                    percent(coverage.doc_coverage),
+                    coverage.doc_files,
+                    coverage.code_files
+                ),
+            );
+        }
+    }
+
+    fn push_violation_deductions(&self, violations: &[Violation], out: &mut Vec<HealthDeduction>) {
+        // Group by rule so the breakdown lists one line per violation id.
+        let mut by_rule: BTreeMap<&str, (usize, u32)> = BTreeMap::new();
+        for violation in violations {
+            let penalty = self.violations.penalty_for(violation);
+            let entry = by_rule.entry(violation.id.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 += penalty;
+        }
+
+        let mut remaining = self.violations.cap.unwrap_or(u32::MAX);
+        for (rule, (count, penalty)) in by_rule {
+            let penalty = penalty.min(remaining);
+            remaining -= penalty;
+            let suffix = if count == 1 { "" } else { "s" };
+            push(
+                out,
+                HealthCategory::Violations,
+                penalty,
+                self.weights.violations,
+                format!("{count} {rule} violation{suffix}"),
+            );
+        }
+    }
+
+    fn push_signal_deductions(&self, signals: &HealthSignals, out: &mut Vec<HealthDeduction>) {
+        for rule in &self.signals {
+            let Some(value) = signals.get(&rule.signal).copied() else {
+                continue;
+            };
+            let above = rule.above.is_some_and(|bound| value > bound);
+            let below = rule.below.is_some_and(|bound| value < bound);
+            if !(above || below) {
+                continue;
+            }
+            let reason = rule
+                .reason
+                .clone()
+                .unwrap_or_else(|| format!("{} = {value}", rule.signal));
+            push(
+                out,
+                HealthCategory::Signal,
+                rule.penalty,
+                self.weights.signals,
+                reason,
+            );
+        }
+    }
+}
+
+impl ViolationPenalties {
+    fn penalty_for(&self, violation: &Violation) -> u32 {
+        self.rules
+            .get(&violation.id)
+            .or_else(|| self.severities.get(&violation.severity))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+fn push(
+    out: &mut Vec<HealthDeduction>,
+    category: HealthCategory,
+    penalty: u32,
+    weight: f64,
+    reason: String,
+) {
+    let points = (penalty as f64 * weight.max(0.0)).round() as u32;
+    if points == 0 {
+        return;
+    }
+    out.push(HealthDeduction {
+        category,
+        points,
+        reason,
+    });
+}
+
+fn threshold_penalty(thresholds: &[Threshold], ratio: f64) -> Option<u32> {
+    let mut sorted: Vec<&Threshold> = thresholds.iter().collect();
+    sorted.sort_by(|a, b| a.below.total_cmp(&b.below));
+    sorted
+        .into_iter()
+        .find(|threshold| ratio < threshold.below)
+        .map(|threshold| threshold.penalty)
+}
+
+fn thresholds(pairs: &[(f64, u32)]) -> Vec<Threshold> {
+    pairs
+        .iter()
+        .map(|(below, penalty)| Threshold {
+            below: *below,
+            penalty: *penalty,
+        })
+        .collect()
+}
+
+fn percent(ratio: f64) -> String {
+    format!("{:.1}%", ratio * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CoverageSource;
+
+    fn coverage(test_coverage: f64, doc_coverage: f64) -> CoverageReport {
+        CoverageReport {
+            code_files: 10,
+            test_files: (test_coverage * 10.0) as usize,
+            doc_files: (doc_coverage * 10.0) as usize,
+            test_coverage,
+            doc_coverage,
+            low_test_coverage: false,
+            low_doc_coverage: false,
+            source: CoverageSource::Heuristic,
+            line_coverage: None,
+            branch_coverage: None,
+            artifacts: Vec::new(),
+            public_items: 0,
+            documented_items: 0,
+            api_doc_coverage: None,
+        }
+    }
+
+    fn violation(id: &str, severity: Severity) -> Violation {
+        Violation {
+            id: id.to_string(),
+            message: "issue".to_string(),
+            severity,
+            path: None,
+            line: None,
+        }
+    }
+
+    #[test]
+    fn default_model_explains_each_deduction() {
+        let violations = vec![
+            violation("lint", Severity::Warning),
+            violation("lint", Severity::Warning),
+            violation("docs", Severity::Warning),
+        ];
+        let breakdown = HealthModel::default().evaluate(
+            &coverage(0.1, 0.0),
+            &violations,
+            &HealthSignals::new(),
+        );
+
+        assert_eq!(breakdown.score, 44);
+        assert_eq!(
+            breakdown.deductions,
+            vec![
+                HealthDeduction {
+                    category: HealthCategory::Tests,
+                    points: 25,
+                    reason: "test file ratio 10.0% (1/10)".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Docs,
+                    points: 25,
+                    reason: "doc coverage 0.0% (0/10)".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Violations,
+                    points: 2,
+                    reason: "1 docs violation".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Violations,
+                    points: 4,
+                    reason: "2 lint violations".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn api_doc_coverage_replaces_doc_file_ratio() {
+        let mut coverage = coverage(1.0, 1.0);
+        coverage.public_items = 8;
+        coverage.documented_items = 3;
+        coverage.api_doc_coverage = Some(0.375);
+        let violations = vec![violation(UNDOCUMENTED_API_RULE, Severity::Info)];
+
+        let breakdown =
+            HealthModel::default().evaluate(&coverage, &violations, &HealthSignals::new());
+
+        assert_eq!(breakdown.score, 85);
+        assert_eq!(
+            breakdown.deductions,
+            vec![HealthDeduction {
+                category: HealthCategory::Docs,
+                points: 15,
+                reason: "API doc coverage 37.5% (3/8 public items)".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn violation_penalties_resolve_rule_then_severity_and_cap() {
+        let mut model = HealthModel::default();
+        model.violations.rules.insert("secret".to_string(), 20);
+        model.violations.severities.insert(Severity::Error, 10);
+        model.violations.cap = Some(25);
+        let violations = vec![
+            violation("secret", Severity::Info),
+            violation("panic", Severity::Error),
+            violation("style", Severity::Info),
+        ];
+
+        let breakdown = model.evaluate(&coverage(1.0, 1.0), &violations, &HealthSignals::new());
+
+        let points: Vec<u32> = breakdown.deductions.iter().map(|d| d.points).collect();
+        assert_eq!(points, vec![10, 15]);
+        assert_eq!(breakdown.score, 75);
+    }
+
+    #[test]
+    fn measured_coverage_uses_line_and_branch_thresholds() {
+        let model = HealthModel {
+            branch_thresholds: thresholds(&[(0.5, 7)]),
+            ..HealthModel::default()
+        };
+        let mut measured = coverage(0.0, 1.0);
+        measured.source = CoverageSource::Measured;
+        measured.line_coverage = Some(0.7);
+        measured.branch_coverage = Some(0.25);
+
+        let breakdown = model.evaluate(&measured, &[], &HealthSignals::new());
+
+        assert_eq!(breakdown.score, 83);
+        assert_eq!(
+            breakdown.deductions[0].reason,
+            "measured line coverage 70.0%"
+        );
+        assert_eq!(
+            breakdown.deductions[1].reason,
+            "measured branch coverage 25.0%"
+        );
+    }
+
+    #[test]
+    fn weights_and_signals_adjust_the_score() {
+        let mut model = HealthModel::default();
+        model.weights.tests = 0.5;
+        model.weights.signals = 2.0;
+        model.signals = vec![
+            SignalRule {
+                signal: "last_commit_age_days".to_string(),
+                above: Some(365.0),
+                below: None,
+                penalty: 5,
+                reason: Some("no commits in a year".to_string()),
+            },
+            SignalRule {
+                signal: "active_contributors".to_string(),
+                above: None,
+                below: Some(2.0),
+                penalty: 3,
+                reason: None,
+            },
+            SignalRule {
+                signal: "missing".to_string(),
+                above: Some(0.0),
+                below: None,
+                penalty: 50,
+                reason: None,
+            },
+        ];
+        let mut signals = HealthSignals::new();
+        signals.insert("last_commit_age_days".to_string(), 400.0);
+        signals.insert("active_contributors".to_string(), 1.0);
+
+        let breakdown = model.evaluate(&coverage(0.0, 1.0), &[], &signals);
+
+        let points: Vec<u32> = breakdown.deductions.iter().map(|d| d.points).collect();
+        assert_eq!(points, vec![18, 10, 6]);
+        assert_eq!(breakdown.deductions[1].reason, "no commits in a year");
+        assert_eq!(breakdown.deductions[2].reason, "active_contributors = 1");
+        assert_eq!(breakdown.score, 66);
+    }
+
+    #[test]
+    fn default_model_penalises_abandoned_repositories() {
+        let mut signals = HealthSignals::new();
+        signals.insert("last_commit_age_days".to_string(), 500.0);
+
+        let breakdown = HealthModel::default().evaluate(&coverage(1.0, 1.0), &[], &signals);
+
+        assert_eq!(breakdown.score, 90);
+        assert_eq!(breakdown.deductions[0].category, HealthCategory::Signal);
+    }
+
+    #[test]
+    fn empty_repositories_are_not_penalised_and_scores_clamp() {
+        let mut empty = coverage(0.0, 0.0);
+        empty.code_files = 0;
+        let model = HealthModel::default();
+        assert_eq!(
+            model.evaluate(&empty, &[], &HealthSignals::new()).score,
+            100
+        );
+
+        let mut harsh = HealthModel::default();
+        harsh.violations.default = 90;
+        harsh.violations.cap = None;
+        let violations = vec![
+            violation("a", Severity::Error),
+            violation("b", Severity::Error),
+        ];
+        assert_eq!(
+            harsh
+                .evaluate(&empty, &violations, &HealthSignals::new())
+                .score,
+            0
+        );
+    }
+
+    #[test]
+    fn loads_models_from_toml_and_json() {
+        let model = HealthModel::from_toml_str(
+            r#"
+doc_thresholds = []
+
+[weights]
+violations = 0.5
+
+[violations]
+default = 4
+severities = { error = 10 }
+rules = { "type-correct" = 1 }
+
+[[signals]]
+signal = "bus_factor"
+below = 2
+penalty = 8
+"#,
+        )
+        .expect("toml model");
+        assert!(model.doc_thresholds.is_empty());
+        assert_eq!(model.weights.violations, 0.5);
+        assert_eq!(model.weights.tests, 1.0);
+        assert_eq!(model.violations.severities.get(&Severity::Error), Some(&10));
+        assert_eq!(model.violations.rules.get("type-correct"), Some(&1));
+        assert_eq!(model.violations.cap, Some(30));
+        assert_eq!(model.signals[0].below, Some(2.0));
+
+        let json = HealthModel::from_json_str(r#"{"weights": {"docs": 0}}"#).expect("json model");
+        assert_eq!(json.weights.docs, 0.0);
+
+        assert!(HealthModel::from_toml_str("unknown = 1").is_err());
+        assert!(HealthModel::from_json_str("[").is_err());
+    }
+
+    #[test]
+    fn from_path_picks_format_by_extension() {
+        let root = std::env::temp_dir().join(format!(
+            "shipshape_health_test_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("system time")
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(&root).expect("create temp dir");
+        std::fs::write(root.join("model.json"), r#"{"signals": []}"#).expect("write json");
+        std::fs::write(root.join("model.toml"), "signals = []\n").expect("write toml");
+
+        assert!(HealthModel::from_path(&root.join("model.json")).is_ok());
+        assert!(HealthModel::from_path(&root.join("model.toml")).is_ok());
+        assert!(HealthModel::from_path(&root.join("missing.toml")).is_err());
+
+        std::fs::remove_dir_all(&root).expect("cleanup temp dir");
+    }
+}
@@ -0,0 +1,445 @@
+//! Git history metrics: activity, ownership and churn.
+//!
+//! History is read through the `git` CLI, so any repository the user can
+//! clone can be inspected without extra dependencies. Parsing and aggregation
+//! are kept separate from the process call so they can be tested in isolation.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::domain::{DirectoryOwnership, FileChurn, HistoryReport};
+use crate::health::HealthSignals;
+use crate::{Result, ShipShapeError};
+
+/// Length of the activity window used for frequency and contributor counts.
+pub const HISTORY_WINDOW_DAYS: u32 = 90;
+/// Maximum number of commits read from very large histories.
+pub const MAX_HISTORY_COMMITS: usize = 5000;
+/// Number of files listed in the churn ranking.
+pub const TOP_CHURN_FILES: usize = 10;
+
+/// Signal name for [`HistoryReport::last_commit_age_days`].
+pub const SIGNAL_LAST_COMMIT_AGE_DAYS: &str = "last_commit_age_days";
+/// Signal name for [`HistoryReport::commits_per_week`].
+pub const SIGNAL_COMMITS_PER_WEEK: &str = "commits_per_week";
+/// Signal name for [`HistoryReport::active_contributors`].
+pub const SIGNAL_ACTIVE_CONTRIBUTORS: &str = "active_contributors";
+/// Signal name for the lowest directory bus factor.
+pub const SIGNAL_BUS_FACTOR: &str = "bus_factor";
+
+const SECONDS_PER_DAY: i64 = 86_400;
+const RECORD_SEPARATOR: char = '\u{1e}';
+const UNIT_SEPARATOR: char = '\u{1f}';
+
+#[derive(Debug, Clone, PartialEq)]
+struct CommitRecord {
+    timestamp: i64,
+    author: String,
+    files: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FileChange {
+    path: String,
+    added: u64,
+    deleted: u64,
+}
+
+/// Read git history for `root`, returning `None` when it is not a git work tree
+/// or has no commits yet.
+///
+/// When `root` is a subdirectory of a repository, only commits and paths
+/// under it are considered.
+pub fn inspect_history(root: &Path) -> Result<Option<HistoryReport>> {
+    if !git_succeeds(root, &["rev-parse", "--verify", "--quiet", "HEAD"]) {
+        return Ok(None);
+    }
+
+    let max_count = format!("--max-count={MAX_HISTORY_COMMITS}");
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "log",
+            "--no-merges",
+            "--no-renames",
+            "--relative",
+            "--numstat",
+            "--format=%x1e%at%x1f%aE",
+            max_count.as_str(),
+            "--",
+            ".",
+        ])
+        .output()?;
+    if !output.status.success() {
+        return Err(ShipShapeError::Other(format!(
+            "git log failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let commits = parse_git_log(&String::from_utf8_lossy(&output.stdout));
+    let Some(mut report) = summarize_history(&commits, unix_now()) else {
+        return Ok(None);
+    };
+    report
+        .bus_factor
+        .retain(|owner| owner.path == "." || root.join(&owner.path).is_dir());
+    report.churn.retain(|file| root.join(&file.path).is_file());
+    Ok(Some(report))
+}
+
+/// Expose history metrics as health model signals.
+pub fn history_signals(history: &HistoryReport) -> HealthSignals {
+    let mut signals = HealthSignals::new();
+    signals.insert(
+        SIGNAL_LAST_COMMIT_AGE_DAYS.to_string(),
+        history.last_commit_age_days as f64,
+    );
+    signals.insert(
+        SIGNAL_COMMITS_PER_WEEK.to_string(),
+        history.commits_per_week,
+    );
+    signals.insert(
+        SIGNAL_ACTIVE_CONTRIBUTORS.to_string(),
+        history.active_contributors as f64,
+    );
+    if let Some(lowest) = history
+        .bus_factor
+        .iter()
+        .map(|owner| owner.bus_factor)
+        .min()
+    {
+        signals.insert(SIGNAL_BUS_FACTOR.to_string(), lowest as f64);
+    }
+    signals
+}
+
+fn git_succeeds(root: &Path, args: &[&str]) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+fn parse_git_log(output: &str) -> Vec<CommitRecord> {
+    output
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next()?;
+            let (timestamp, author) = header.split_once(UNIT_SEPARATOR)?;
+            let timestamp = timestamp.trim().parse().ok()?;
+            let files = lines.filter_map(parse_numstat_line).collect();
+            Some(CommitRecord {
+                timestamp,
+                author: author.trim().to_lowercase(),
+                files,
+            })
+        })
+        .collect()
+}
+
+fn parse_numstat_line(line: &str) -> Option<FileChange> {
+    let mut parts = line.splitn(3, '\t');
+    let added = parts.next()?;
+    let deleted = parts.next()?;
+    let path = parts.next()?.trim();
+    if path.is_empty() {
+        return None;
+    }
+    // Binary files report "-" for both counts.
+    Some(FileChange {
+        path: path.to_string(),
+        added: added.parse().unwrap_or(0),
+        deleted: deleted.parse().unwrap_or(0),
+    })
+}
+
+fn summarize_history(commits: &[CommitRecord], now: i64) -> Option<HistoryReport> {
+    let last_commit_at = commits.iter().map(|commit| commit.timestamp).max()?;
+    let window_start = now - i64::from(HISTORY_WINDOW_DAYS) * SECONDS_PER_DAY;
+
+    let mut active = BTreeSet::new();
+    let mut everyone = BTreeSet::new();
+    let mut commits_in_window = 0;
+    let mut directories: BTreeMap<String, DirectoryStats> = BTreeMap::new();
+    let mut churn: BTreeMap<&str, FileChurn> = BTreeMap::new();
+
+    for commit in commits {
+        everyone.insert(commit.author.as_str());
+        if commit.timestamp >= window_start {
+            commits_in_window += 1;
+            active.insert(commit.author.as_str());
+        }
+
+        let mut touched = BTreeSet::new();
+        for file in &commit.files {
+            touched.insert(top_level_directory(&file.path));
+            let entry = churn
+                .entry(file.path.as_str())
+                .or_insert_with(|| FileChurn {
+                    path: file.path.clone(),
+                    commits: 0,
+                    lines_added: 0,
+                    lines_deleted: 0,
+                });
+            entry.commits += 1;
+            entry.lines_added += file.added;
+            entry.lines_deleted += file.deleted;
+        }
+        for directory in touched {
+            let stats = directories.entry(directory.to_string()).or_default();
+            stats.commits += 1;
+            *stats.authors.entry(commit.author.as_str()).or_default() += 1;
+        }
+    }
+
+    let bus_factor = directories
+        .into_iter()
+        .map(|(path, stats)| DirectoryOwnership {
+            path,
+            bus_factor: stats.bus_factor(),
+            authors: stats.authors.len(),
+            commits: stats.commits,
+        })
+        .collect();
+
+    let mut churn: Vec<FileChurn> = churn.into_values().collect();
+    churn.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| (b.lines_added + b.lines_deleted).cmp(&(a.lines_added + a.lines_deleted)))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    churn.truncate(TOP_CHURN_FILES);
+
+    let age_seconds = (now - last_commit_at).max(0);
+    Some(HistoryReport {
+        commits_analyzed: commits.len(),
+        last_commit_at,
+        last_commit_age_days: (age_seconds / SECONDS_PER_DAY) as u64,
+        window_days: HISTORY_WINDOW_DAYS,
+        commits_in_window,
+        commits_per_week: commits_in_window as f64 / (f64::from(HISTORY_WINDOW_DAYS) / 7.0),
+        active_contributors: active.len(),
+        total_contributors: everyone.len(),
+        bus_factor,
+        churn,
+    })
+}
+
+#[derive(Default)]
+struct DirectoryStats<'a> {
+    commits: usize,
+    authors: BTreeMap<&'a str, usize>,
+}
+
+impl DirectoryStats<'_> {
+    fn bus_factor(&self) -> usize {
+        let mut counts: Vec<usize> = self.authors.values().copied().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        let total: usize = counts.iter().sum();
+        let mut covered = 0;
+        for (index, count) in counts.iter().enumerate() {
+            covered += count;
+            if covered * 2 > total {
+                return index + 1;
+            }
+        }
+        counts.len()
+    }
+}
+
+fn top_level_directory(path: &str) -> &str {
+    match path.split_once('/') {
+        Some((directory, _)) => directory,
+        None => ".",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    fn log_entry(timestamp: i64, author: &str, numstat: &[&str]) -> String {
+        let mut entry = format!("\u{1e}{timestamp}\u{1f}{author}\n\n");
+        for line in numstat {
+            entry.push_str(line);
+            entry.push('\n');
+        }
+        entry
+    }
+
+    #[test]
+    fn parses_numstat_records() {
+        let output = [
+            log_entry(
+                NOW,
+                "Ada@Example.com",
+                &["3\t1\tsrc/lib.rs", "-\t-\tlogo.png"],
+            ),
+            log_entry(NOW - DAY, "bob@example.com", &[]),
+        ]
+        .concat();
+
+        let commits = parse_git_log(&output);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].author, "ada@example.com");
+        assert_eq!(
+            commits[0].files,
+            vec![
+                FileChange {
+                    path: "src/lib.rs".to_string(),
+                    added: 3,
+                    deleted: 1,
+                },
+                FileChange {
+                    path: "logo.png".to_string(),
+                    added: 0,
+                    deleted: 0,
+                },
+            ]
+        );
+        assert!(commits[1].files.is_empty());
+    }
+
+    #[test]
+    fn summarizes_activity_ownership_and_churn() {
+        let output = [
+            log_entry(
+                NOW - 2 * DAY,
+                "ada",
+                &["10\t2\tsrc/lib.rs", "1\t0\tREADME.md"],
+            ),
+            log_entry(NOW - 10 * DAY, "ada", &["4\t4\tsrc/lib.rs"]),
+            log_entry(NOW - 20 * DAY, "bob", &["5\t0\tdocs/guide.md"]),
+            log_entry(NOW - 200 * DAY, "cy", &["1\t1\tsrc/main.rs"]),
+        ]
+        .concat();
+        let commits = parse_git_log(&output);
+
+        let report = summarize_history(&commits, NOW).expect("report");
+
+        assert_eq!(report.commits_analyzed, 4);
+        assert_eq!(report.last_commit_age_days, 2);
+        assert_eq!(report.commits_in_window, 3);
+        assert_eq!(report.active_contributors, 2);
+        assert_eq!(report.total_contributors, 3);
+        assert!((report.commits_per_week - 3.0 / (90.0 / 7.0)).abs() < 1e-9);
+
+        let src = report
+            .bus_factor
+            .iter()
+            .find(|owner| owner.path == "src")
+            .expect("src ownership");
+        assert_eq!(src.bus_factor, 1);
+        assert_eq!(src.authors, 2);
+        assert_eq!(src.commits, 3);
+        assert!(report.bus_factor.iter().any(|owner| owner.path == "."));
+
+        assert_eq!(report.churn[0].path, "src/lib.rs");
+        assert_eq!(report.churn[0].commits, 2);
+        assert_eq!(report.churn[0].lines_added, 14);
+        assert_eq!(report.churn[1].path, "docs/guide.md");
+    }
+
+    #[test]
+    fn bus_factor_counts_authors_covering_majority() {
+        let mut stats = DirectoryStats::default();
+        stats.authors.insert("a", 2);
+        stats.authors.insert("b", 2);
+        stats.authors.insert("c", 1);
+        assert_eq!(stats.bus_factor(), 2);
+    }
+
+    #[test]
+    fn empty_history_has_no_report() {
+        assert!(summarize_history(&[], NOW).is_none());
+    }
+
+    #[test]
+    fn exposes_history_signals() {
+        let commits = parse_git_log(&log_entry(NOW - 400 * DAY, "ada", &["1\t0\tsrc/lib.rs"]));
+        let report = summarize_history(&commits, NOW).expect("report");
+
+        let signals = history_signals(&report);
+
+        assert_eq!(signals[SIGNAL_LAST_COMMIT_AGE_DAYS], 400.0);
+        assert_eq!(signals[SIGNAL_ACTIVE_CONTRIBUTORS], 0.0);
+        assert_eq!(signals[SIGNAL_COMMITS_PER_WEEK], 0.0);
+        assert_eq!(signals[SIGNAL_BUS_FACTOR], 1.0);
+    }
+
+    #[test]
+    fn inspects_git_repository() {
+        let root = std::env::temp_dir().join(unique_dir_name());
+        std::fs::create_dir_all(root.join("src")).expect("create repo");
+        git(&root, &["init", "-q"]);
+        std::fs::write(root.join("src/lib.rs"), "pub fn demo() {}\n").expect("write file");
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "initial"]);
+
+        let report = inspect_history(&root).expect("history").expect("report");
+
+        assert_eq!(report.commits_analyzed, 1);
+        assert_eq!(report.active_contributors, 1);
+        assert_eq!(report.churn[0].path, "src/lib.rs");
+        assert_eq!(report.bus_factor[0].path, "src");
+
+        std::fs::remove_dir_all(&root).expect("cleanup repo");
+    }
+
+    #[test]
+    fn non_repository_has_no_history() {
+        let root = std::env::temp_dir().join(unique_dir_name());
+        std::fs::create_dir_all(&root).expect("create dir");
+
+        assert!(inspect_history(&root).expect("history").is_none());
+
+        std::fs::remove_dir_all(&root).expect("cleanup dir");
+    }
+
+    fn git(root: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args([
+                "-c",
+                "user.name=Ship Shape",
+                "-c",
+                "user.email=ship@example.com",
+            ])
+            .args(args)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn unique_dir_name() -> PathBuf {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        let count = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        PathBuf::from(format!("shipshape_core_history_test_{nanos}_{count}"))
+    }
+}
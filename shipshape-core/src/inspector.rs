@@ -1,51 +1,131 @@
 //! Repository inspection utilities.
 
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use tokei::{Config, LanguageType};
 
-use crate::domain::{CoverageReport, LanguageDistribution, Violation};
+use crate::cache::{AuditCache, CACHE_FORMAT, LANGUAGE_STATS_NAMESPACE, cache_key, tree_hash};
+use crate::coverage::{apply_measured_coverage, is_coverage_artifact, load_measured_coverage};
+use crate::domain::{CoverageReport, CoverageSource, LanguageDistribution, Violation};
 use crate::error::Result;
 use crate::fs::FileSystem;
+use crate::generated::GeneratedFiles;
+use crate::health::{HealthModel, HealthSignals};
+
+/// A file classified by `tokei`, with its contents.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// Path to the file.
+    pub path: PathBuf,
+    /// Language detected by `tokei`.
+    pub language: LanguageType,
+    /// File contents.
+    pub contents: String,
+}
 
 /// Inspects a repository using `tokei` to compute language distribution.
+///
+/// Generated and vendored files are skipped unless
+/// [`include_generated`](Self::include_generated) is set. With
+/// [`with_cache`](Self::with_cache), distributions are reused for unchanged
+/// trees.
 pub struct TokeiInspector<F: FileSystem> {
     fs: F,
     config: Config,
+    include_generated: bool,
+    cache: Option<AuditCache>,
 }
 
 impl<F: FileSystem> TokeiInspector<F> {
     /// Create a new inspector with default `tokei` configuration.
     pub fn new(fs: F) -> Self {
+        Self::with_config(fs, Config::default())
+    }
+
+    /// Create a new inspector with a custom `tokei` configuration.
+    pub fn with_config(fs: F, config: Config) -> Self {
         Self {
             fs,
-            config: Config::default(),
+            config,
+            include_generated: false,
+            cache: None,
         }
     }
 
-    /// Create a new inspector with a custom `tokei` configuration.
-    pub fn with_config(fs: F, config: Config) -> Self {
-        Self { fs, config }
+    /// Also inspect generated and vendored files.
+    pub fn include_generated(mut self, include: bool) -> Self {
+        self.include_generated = include;
+        self
     }
 
-    /// Inspect the repository and return language distribution percentages.
-    pub fn inspect(&self, root: &Path) -> Result<LanguageDistribution> {
-        let files = self.fs.list_files(root)?;
-        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
-        let mut total = 0usize;
+    /// Reuse language distributions stored in `cache` for unchanged trees.
+    pub fn with_cache(mut self, cache: AuditCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
 
-        for path in files {
+    /// Read every file `tokei` recognises, tagged with its language.
+    pub fn read_source_files(&self, root: &Path) -> Result<Vec<SourceFile>> {
+        let classifier = (!self.include_generated).then(|| GeneratedFiles::load(&self.fs, root));
+        let mut sources = Vec::new();
+        for path in self.fs.list_files(root)? {
             let Some(language) = LanguageType::from_path(&path, &self.config) else {
                 continue;
             };
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            if classifier
+                .as_ref()
+                .is_some_and(|classifier| classifier.classify_path(relative).is_some())
+            {
+                continue;
+            }
             let contents = self.fs.read_to_string(&path)?;
-            let lines = count_lines(&contents);
+            if classifier
+                .as_ref()
+                .is_some_and(|classifier| classifier.classify(relative, &contents).is_some())
+            {
+                continue;
+            }
+            sources.push(SourceFile {
+                path,
+                language,
+                contents,
+            });
+        }
+        Ok(sources)
+    }
+
+    /// Inspect the repository and return language distribution percentages.
+    pub fn inspect(&self, root: &Path) -> Result<LanguageDistribution> {
+        let Some(cache) = &self.cache else {
+            return self.count_languages(root);
+        };
+        let key = cache_key(&[
+            CACHE_FORMAT,
+            &tree_hash(&self.fs, root)?,
+            &self.include_generated.to_string(),
+            &format!("{:?}", self.config),
+        ]);
+        if let Some(distribution) = cache.get(LANGUAGE_STATS_NAMESPACE, &key) {
+            return Ok(distribution);
+        }
+        let distribution = self.count_languages(root)?;
+        let _ = cache.put(LANGUAGE_STATS_NAMESPACE, &key, &distribution);
+        Ok(distribution)
+    }
+
+    fn count_languages(&self, root: &Path) -> Result<LanguageDistribution> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        let mut total = 0usize;
+
+        for source in self.read_source_files(root)? {
+            let lines = count_lines(&source.contents);
             if lines == 0 {
                 continue;
             }
             total += lines;
-            let key = language.to_string();
+            let key = source.language.to_string();
             *counts.entry(key).or_insert(0) += lines;
         }
 
@@ -64,13 +144,29 @@ impl<F: FileSystem> TokeiInspector<F> {
 }
 
 /// Inspect a repository for test and documentation coverage heuristics.
+///
+/// Coverage artifacts found in the repository (`lcov.info`, `coverage.xml`,
+/// `coverage.out`, ...) replace the test file heuristic with measured data.
 pub fn inspect_coverage<F: FileSystem>(fs: &F, root: &Path) -> Result<CoverageReport> {
+    inspect_coverage_with_artifacts(fs, root, &[])
+}
+
+/// Inspect coverage, additionally ingesting the given coverage artifacts.
+pub fn inspect_coverage_with_artifacts<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    artifacts: &[PathBuf],
+) -> Result<CoverageReport> {
     let files = fs.list_files(root)?;
     let mut code_files = 0usize;
     let mut test_files = 0usize;
     let mut doc_files = 0usize;
+    let mut candidates = artifacts.to_vec();
 
     for path in files {
+        if is_coverage_artifact(&path) {
+            candidates.push(path.clone());
+        }
         if is_doc_file(&path) {
             doc_files += 1;
         }
@@ -85,7 +181,7 @@ pub fn inspect_coverage<F: FileSystem>(fs: &F, root: &Path) -> Result<CoverageRe
     let (test_coverage, doc_coverage, low_test_coverage, low_doc_coverage) =
         compute_coverage_metrics(code_files, test_files, doc_files);
 
-    Ok(CoverageReport {
+    let mut report = CoverageReport {
         code_files,
         test_files,
         doc_files,
@@ -93,17 +189,29 @@ pub fn inspect_coverage<F: FileSystem>(fs: &F, root: &Path) -> Result<CoverageRe
         doc_coverage,
         low_test_coverage,
         low_doc_coverage,
-    })
+        source: CoverageSource::Heuristic,
+        line_coverage: None,
+        branch_coverage: None,
+        artifacts: Vec::new(),
+        public_items: 0,
+        documented_items: 0,
+        api_doc_coverage: None,
+    };
+    if let Some((measured, used)) = load_measured_coverage(fs, &candidates)? {
+        apply_measured_coverage(&mut report, &measured, &used);
+    }
+
+    Ok(report)
 }
 
 /// Compute a heuristic health score using coverage metrics and violations.
+///
+/// Uses the default [`HealthModel`]; call [`HealthModel::evaluate`] directly
+/// for a custom model or an explained breakdown.
 pub fn compute_health_score(coverage: &CoverageReport, violations: &[Violation]) -> u8 {
-    let mut score: i32 = 100;
-    score -= test_coverage_penalty(coverage);
-    score -= doc_coverage_penalty(coverage);
-    score -= violation_penalty(violations.len());
-
-    score.clamp(0, 100) as u8
+    HealthModel::default()
+        .evaluate(coverage, violations, &HealthSignals::new())
+        .score
 }
 
 fn compute_coverage_metrics(
@@ -129,48 +237,11 @@ fn compute_coverage_metrics(
     )
 }
 
-fn test_coverage_penalty(coverage: &CoverageReport) -> i32 {
-    if coverage.code_files == 0 {
-        return 0;
-    }
-
-    if coverage.test_coverage < 0.10 {
-        35
-    } else if coverage.test_coverage < 0.20 {
-        25
-    } else if coverage.test_coverage < 0.40 {
-        10
-    } else {
-        0
-    }
-}
-
-fn doc_coverage_penalty(coverage: &CoverageReport) -> i32 {
-    if coverage.code_files == 0 {
-        return 0;
-    }
-
-    if coverage.doc_coverage < 0.05 {
-        25
-    } else if coverage.doc_coverage < 0.10 {
-        15
-    } else if coverage.doc_coverage < 0.20 {
-        5
-    } else {
-        0
-    }
-}
-
-fn violation_penalty(violations: usize) -> i32 {
-    let penalty = violations as i32 * 2;
-    penalty.min(30)
-}
-
 fn count_lines(text: &str) -> usize {
     text.lines().count()
 }
 
-fn is_code_file(path: &Path) -> bool {
+pub(crate) fn is_code_file(path: &Path) -> bool {
     let ext = path
         .extension()
         .and_then(|ext| ext.to_str())
@@ -225,7 +296,7 @@ fn is_doc_file(path: &Path) -> bool {
     matches!(ext.as_str(), "md" | "mdx" | "rst" | "adoc" | "txt")
 }
 
-fn is_test_file(path: &Path) -> bool {
+pub(crate) fn is_test_file(path: &Path) -> bool {
     if path_components_match(path, &["test", "tests", "spec", "specs"]) {
         return true;
     }
@@ -260,8 +331,11 @@ fn path_components_match(path: &Path, segments: &[&str]) -> bool {
 #[cfg(test)]
 mod tests {
     use super::{
-        CoverageReport, TokeiInspector, Violation, compute_health_score, inspect_coverage,
+        CoverageReport, CoverageSource, TokeiInspector, Violation, compute_health_score,
+        inspect_coverage, inspect_coverage_with_artifacts,
     };
+    use crate::domain::Severity;
+    use crate::error::ShipShapeError;
     use crate::fs::MockFileSystem;
     use std::path::{Path, PathBuf};
 
@@ -280,6 +354,9 @@ mod tests {
         fs.expect_read_to_string()
             .withf(|path| path == Path::new("src/app.py"))
             .returning(|_| Ok("print('hi')\n".to_string()));
+        fs.expect_read_to_string()
+            .withf(|path| path.ends_with(".gitattributes"))
+            .returning(|path| Err(ShipShapeError::Other(format!("{} missing", path.display()))));
 
         let inspector = TokeiInspector::new(fs);
         let distribution = inspector
@@ -358,15 +435,28 @@ mod tests {
             doc_coverage: 0.0,
             low_test_coverage: true,
             low_doc_coverage: true,
+            source: CoverageSource::Heuristic,
+            line_coverage: None,
+            branch_coverage: None,
+            artifacts: Vec::new(),
+            public_items: 0,
+            documented_items: 0,
+            api_doc_coverage: None,
         };
         let violations = vec![
             Violation {
                 id: "v1".to_string(),
                 message: "first".to_string(),
+                severity: Severity::Warning,
+                path: None,
+                line: None,
             },
             Violation {
                 id: "v2".to_string(),
                 message: "second".to_string(),
+                severity: Severity::Warning,
+                path: None,
+                line: None,
             },
         ];
 
@@ -374,4 +464,69 @@ mod tests {
 
         assert!(score < 100);
     }
+
+    #[test]
+    fn coverage_prefers_measured_artifacts() {
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(|_| {
+            Ok(vec![
+                PathBuf::from("/repo/src/main.rs"),
+                PathBuf::from("/repo/src/lib.rs"),
+                PathBuf::from("/repo/target/lcov.info"),
+            ])
+        });
+        fs.expect_read_to_string()
+            .withf(|path| path == Path::new("/repo/target/lcov.info"))
+            .returning(|_| Ok("SF:src/lib.rs\nLF:10\nLH:9\nend_of_record\n".to_string()));
+        fs.expect_read_to_string()
+            .withf(|path| path == Path::new("/ci/coverage.out"))
+            .returning(|_| Ok("mode: set\nexample.com/a.go:1.1,2.2 10 0\n".to_string()));
+
+        let coverage = inspect_coverage(&fs, Path::new("/repo")).expect("coverage");
+        assert_eq!(coverage.source, CoverageSource::Measured);
+        assert_eq!(coverage.line_coverage, Some(0.9));
+        assert_eq!(coverage.test_files, 0);
+        assert!(!coverage.low_test_coverage);
+
+        let combined = inspect_coverage_with_artifacts(
+            &fs,
+            Path::new("/repo"),
+            &[PathBuf::from("/ci/coverage.out")],
+        )
+        .expect("coverage");
+        assert_eq!(combined.line_coverage, Some(0.45));
+        assert!(combined.low_test_coverage);
+        assert_eq!(combined.artifacts.len(), 2);
+    }
+
+    #[test]
+    fn health_score_prefers_measured_coverage() {
+        let heuristic = CoverageReport {
+            code_files: 10,
+            test_files: 0,
+            doc_files: 10,
+            test_coverage: 0.0,
+            doc_coverage: 1.0,
+            low_test_coverage: true,
+            low_doc_coverage: false,
+            source: CoverageSource::Heuristic,
+            line_coverage: None,
+            branch_coverage: None,
+            artifacts: Vec::new(),
+            public_items: 0,
+            documented_items: 0,
+            api_doc_coverage: None,
+        };
+        let measured = CoverageReport {
+            source: CoverageSource::Measured,
+            line_coverage: Some(0.92),
+            branch_coverage: Some(0.8),
+            low_test_coverage: false,
+            ..heuristic.clone()
+        };
+
+        assert_eq!(compute_health_score(&heuristic, &[]), 65);
+        assert_eq!(compute_health_score(&measured, &[]), 100);
+        assert_eq!(measured.effective_test_coverage(), 0.92);
+    }
 }
@@ -4,29 +4,85 @@
 //! This crate contains the domain types and inspection primitives that power
 //! the broader ShipShape platform.
 
+pub mod api_docs;
+pub mod archive;
+pub mod cache;
+pub mod complexity;
+pub mod coverage;
+pub mod diff;
 pub mod domain;
 pub mod drydock;
+pub mod duplication;
 pub mod error;
 pub mod fs;
+pub mod generated;
+pub mod git_tree;
+pub mod health;
+pub mod history;
 pub mod inspector;
+pub mod license;
 pub mod mechanic;
 /// Mechanic registry and orchestration helpers.
 pub mod mechanics;
+pub mod packaging;
 pub mod pr_template;
+pub mod projects;
 pub mod report;
+pub mod test_mapping;
+pub mod toolchain;
+pub mod verify;
 
-pub use domain::{CoverageReport, FleetReport, LanguageDistribution, Violation};
-pub use drydock::generate_ci_config;
+pub use api_docs::{ApiDocCoverage, apply_api_doc_coverage, inspect_api_docs};
+pub use archive::{ArchiveFileSystem, ArchiveFormat};
+pub use cache::{AuditCache, cached_mechanic_audit, tree_hash};
+pub use complexity::{
+    ComplexityThresholds, SourceLanguage, complexity_signals, inspect_complexity,
+};
+pub use coverage::{CoverageFormat, MeasuredCoverage};
+pub use diff::ChangedLines;
+pub use domain::{
+    CodeSpan, ComplexityReport, CoverageReport, CoverageSource, DetectedPackageManager,
+    DetectedStack, DiffReport, DirectoryOwnership, DuplicateBlock, DuplicationReport, ExcludedFile,
+    ExcludedKind, FileChurn, FleetReport, HealthBreakdown, HealthCategory, HealthDeduction,
+    HistoryReport, JobOutcome, JobVerification, LanguageDistribution, LicenseFile, LicenseReport,
+    PackageManager, Project, ProjectKind, Severity, TestMapping, TestMappingReport, TestRunner,
+    ToolchainRequirement, Verification, Violation,
+};
+pub use drydock::{
+    CiTarget, Drydock, DrydockJob, DrydockOptions, detect_stacks, generate_ci_config,
+    generate_dockerignore, generate_drydock, project_stacks,
+};
+pub use duplication::{
+    DuplicationConfig, SourceCorpus, duplication_signals, find_cross_repo_duplicates,
+    inspect_duplication,
+};
 pub use error::{Result, ShipShapeError};
-pub use fs::{FileSystem, StdFileSystem};
+pub use fs::{FileSystem, MemoryFileSystem, StdFileSystem};
+pub use generated::{
+    GeneratedFiles, SkipExcluded, detect_generated_files, drop_excluded_violations,
+};
+pub use git_tree::GitTreeFileSystem;
+pub use health::{HealthModel, HealthSignals};
+pub use history::{history_signals, inspect_history};
 pub use inspector::TokeiInspector;
+pub use license::{LicensePolicy, inspect_license};
 pub use mechanic::Mechanic;
 pub use mechanics::build_mechanics;
+pub use packaging::{detect_package_manager, detect_test_runner};
 pub use pr_template::{
     PrTemplateContext, SHIPSHAPE_CI, SHIPSHAPE_FIXES, SHIPSHAPE_STATS, ensure_placeholders,
     find_pr_template, interpolate_pr_template,
 };
+pub use projects::{ProjectFileSystem, discover_projects, kind_markers, project_kinds};
 pub use report::{
-    CloneStatus, LaunchReport, RefitReport, RepoReport, format_language_stats,
-    render_audit_markdown, render_json, render_launch_markdown, render_refit_markdown,
+    CloneStatus, LaunchReport, ProjectLaunch, ProjectReport, RefitReport, RepoReport,
+    complexity_lines, coverage_lines, diff_lines, duplication_lines, excluded_files_lines,
+    format_health_deduction, format_language_stats, format_violation, history_lines, license_lines,
+    project_lines, render_audit_markdown, render_json, render_launch_markdown,
+    render_refit_markdown, stack_lines, test_mapping_lines, verification_lines,
+};
+pub use test_mapping::{inspect_test_mapping, test_mapping_signals};
+pub use toolchain::{ToolchainDefaults, detect_toolchain, resolve_version};
+pub use verify::{
+    DEFAULT_VERIFY_TIMEOUT, LOG_TAIL_LINES, VerifyOptions, VerifyRuntime, verify_local,
 };
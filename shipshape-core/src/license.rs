@@ -0,0 +1,599 @@
+//! License detection and SPDX compliance checks.
+//!
+//! Identifies root license files (`LICENSE`, `COPYING`, ...) by SPDX id, then
+//! scans the header of every source file for an `SPDX-License-Identifier`
+//! tag or recognisable license text. Findings become violations: a missing
+//! or unrecognised repository license, file licenses that cannot be combined
+//! with it, licenses outside the policy's allow-list and, when the policy
+//! requires them, files without a license header. Compatibility rules are a
+//! conservative approximation, not legal advice.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::coverage::is_dependency_dir;
+use crate::domain::{FileLicense, LicenseFile, LicenseReport, Severity, Violation};
+use crate::error::{Result, ShipShapeError};
+use crate::fs::FileSystem;
+use crate::inspector::is_code_file;
+
+/// Violation id for repositories without a license file.
+pub const LICENSE_MISSING_RULE: &str = "license-missing";
+/// Violation id for license files whose text was not recognised.
+pub const LICENSE_UNKNOWN_RULE: &str = "license-unknown";
+/// Violation id for files licensed incompatibly with the repository.
+pub const LICENSE_INCOMPATIBLE_RULE: &str = "license-incompatible";
+/// Violation id for licenses outside the policy allow-list.
+pub const LICENSE_NOT_ALLOWED_RULE: &str = "license-not-allowed";
+/// Violation id for source files without a license header.
+pub const LICENSE_HEADER_MISSING_RULE: &str = "license-header-missing";
+
+/// Lines at the top of a source file searched for a license header.
+const HEADER_LINES: usize = 30;
+const SPDX_TAG: &str = "SPDX-License-Identifier:";
+
+/// Recognisable phrases per SPDX id, most specific first.
+const FINGERPRINTS: &[(&str, &[&str])] = &[
+    (
+        "AGPL-3.0-only",
+        &["gnu affero general public license", "version 3"],
+    ),
+    (
+        "LGPL-3.0-only",
+        &["gnu lesser general public license", "version 3"],
+    ),
+    (
+        "LGPL-2.1-only",
+        &["gnu lesser general public license", "version 2.1"],
+    ),
+    ("GPL-3.0-only", &["gnu general public license", "version 3"]),
+    ("GPL-2.0-only", &["gnu general public license", "version 2"]),
+    ("Apache-2.0", &["apache license", "version 2.0"]),
+    ("MPL-2.0", &["mozilla public license", "2.0"]),
+    ("EPL-2.0", &["eclipse public license", "2.0"]),
+    ("BSL-1.0", &["boost software license"]),
+    (
+        "Unlicense",
+        &["free and unencumbered software released into the public domain"],
+    ),
+    ("CC0-1.0", &["cc0 1.0 universal"]),
+    (
+        "ISC",
+        &["permission to use, copy, modify, and/or distribute this software for any purpose"],
+    ),
+    ("MIT", &["permission is hereby granted, free of charge"]),
+    (
+        "BSD-3-Clause",
+        &[
+            "redistribution and use in source and binary forms",
+            "neither the name",
+        ],
+    ),
+    (
+        "BSD-2-Clause",
+        &["redistribution and use in source and binary forms"],
+    ),
+    (
+        "Zlib",
+        &["altered source versions must be plainly marked as such"],
+    ),
+];
+
+/// Organisation rules applied on top of license detection.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LicensePolicy {
+    /// Require an SPDX tag or license text in every source file header.
+    pub require_headers: bool,
+    /// SPDX identifiers permitted in the repository; empty allows any.
+    pub allowed: Vec<String>,
+}
+
+impl LicensePolicy {
+    /// Load a policy from a `.toml` or `.json` file.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let is_json = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        if is_json {
+            Self::from_json_str(&contents)
+        } else {
+            Self::from_toml_str(&contents)
+        }
+    }
+
+    /// Parse a policy from TOML.
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        toml::from_str(contents)
+            .map_err(|err| ShipShapeError::Other(format!("invalid license policy: {err}")))
+    }
+
+    /// Parse a policy from JSON.
+    pub fn from_json_str(contents: &str) -> Result<Self> {
+        serde_json::from_str(contents)
+            .map_err(|err| ShipShapeError::Other(format!("invalid license policy: {err}")))
+    }
+
+    fn allows(&self, id: &str) -> bool {
+        self.allowed.is_empty() || self.allowed.iter().any(|allowed| allowed == id)
+    }
+}
+
+/// Detect the repository license and check source headers against `policy`.
+///
+/// Every code file is scanned, including vendored third-party code; files in
+/// dependency directories are exempt from the header requirement.
+pub fn inspect_license<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    policy: &LicensePolicy,
+) -> Result<(LicenseReport, Vec<Violation>)> {
+    let mut files = fs.list_files(root)?;
+    files.sort();
+    let mut license_files = Vec::new();
+    let mut headers = Vec::new();
+    let mut missing_headers = Vec::new();
+    let mut files_scanned = 0;
+
+    for path in files {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let display = relative.to_string_lossy().replace('\\', "/");
+        if is_license_file(relative) {
+            let spdx = fs
+                .read_to_string(&path)
+                .ok()
+                .and_then(|text| identify_license_text(&text));
+            license_files.push(LicenseFile {
+                path: display,
+                spdx,
+            });
+            continue;
+        }
+        if !is_code_file(relative) || has_component(relative, "node_modules") {
+            continue;
+        }
+        let Ok(source) = fs.read_to_string(&path) else {
+            continue;
+        };
+        files_scanned += 1;
+        match header_license(&source) {
+            Some((spdx, line)) => headers.push((display, spdx, line)),
+            None if policy.require_headers && !is_dependency_dir(relative) => {
+                missing_headers.push(display)
+            }
+            None => {}
+        }
+    }
+
+    let repo_ids: Vec<String> = license_files
+        .iter()
+        .filter_map(|file| file.spdx.as_deref())
+        .flat_map(license_ids)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let mut violations = Vec::new();
+    if license_files.is_empty() {
+        violations.push(violation(
+            LICENSE_MISSING_RULE,
+            "No LICENSE file found".to_string(),
+            Severity::Error,
+            None,
+            None,
+        ));
+    }
+    for file in &license_files {
+        match &file.spdx {
+            None => violations.push(violation(
+                LICENSE_UNKNOWN_RULE,
+                "License text not recognised as an SPDX license".to_string(),
+                Severity::Warning,
+                Some(&file.path),
+                None,
+            )),
+            Some(spdx) if !license_ids(spdx).iter().any(|id| policy.allows(id)) => {
+                violations.push(violation(
+                    LICENSE_NOT_ALLOWED_RULE,
+                    format!("Repository license `{spdx}` is not allowed by policy"),
+                    Severity::Error,
+                    Some(&file.path),
+                    None,
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut header_licenses = BTreeMap::new();
+    let mut incompatible = Vec::new();
+    for (path, spdx, line) in &headers {
+        *header_licenses.entry(spdx.clone()).or_insert(0) += 1;
+        let ids = license_ids(spdx);
+        if !repo_ids.is_empty()
+            && !ids
+                .iter()
+                .any(|id| repo_ids.iter().any(|repo| is_compatible(id, repo)))
+        {
+            violations.push(violation(
+                LICENSE_INCOMPATIBLE_RULE,
+                format!(
+                    "`{spdx}` is incompatible with the repository license `{}`",
+                    repo_ids.join(" OR ")
+                ),
+                Severity::Error,
+                Some(path),
+                Some(*line),
+            ));
+            incompatible.push(FileLicense {
+                path: path.clone(),
+                spdx: spdx.clone(),
+            });
+        }
+        if !ids.iter().any(|id| policy.allows(id)) {
+            violations.push(violation(
+                LICENSE_NOT_ALLOWED_RULE,
+                format!("File license `{spdx}` is not allowed by policy"),
+                Severity::Error,
+                Some(path),
+                Some(*line),
+            ));
+        }
+    }
+    for path in &missing_headers {
+        violations.push(violation(
+            LICENSE_HEADER_MISSING_RULE,
+            "Missing license header".to_string(),
+            Severity::Warning,
+            Some(path),
+            Some(1),
+        ));
+    }
+
+    let report = LicenseReport {
+        license: (!repo_ids.is_empty()).then(|| repo_ids.join(" OR ")),
+        license_files,
+        files_scanned,
+        files_with_headers: headers.len(),
+        header_licenses,
+        incompatible,
+    };
+    Ok((report, violations))
+}
+
+/// Identify the SPDX id of a license file from its tag or text.
+pub fn identify_license_text(text: &str) -> Option<String> {
+    if let Some((spdx, _)) = spdx_tag(text) {
+        return Some(spdx);
+    }
+    fingerprint(&normalize(text))
+}
+
+/// License declared in the header of a source file, with its 1-based line.
+///
+/// Prefers an `SPDX-License-Identifier` tag; otherwise recognises common
+/// license notices, including GPL "or any later version" grants.
+pub fn header_license(source: &str) -> Option<(String, u32)> {
+    let header: Vec<&str> = source.lines().take(HEADER_LINES).collect();
+    if let Some(found) = spdx_tag(&header.join("\n")) {
+        return Some(found);
+    }
+    let text = normalize(&header.join("\n"));
+    let mut spdx = fingerprint(&text)?;
+    if text.contains("any later version") {
+        spdx = spdx.replace("-only", "-or-later");
+    }
+    let line = header
+        .iter()
+        .position(|line| line.to_lowercase().contains("licen"))
+        .unwrap_or(0);
+    Some((spdx, line as u32 + 1))
+}
+
+/// Whether code under `file` may be combined into a work licensed `repo`.
+///
+/// Permissive and weak copyleft licenses are accepted anywhere (except
+/// Apache-2.0 under GPL-2.0-only); strong copyleft only within a compatible
+/// GPL family. Unknown licenses are not flagged.
+pub fn is_compatible(file: &str, repo: &str) -> bool {
+    if file == repo {
+        return true;
+    }
+    let file_family = family(file);
+    let repo_family = family(repo);
+    match file_family {
+        "MIT" | "Apache-2.0" | "BSD-2-Clause" | "BSD-3-Clause" | "ISC" | "Unlicense"
+        | "BSL-1.0" | "CC0-1.0" | "Zlib" | "0BSD" => {
+            !(file_family == "Apache-2.0" && repo == "GPL-2.0-only")
+        }
+        "GPL-2.0" => match repo_family {
+            "GPL-2.0" => true,
+            "GPL-3.0" | "AGPL-3.0" => file.ends_with("-or-later"),
+            _ => false,
+        },
+        "GPL-3.0" | "AGPL-3.0" => matches!(repo_family, "GPL-3.0" | "AGPL-3.0"),
+        _ => true,
+    }
+}
+
+/// Split an SPDX expression into normalised license ids.
+fn license_ids(expression: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut tokens = expression
+        .split(|ch: char| ch.is_whitespace() || ch == '(' || ch == ')')
+        .filter(|token| !token.is_empty());
+    while let Some(token) = tokens.next() {
+        match token {
+            "OR" | "AND" | "or" | "and" => {}
+            "WITH" | "with" => {
+                tokens.next();
+            }
+            id => ids.push(normalize_id(id)),
+        }
+    }
+    ids
+}
+
+/// Map deprecated GNU ids (`GPL-2.0`, `GPL-2.0+`) to their `-only`/`-or-later` forms.
+fn normalize_id(id: &str) -> String {
+    let is_gnu = ["GPL-", "LGPL-", "AGPL-"]
+        .iter()
+        .any(|prefix| id.starts_with(prefix));
+    if !is_gnu || id.ends_with("-only") || id.ends_with("-or-later") {
+        return id.to_string();
+    }
+    match id.strip_suffix('+') {
+        Some(base) => format!("{base}-or-later"),
+        None => format!("{id}-only"),
+    }
+}
+
+fn family(id: &str) -> &str {
+    id.trim_end_matches("-only").trim_end_matches("-or-later")
+}
+
+fn spdx_tag(text: &str) -> Option<(String, u32)> {
+    text.lines().enumerate().find_map(|(index, line)| {
+        let (_, rest) = line.split_once(SPDX_TAG)?;
+        let expression = rest
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end_matches("-->")
+            .trim();
+        if expression.is_empty() {
+            return None;
+        }
+        let ids = license_ids(expression);
+        let normalized = expression
+            .split_whitespace()
+            .map(|token| {
+                if ids.iter().any(|id| id == &normalize_id(token)) {
+                    normalize_id(token)
+                } else {
+                    token.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some((normalized, index as u32 + 1))
+    })
+}
+
+fn fingerprint(text: &str) -> Option<String> {
+    FINGERPRINTS
+        .iter()
+        .find(|(_, phrases)| phrases.iter().all(|phrase| text.contains(phrase)))
+        .map(|(spdx, _)| spdx.to_string())
+}
+
+/// Lowercase text with comment markers stripped and whitespace collapsed.
+fn normalize(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(['/', '*', '#', '!', ';', '-'])
+        })
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn is_license_file(relative: &Path) -> bool {
+    if relative.components().count() != 1 {
+        return false;
+    }
+    let name = relative
+        .file_name()
+        .map(|name| name.to_string_lossy().to_uppercase())
+        .unwrap_or_default();
+    ["LICENSE", "LICENCE", "COPYING", "UNLICENSE"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+fn has_component(relative: &Path, segment: &str) -> bool {
+    relative
+        .components()
+        .any(|component| component.as_os_str() == segment)
+}
+
+fn violation(
+    id: &str,
+    message: String,
+    severity: Severity,
+    path: Option<&str>,
+    line: Option<u32>,
+) -> Violation {
+    Violation {
+        id: id.to_string(),
+        message,
+        severity,
+        path: path.map(str::to_string),
+        line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MockFileSystem;
+    use std::path::PathBuf;
+
+    const MIT: &str = "MIT License\n\nCopyright (c) 2024 Example\n\nPermission is hereby granted, free of charge, to any person obtaining a copy\nof this software...";
+
+    fn inspect(
+        files: Vec<(&'static str, &'static str)>,
+        policy: &LicensePolicy,
+    ) -> (LicenseReport, Vec<Violation>) {
+        let paths: Vec<PathBuf> = files
+            .iter()
+            .map(|(path, _)| Path::new("/repo").join(path))
+            .collect();
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(move |_| Ok(paths.clone()));
+        fs.expect_read_to_string().returning(move |path| {
+            let relative = path.strip_prefix("/repo").expect("relative");
+            Ok(files
+                .iter()
+                .find(|(name, _)| Path::new(name) == relative)
+                .map(|(_, contents)| contents.to_string())
+                .unwrap_or_default())
+        });
+        inspect_license(&fs, Path::new("/repo"), policy).expect("license")
+    }
+
+    fn ids(violations: &[Violation]) -> Vec<(&str, Option<String>)> {
+        violations
+            .iter()
+            .map(|violation| (violation.id.as_str(), violation.location()))
+            .collect()
+    }
+
+    #[test]
+    fn identifies_license_texts_and_headers() {
+        assert_eq!(identify_license_text(MIT).as_deref(), Some("MIT"));
+        assert_eq!(
+            identify_license_text("Apache License\n   Version 2.0, January 2004").as_deref(),
+            Some("Apache-2.0")
+        );
+        assert_eq!(
+            identify_license_text("Redistribution and use in source and binary forms...\n3. Neither the name of the copyright holder").as_deref(),
+            Some("BSD-3-Clause")
+        );
+        assert_eq!(identify_license_text("All rights reserved."), None);
+
+        let gpl = "/*\n * This program is free software: you can redistribute it and/or modify\n * it under the terms of the GNU General Public License as published by\n * the Free Software Foundation, either version 3 of the License, or\n * (at your option) any later version.\n */\nint main() {}\n";
+        assert_eq!(
+            header_license(gpl),
+            Some(("GPL-3.0-or-later".to_string(), 3))
+        );
+        assert_eq!(
+            header_license("// SPDX-License-Identifier: GPL-2.0+ WITH Linux-syscall-note\n"),
+            Some(("GPL-2.0-or-later WITH Linux-syscall-note".to_string(), 1))
+        );
+        assert_eq!(header_license("fn main() {}\n"), None);
+    }
+
+    #[test]
+    fn checks_license_compatibility() {
+        assert!(is_compatible("MIT", "GPL-3.0-only"));
+        assert!(is_compatible("MPL-2.0", "MIT"));
+        assert!(!is_compatible("GPL-3.0-only", "MIT"));
+        assert!(!is_compatible("Apache-2.0", "GPL-2.0-only"));
+        assert!(is_compatible("Apache-2.0", "GPL-3.0-only"));
+        assert!(is_compatible("GPL-2.0-or-later", "GPL-3.0-only"));
+        assert!(!is_compatible("GPL-2.0-only", "GPL-3.0-only"));
+        assert!(is_compatible("GPL-3.0-only", "AGPL-3.0-or-later"));
+    }
+
+    #[test]
+    fn flags_incompatible_files_and_missing_headers() {
+        let policy = LicensePolicy {
+            require_headers: true,
+            allowed: Vec::new(),
+        };
+        let (report, violations) = inspect(
+            vec![
+                ("LICENSE", MIT),
+                (
+                    "src/lib.rs",
+                    "// SPDX-License-Identifier: MIT\npub fn a() {}\n",
+                ),
+                (
+                    "src/gpl.c",
+                    "/* SPDX-License-Identifier: GPL-3.0 */\nint b;\n",
+                ),
+                ("src/plain.py", "def c():\n    pass\n"),
+                (
+                    "vendor/lib.js",
+                    "// SPDX-License-Identifier: MIT OR Apache-2.0\n",
+                ),
+                (
+                    "node_modules/x/index.js",
+                    "// SPDX-License-Identifier: GPL-3.0-only\n",
+                ),
+                ("README.md", "# Readme"),
+            ],
+            &policy,
+        );
+
+        assert_eq!(report.license.as_deref(), Some("MIT"));
+        assert_eq!(report.license_files[0].path, "LICENSE");
+        assert_eq!(report.files_scanned, 4);
+        assert_eq!(report.files_with_headers, 3);
+        assert_eq!(report.header_licenses["GPL-3.0-only"], 1);
+        assert_eq!(report.header_licenses["MIT OR Apache-2.0"], 1);
+        assert_eq!(
+            report.incompatible,
+            vec![FileLicense {
+                path: "src/gpl.c".to_string(),
+                spdx: "GPL-3.0-only".to_string(),
+            }]
+        );
+        assert_eq!(
+            ids(&violations),
+            vec![
+                (LICENSE_INCOMPATIBLE_RULE, Some("src/gpl.c:1".to_string())),
+                (
+                    LICENSE_HEADER_MISSING_RULE,
+                    Some("src/plain.py:1".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_missing_unknown_and_disallowed_licenses() {
+        let (report, violations) = inspect(
+            vec![("src/main.go", "package main\n")],
+            &LicensePolicy::default(),
+        );
+        assert_eq!(report.license, None);
+        assert_eq!(ids(&violations), vec![(LICENSE_MISSING_RULE, None)]);
+
+        let policy = LicensePolicy::from_toml_str("allowed = [\"MIT\"]").expect("policy");
+        let (report, violations) = inspect(
+            vec![
+                ("COPYING", "Some custom terms."),
+                ("LICENSE-APACHE", "Apache License\nVersion 2.0"),
+                ("LICENSE-MIT", MIT),
+                ("src/util.ts", "// SPDX-License-Identifier: BSD-2-Clause\n"),
+            ],
+            &policy,
+        );
+        assert_eq!(report.license.as_deref(), Some("Apache-2.0 OR MIT"));
+        assert_eq!(
+            ids(&violations),
+            vec![
+                (LICENSE_UNKNOWN_RULE, Some("COPYING".to_string())),
+                (LICENSE_NOT_ALLOWED_RULE, Some("LICENSE-APACHE".to_string())),
+                (LICENSE_NOT_ALLOWED_RULE, Some("src/util.ts:1".to_string())),
+            ]
+        );
+    }
+}
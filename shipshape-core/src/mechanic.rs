@@ -9,6 +9,14 @@ use crate::error::Result;
 pub trait Mechanic {
     /// Returns the unique ID of the tool (e.g., "lib2nb2lib").
     fn id(&self) -> &str;
+    /// Version of the tool; cached audit results are discarded when it changes.
+    fn version(&self) -> &str {
+        env!("CARGO_PKG_VERSION")
+    }
+    /// Settings that affect audit results, hashed into cache keys.
+    fn config(&self) -> String {
+        String::new()
+    }
     /// Checks for issues and returns a list of violations.
     fn audit(&self, path: &Path) -> Result<Vec<Violation>>;
     /// Applies a fix in dry-run mode and returns the diff output.
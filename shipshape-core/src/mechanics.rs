@@ -1,6 +1,7 @@
 //! Mechanic registry and external tool wrappers.
 
-use crate::{FileSystem, Mechanic, Result, ShipShapeError, StdFileSystem, Violation};
+use crate::generated::GeneratedFiles;
+use crate::{FileSystem, Mechanic, Result, Severity, ShipShapeError, StdFileSystem, Violation};
 use std::path::Path;
 use std::process::Command;
 use std::sync::Arc;
@@ -176,6 +177,9 @@ fn output_to_violations(id: &str, output: &str) -> Vec<Violation> {
         .map(|line| Violation {
             id: id.to_string(),
             message: line.to_string(),
+            severity: Severity::Warning,
+            path: None,
+            line: None,
         })
         .collect()
 }
@@ -203,9 +207,11 @@ impl NotebookMechanic {
     fn detect_notebook_only_repo(&self, path: &Path) -> Result<bool> {
         let fs = StdFileSystem::new();
         let files = fs.list_files(path)?;
+        let generated = GeneratedFiles::load(&fs, path);
         let mut has_notebooks = false;
         for file in files {
-            if is_notebook(&file) {
+            let relative = file.strip_prefix(path).unwrap_or(&file);
+            if is_notebook(&file) && generated.classify_path(relative).is_none() {
                 has_notebooks = true;
                 break;
             }
@@ -232,6 +238,9 @@ impl Mechanic for NotebookMechanic {
             return Ok(vec![Violation {
                 id: self.id().to_string(),
                 message: "Jupyter notebooks detected without packaging metadata (pyproject.toml, setup.py, setup.cfg).".to_string(),
+                severity: Severity::Warning,
+                path: None,
+                line: None,
             }]);
         }
         Ok(Vec::new())
@@ -258,6 +267,10 @@ impl Mechanic for TypeCorrectMechanic {
         self.inner.id
     }
 
+    fn config(&self) -> String {
+        format!("{:?}", self.inner.audit)
+    }
+
     fn audit(&self, path: &Path) -> Result<Vec<Violation>> {
         self.inner.audit_with(path)
     }
@@ -281,6 +294,10 @@ impl Mechanic for CddCMechanic {
         self.inner.id
     }
 
+    fn config(&self) -> String {
+        format!("{:?}", self.inner.audit)
+    }
+
     fn audit(&self, path: &Path) -> Result<Vec<Violation>> {
         self.inner.audit_with(path)
     }
@@ -304,6 +321,10 @@ impl Mechanic for GoAutoErrHandlingMechanic {
         self.inner.id
     }
 
+    fn config(&self) -> String {
+        format!("{:?}", self.inner.audit)
+    }
+
     fn audit(&self, path: &Path) -> Result<Vec<Violation>> {
         self.inner.audit_with(path)
     }
@@ -443,7 +464,7 @@ mod tests {
 
     #[test]
     fn build_mechanics_supports_aliases() {
-        let mechanics = build_mechanics(&vec![
+        let mechanics = build_mechanics(&[
             "lib2notebook2lib".to_string(),
             "cpp-types".to_string(),
             "go-auto-err-handling".to_string(),
@@ -0,0 +1,366 @@
+//! Package manager and test runner detection for drydock recipes.
+//!
+//! Node stacks follow the `packageManager` field of the nearest
+//! `package.json`, then the nearest lockfile (`pnpm-lock.yaml`, `yarn.lock`,
+//! `package-lock.json`); both are searched up to the repository root, since
+//! workspace members share the workspace's lockfile. Python stacks follow
+//! their own directory: `poetry.lock`, `uv.lock` and `Pipfile(.lock)` first,
+//! then `[tool.poetry]`/`[tool.uv]` in `pyproject.toml`, then a bare
+//! `requirements.txt`, and otherwise a pip install of the package itself.
+//!
+//! Test runners are tox and nox when configured, then pytest when it is
+//! configured or listed as a dependency, and `unittest` otherwise. Node stacks
+//! only get a runner when `package.json` defines a real `test` script.
+
+use std::path::Path;
+
+use crate::domain::{DetectedPackageManager, PackageManager, ProjectKind, TestRunner};
+use crate::fs::FileSystem;
+use crate::projects::{ancestors, in_dir};
+
+/// Node lockfiles in detection priority order.
+const NODE_LOCKFILES: &[(&str, PackageManager)] = &[
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("yarn.lock", PackageManager::Yarn),
+    ("package-lock.json", PackageManager::Npm),
+    ("npm-shrinkwrap.json", PackageManager::Npm),
+];
+
+/// Python lockfiles in detection priority order.
+const PYTHON_LOCKFILES: &[(&str, PackageManager)] = &[
+    ("poetry.lock", PackageManager::Poetry),
+    ("uv.lock", PackageManager::Uv),
+    ("Pipfile.lock", PackageManager::Pipenv),
+];
+
+/// Python packaging metadata that pip can install the project from.
+const PYTHON_PACKAGE_FILES: &[&str] = &["pyproject.toml", "setup.py", "setup.cfg"];
+
+/// Python files whose text lists pytest as a dependency.
+const PYTHON_DEPENDENCY_FILES: &[&str] = &[
+    "pyproject.toml",
+    "setup.py",
+    "setup.cfg",
+    "Pipfile",
+    "requirements.txt",
+    "requirements-dev.txt",
+    "requirements-test.txt",
+    "dev-requirements.txt",
+    "test-requirements.txt",
+];
+
+/// Placeholder `test` script written by `npm init`.
+const NPM_INIT_TEST_SCRIPT: &str = "no test specified";
+
+/// Find the package manager of a `kind` stack built from `dir` (relative to
+/// `root`, `.` for the root). Stacks without package managers yield `None`.
+pub fn detect_package_manager<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    dir: &str,
+    kind: ProjectKind,
+) -> Option<DetectedPackageManager> {
+    let read = |path: &str| fs.read_to_string(&root.join(path)).ok();
+    match kind {
+        ProjectKind::Node => Some(node_package_manager(&read, dir)),
+        ProjectKind::Python => Some(python_package_manager(&read, dir)),
+        ProjectKind::Rust | ProjectKind::Go | ProjectKind::CMake => None,
+    }
+}
+
+/// Find the tool that runs the tests of a `kind` stack built from `dir`.
+pub fn detect_test_runner<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    dir: &str,
+    kind: ProjectKind,
+) -> Option<TestRunner> {
+    let read = |name: &str| fs.read_to_string(&root.join(in_dir(dir, name))).ok();
+    match kind {
+        ProjectKind::Python => Some(python_test_runner(&read)),
+        ProjectKind::Node => {
+            let manifest =
+                serde_json::from_str::<serde_json::Value>(&read("package.json")?).ok()?;
+            let script = manifest.get("scripts")?.get("test")?.as_str()?;
+            (!script.trim().is_empty() && !script.contains(NPM_INIT_TEST_SCRIPT))
+                .then_some(TestRunner::NpmScript)
+        }
+        ProjectKind::Rust | ProjectKind::Go | ProjectKind::CMake => None,
+    }
+}
+
+fn detected(
+    manager: PackageManager,
+    source: impl Into<String>,
+    lockfile: Option<String>,
+) -> DetectedPackageManager {
+    DetectedPackageManager {
+        manager,
+        version: None,
+        source: source.into(),
+        lockfile,
+    }
+}
+
+fn node_package_manager(
+    read: &impl Fn(&str) -> Option<String>,
+    dir: &str,
+) -> DetectedPackageManager {
+    let lockfile = |wanted: Option<PackageManager>| {
+        ancestors(dir).into_iter().find_map(|dir| {
+            NODE_LOCKFILES
+                .iter()
+                .filter(|(_, manager)| wanted.is_none_or(|wanted| wanted == *manager))
+                .map(|(name, manager)| (in_dir(&dir, name), *manager))
+                .find(|(path, _)| read(path).is_some())
+        })
+    };
+    for dir in ancestors(dir) {
+        let path = in_dir(&dir, "package.json");
+        let Some(field) = read(&path)
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+            .and_then(|manifest| Some(manifest.get("packageManager")?.as_str()?.to_string()))
+        else {
+            continue;
+        };
+        let (name, version) = field.split_once('@').unwrap_or((&field, ""));
+        let Some(manager) = [
+            PackageManager::Npm,
+            PackageManager::Yarn,
+            PackageManager::Pnpm,
+        ]
+        .into_iter()
+        .find(|manager| manager.name() == name) else {
+            continue;
+        };
+        let version = version.split('+').next().unwrap_or_default();
+        return DetectedPackageManager {
+            version: (!version.is_empty()).then(|| version.to_string()),
+            ..detected(
+                manager,
+                format!("{path} (packageManager)"),
+                lockfile(Some(manager)).map(|(path, _)| path),
+            )
+        };
+    }
+    match lockfile(None) {
+        Some((path, manager)) => detected(manager, path.clone(), Some(path)),
+        None => detected(PackageManager::Npm, in_dir(dir, "package.json"), None),
+    }
+}
+
+fn python_package_manager(
+    read: &impl Fn(&str) -> Option<String>,
+    dir: &str,
+) -> DetectedPackageManager {
+    let exists = |name: &str| read(&in_dir(dir, name)).is_some();
+    if let Some((name, manager)) = PYTHON_LOCKFILES.iter().find(|(name, _)| exists(name)) {
+        let path = in_dir(dir, name);
+        return detected(*manager, path.clone(), Some(path));
+    }
+    if exists("Pipfile") {
+        return detected(PackageManager::Pipenv, in_dir(dir, "Pipfile"), None);
+    }
+    let pyproject_path = in_dir(dir, "pyproject.toml");
+    let pyproject = read(&pyproject_path).and_then(|text| text.parse::<toml::Table>().ok());
+    let tool = |name: &str| {
+        pyproject
+            .as_ref()
+            .and_then(|manifest| manifest.get("tool")?.get(name))
+            .is_some()
+    };
+    if tool("poetry") {
+        return detected(
+            PackageManager::Poetry,
+            format!("{pyproject_path} (tool.poetry)"),
+            None,
+        );
+    }
+    if tool("uv") {
+        return detected(
+            PackageManager::Uv,
+            format!("{pyproject_path} (tool.uv)"),
+            None,
+        );
+    }
+    let packaged = exists("setup.py")
+        || exists("setup.cfg")
+        || pyproject.as_ref().is_some_and(|manifest| {
+            manifest.contains_key("project") || manifest.contains_key("build-system")
+        });
+    if !packaged && exists("requirements.txt") {
+        let path = in_dir(dir, "requirements.txt");
+        return detected(PackageManager::Pip, path.clone(), Some(path));
+    }
+    let manifest = PYTHON_PACKAGE_FILES
+        .iter()
+        .copied()
+        .find(|name| exists(name))
+        .unwrap_or("pyproject.toml");
+    detected(PackageManager::Pip, in_dir(dir, manifest), None)
+}
+
+fn python_test_runner(read: &impl Fn(&str) -> Option<String>) -> TestRunner {
+    let pyproject = read("pyproject.toml").and_then(|text| text.parse::<toml::Table>().ok());
+    let tool = |name: &str| {
+        pyproject
+            .as_ref()
+            .and_then(|manifest| manifest.get("tool")?.get(name))
+    };
+    let setup_cfg = read("setup.cfg").unwrap_or_default();
+    if read("tox.ini").is_some() || tool("tox").is_some() || setup_cfg.contains("[tox:tox]") {
+        return TestRunner::Tox;
+    }
+    if read("noxfile.py").is_some() {
+        return TestRunner::Nox;
+    }
+    let configured = read("pytest.ini").is_some()
+        || read("conftest.py").is_some()
+        || tool("pytest").is_some()
+        || setup_cfg.contains("[tool:pytest]");
+    let listed = || {
+        PYTHON_DEPENDENCY_FILES
+            .iter()
+            .filter_map(|name| read(name))
+            .any(|text| text.contains("pytest"))
+    };
+    if configured || listed() {
+        TestRunner::Pytest
+    } else {
+        TestRunner::Unittest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_package_manager, detect_test_runner};
+    use crate::domain::{PackageManager, ProjectKind, TestRunner};
+    use crate::fs::MemoryFileSystem;
+    use std::path::Path;
+
+    #[test]
+    fn detects_node_package_managers_and_test_scripts() {
+        let fs = MemoryFileSystem::new()
+            .with_file(
+                "/repo/package.json",
+                "{\"packageManager\": \"pnpm@9.1.0+sha512.abc\", \"workspaces\": [\"web\"]}",
+            )
+            .with_file("/repo/pnpm-lock.yaml", "lockfileVersion: '9.0'\n")
+            .with_file(
+                "/repo/web/package.json",
+                "{\"scripts\": {\"test\": \"vitest run\"}}",
+            )
+            .with_file(
+                "/repo/legacy/package.json",
+                "{\"scripts\": {\"test\": \"echo \\\"Error: no test specified\\\" && exit 1\"}}",
+            )
+            .with_file("/other/package.json", "{}")
+            .with_file("/other/yarn.lock", "# yarn lockfile v1\n")
+            .with_file("/bare/package.json", "{}");
+        let root = Path::new("/repo");
+
+        let web = detect_package_manager(&fs, root, "web", ProjectKind::Node).expect("web");
+        assert_eq!(web.manager, PackageManager::Pnpm);
+        assert_eq!(web.version.as_deref(), Some("9.1.0"));
+        assert_eq!(web.source, "package.json (packageManager)");
+        assert_eq!(web.lockfile.as_deref(), Some("pnpm-lock.yaml"));
+        assert_eq!(
+            detect_test_runner(&fs, root, "web", ProjectKind::Node),
+            Some(TestRunner::NpmScript)
+        );
+        assert_eq!(
+            detect_test_runner(&fs, root, "legacy", ProjectKind::Node),
+            None
+        );
+
+        let yarn =
+            detect_package_manager(&fs, Path::new("/other"), ".", ProjectKind::Node).expect("yarn");
+        assert_eq!(
+            (yarn.manager, yarn.source.as_str(), yarn.lockfile.as_deref()),
+            (PackageManager::Yarn, "yarn.lock", Some("yarn.lock"))
+        );
+        let bare =
+            detect_package_manager(&fs, Path::new("/bare"), ".", ProjectKind::Node).expect("bare");
+        assert_eq!((bare.manager, bare.lockfile), (PackageManager::Npm, None));
+        assert_eq!(
+            detect_package_manager(&fs, root, ".", ProjectKind::Rust),
+            None
+        );
+    }
+
+    #[test]
+    fn detects_python_package_managers_and_test_runners() {
+        let fs = MemoryFileSystem::new()
+            .with_file(
+                "/repo/poetry/pyproject.toml",
+                "[tool.poetry]\nname = \"app\"\n\n[tool.pytest.ini_options]\n",
+            )
+            .with_file("/repo/uv/pyproject.toml", "[project]\nname = \"app\"\n")
+            .with_file("/repo/uv/uv.lock", "version = 1\n")
+            .with_file("/repo/uv/noxfile.py", "import nox\n")
+            .with_file("/repo/pipenv/Pipfile", "[dev-packages]\npytest = \"*\"\n")
+            .with_file("/repo/pipenv/Pipfile.lock", "{}")
+            .with_file("/repo/reqs/requirements.txt", "flask\n")
+            .with_file("/repo/reqs/tox.ini", "[tox]\n")
+            .with_file("/repo/pkg/setup.py", "from setuptools import setup\n")
+            .with_file("/repo/pkg/requirements.txt", "requests\n");
+        let root = Path::new("/repo");
+        let detect = |dir: &str| {
+            let detected =
+                detect_package_manager(&fs, root, dir, ProjectKind::Python).expect("python");
+            (
+                detected.manager,
+                detected.source,
+                detected.lockfile,
+                detect_test_runner(&fs, root, dir, ProjectKind::Python),
+            )
+        };
+        let path = |path: &str| path.to_string();
+
+        assert_eq!(
+            detect("poetry"),
+            (
+                PackageManager::Poetry,
+                path("poetry/pyproject.toml (tool.poetry)"),
+                None,
+                Some(TestRunner::Pytest)
+            )
+        );
+        assert_eq!(
+            detect("uv"),
+            (
+                PackageManager::Uv,
+                path("uv/uv.lock"),
+                Some(path("uv/uv.lock")),
+                Some(TestRunner::Nox)
+            )
+        );
+        assert_eq!(
+            detect("pipenv"),
+            (
+                PackageManager::Pipenv,
+                path("pipenv/Pipfile.lock"),
+                Some(path("pipenv/Pipfile.lock")),
+                Some(TestRunner::Pytest)
+            )
+        );
+        assert_eq!(
+            detect("reqs"),
+            (
+                PackageManager::Pip,
+                path("reqs/requirements.txt"),
+                Some(path("reqs/requirements.txt")),
+                Some(TestRunner::Tox)
+            )
+        );
+        assert_eq!(
+            detect("pkg"),
+            (
+                PackageManager::Pip,
+                path("pkg/setup.py"),
+                None,
+                Some(TestRunner::Unittest)
+            )
+        );
+    }
+}
@@ -1,9 +1,10 @@
 //! PR template interpolation for ShipShape placeholders.
 
 use crate::ShipShapeError;
-use crate::domain::{FleetReport, LanguageDistribution};
+use crate::domain::{CoverageReport, FleetReport, LanguageDistribution};
 use crate::error::Result;
 use crate::fs::FileSystem;
+use crate::report::format_health_deduction;
 use crate::report::format_language_stats;
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
@@ -67,20 +68,15 @@ fn format_stats(report: &FleetReport) -> String {
     let coverage = &report.coverage;
     let _ = writeln!(output, "ShipShape stats:");
     let _ = writeln!(output, "- Health score: {}/100", report.health_score);
+    for deduction in &report.health_breakdown {
+        let _ = writeln!(output, "  - {}", format_health_deduction(deduction));
+    }
     let _ = writeln!(
         output,
-        "- Test coverage: {:.1}% ({}/{})",
-        coverage.test_coverage * 100.0,
-        coverage.test_files,
-        coverage.code_files
-    );
-    let _ = writeln!(
-        output,
-        "- Doc coverage: {:.1}% ({}/{})",
-        coverage.doc_coverage * 100.0,
-        coverage.doc_files,
-        coverage.code_files
+        "- Test coverage: {}",
+        format_test_coverage(coverage)
     );
+    let _ = writeln!(output, "- Doc coverage: {}", format_doc_coverage(coverage));
     let languages = format_languages(&report.language_stats);
     let _ = writeln!(output, "- Languages: {languages}");
     output.trim_end().to_string()
@@ -104,23 +100,55 @@ fn format_ci(report: &FleetReport) -> String {
     let _ = writeln!(output, "Coverage gates:");
     let _ = writeln!(
         output,
-        "- Tests: {} ({:.1}%, {}/{})",
+        "- Tests: {} ({})",
         coverage_status(coverage.low_test_coverage),
-        coverage.test_coverage * 100.0,
-        coverage.test_files,
-        coverage.code_files
+        format_test_coverage(coverage)
     );
     let _ = writeln!(
         output,
-        "- Docs: {} ({:.1}%, {}/{})",
+        "- Docs: {} ({})",
         coverage_status(coverage.low_doc_coverage),
-        coverage.doc_coverage * 100.0,
-        coverage.doc_files,
-        coverage.code_files
+        format_doc_coverage(coverage)
     );
     output.trim_end().to_string()
 }
 
+fn format_test_coverage(coverage: &CoverageReport) -> String {
+    let Some(line_coverage) = coverage.line_coverage else {
+        return format!(
+            "{:.1}% ({}/{})",
+            coverage.test_coverage * 100.0,
+            coverage.test_files,
+            coverage.code_files
+        );
+    };
+    match coverage.branch_coverage {
+        Some(branch_coverage) => format!(
+            "{:.1}% lines, {:.1}% branches (measured)",
+            line_coverage * 100.0,
+            branch_coverage * 100.0
+        ),
+        None => format!("{:.1}% lines (measured)", line_coverage * 100.0),
+    }
+}
+
+fn format_doc_coverage(coverage: &CoverageReport) -> String {
+    match coverage.api_doc_coverage {
+        Some(api_doc_coverage) => format!(
+            "{:.1}% ({}/{} public items)",
+            api_doc_coverage * 100.0,
+            coverage.documented_items,
+            coverage.public_items
+        ),
+        None => format!(
+            "{:.1}% ({}/{})",
+            coverage.doc_coverage * 100.0,
+            coverage.doc_files,
+            coverage.code_files
+        ),
+    }
+}
+
 fn coverage_status(low: bool) -> &'static str {
     if low { "low" } else { "ok" }
 }
@@ -169,7 +197,10 @@ pub fn ensure_placeholders(template: &str) -> Result<()> {
 mod tests {
     use super::*;
     use crate::fs::MockFileSystem;
-    use crate::{CoverageReport, FleetReport, Violation};
+    use crate::{
+        CoverageReport, CoverageSource, FleetReport, HealthCategory, HealthDeduction, Severity,
+        Violation,
+    };
     use std::collections::BTreeMap;
     use std::path::PathBuf;
 
@@ -238,6 +269,9 @@ mod tests {
             violations: vec![Violation {
                 id: "doc-1".to_string(),
                 message: "Missing docs".to_string(),
+                severity: Severity::Warning,
+                path: None,
+                line: None,
             }],
             coverage: CoverageReport {
                 code_files: 10,
@@ -247,13 +281,30 @@ mod tests {
                 doc_coverage: 0.2,
                 low_test_coverage: false,
                 low_doc_coverage: true,
+                source: CoverageSource::Heuristic,
+                line_coverage: None,
+                branch_coverage: None,
+                artifacts: Vec::new(),
+                public_items: 0,
+                documented_items: 0,
+                api_doc_coverage: None,
             },
             health_score: 84,
+            health_breakdown: vec![HealthDeduction {
+                category: HealthCategory::Docs,
+                points: 16,
+                reason: "doc coverage 20.0% (2/10)".to_string(),
+            }],
         };
 
         let context = PrTemplateContext::from_report(&report);
 
         assert!(context.stats.contains("Health score: 84/100"));
+        assert!(
+            context
+                .stats
+                .contains("  - -16 docs: doc coverage 20.0% (2/10)")
+        );
         assert!(context.stats.contains("Test coverage: 50.0% (5/10)"));
         assert!(context.stats.contains("Doc coverage: 20.0% (2/10)"));
         assert!(context.stats.contains("Languages: Rust 70.00%, Go 30.00%"));
@@ -277,8 +328,16 @@ mod tests {
                 doc_coverage: 0.0,
                 low_test_coverage: true,
                 low_doc_coverage: true,
+                source: CoverageSource::Heuristic,
+                line_coverage: None,
+                branch_coverage: None,
+                artifacts: Vec::new(),
+                public_items: 0,
+                documented_items: 0,
+                api_doc_coverage: None,
             },
             health_score: 0,
+            health_breakdown: Vec::new(),
         };
 
         let context = PrTemplateContext::from_report(&report);
@@ -289,6 +348,61 @@ mod tests {
         assert!(context.ci.contains("Docs: low"));
     }
 
+    #[test]
+    fn context_from_report_prefers_measured_coverage() {
+        let report = FleetReport {
+            language_stats: BTreeMap::new(),
+            violations: Vec::new(),
+            coverage: CoverageReport {
+                code_files: 10,
+                test_files: 0,
+                doc_files: 1,
+                test_coverage: 0.0,
+                doc_coverage: 0.1,
+                low_test_coverage: false,
+                low_doc_coverage: false,
+                source: CoverageSource::Measured,
+                line_coverage: Some(0.875),
+                branch_coverage: None,
+                artifacts: vec!["lcov.info".to_string()],
+                public_items: 0,
+                documented_items: 0,
+                api_doc_coverage: None,
+            },
+            health_score: 90,
+            health_breakdown: Vec::new(),
+        };
+
+        let context = PrTemplateContext::from_report(&report);
+        assert!(
+            context
+                .stats
+                .contains("Test coverage: 87.5% lines (measured)")
+        );
+        assert!(context.ci.contains("Tests: ok (87.5% lines (measured))"));
+
+        let mut with_branches = report.clone();
+        with_branches.coverage.branch_coverage = Some(0.5);
+        let context = PrTemplateContext::from_report(&with_branches);
+        assert!(
+            context
+                .stats
+                .contains("87.5% lines, 50.0% branches (measured)")
+        );
+
+        let mut with_api_docs = report.clone();
+        with_api_docs.coverage.public_items = 4;
+        with_api_docs.coverage.documented_items = 3;
+        with_api_docs.coverage.api_doc_coverage = Some(0.75);
+        let context = PrTemplateContext::from_report(&with_api_docs);
+        assert!(
+            context
+                .stats
+                .contains("Doc coverage: 75.0% (3/4 public items)")
+        );
+        assert!(context.ci.contains("Docs: ok (75.0% (3/4 public items))"));
+    }
+
     fn temp_dir_with_template(rel_path: &str) -> PathBuf {
         let root = std::env::temp_dir().join(unique_dir_name());
         let template_path = root.join(rel_path);
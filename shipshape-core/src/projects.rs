@@ -0,0 +1,527 @@
+//! Monorepo project discovery.
+//!
+//! Finds nested project roots by their marker files (`Cargo.toml`,
+//! `package.json`, `pyproject.toml`, `go.mod`, `CMakeLists.txt`, ...) and links
+//! them to the workspaces that declare them: Cargo `[workspace] members`,
+//! npm/yarn `workspaces`, `pnpm-workspace.yaml` and `go.work`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::domain::{Project, ProjectKind, Violation};
+use crate::error::Result;
+use crate::fs::FileSystem;
+
+/// Path of the project at the repository root.
+pub const ROOT_PROJECT: &str = ".";
+
+const MARKERS: &[(&str, ProjectKind)] = &[
+    ("pyproject.toml", ProjectKind::Python),
+    ("setup.py", ProjectKind::Python),
+    ("setup.cfg", ProjectKind::Python),
+    ("Pipfile", ProjectKind::Python),
+    ("requirements.txt", ProjectKind::Python),
+    ("package.json", ProjectKind::Node),
+    ("Cargo.toml", ProjectKind::Rust),
+    ("go.mod", ProjectKind::Go),
+    ("CMakeLists.txt", ProjectKind::CMake),
+];
+/// Markers that only identify a project at the repository root; nested
+/// copies are usually documentation or tooling pins.
+const ROOT_ONLY_MARKERS: &[&str] = &["requirements.txt"];
+const WORKSPACE_FILES: &[&str] = &["pnpm-workspace.yaml", "go.work"];
+/// Directories whose manifests belong to dependencies, build output or fixtures.
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "bower_components",
+    "vendor",
+    "third_party",
+    "target",
+    "dist",
+    "build",
+    "__pycache__",
+    "site-packages",
+    "venv",
+    "testdata",
+    "fixtures",
+];
+
+/// Ecosystems detected from marker files directly inside `dir`.
+pub fn project_kinds(dir: &Path) -> Vec<ProjectKind> {
+    kinds_from_markers(|name| dir.join(name).is_file())
+}
+
+/// Marker file names that identify `kind`.
+pub fn kind_markers(kind: ProjectKind) -> impl Iterator<Item = &'static str> {
+    MARKERS
+        .iter()
+        .filter(move |(_, marker_kind)| *marker_kind == kind)
+        .map(|(marker, _)| *marker)
+}
+
+/// Find every project root under `root`, sorted by path.
+///
+/// A virtual Cargo manifest (`[workspace]` without `[package]`) is a
+/// workspace but not a project of its own.
+pub fn discover_projects<F: FileSystem>(fs: &F, root: &Path) -> Result<Vec<Project>> {
+    let mut markers: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for path in fs.list_files(root)? {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let Some(name) = relative.file_name().map(|name| name.to_string_lossy()) else {
+            continue;
+        };
+        let is_marker = MARKERS.iter().any(|(marker, _)| *marker == name)
+            || WORKSPACE_FILES.contains(&name.as_ref());
+        if !is_marker || in_skipped_dir(relative) {
+            continue;
+        }
+        let dir = dir_key(relative.parent());
+        if dir != ROOT_PROJECT && ROOT_ONLY_MARKERS.contains(&name.as_ref()) {
+            continue;
+        }
+        markers.entry(dir).or_default().insert(name.into_owned());
+    }
+
+    let mut workspaces: Vec<(String, Vec<GlobMatcher>)> = Vec::new();
+    let mut projects = Vec::new();
+    for (dir, names) in &markers {
+        let dir_path = root.join(dir);
+        let read = |name: &str| -> Option<String> {
+            names
+                .contains(name)
+                .then(|| fs.read_to_string(&dir_path.join(name)).ok())
+                .flatten()
+        };
+        let cargo = read("Cargo.toml").and_then(|text| text.parse::<toml::Table>().ok());
+        let package = read("package.json")
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok());
+
+        let members = workspace_members(
+            cargo.as_ref(),
+            package.as_ref(),
+            read("pnpm-workspace.yaml").as_deref(),
+            read("go.work").as_deref(),
+        );
+        if !members.is_empty() {
+            workspaces.push((
+                dir.clone(),
+                members.iter().filter_map(|m| member_matcher(m)).collect(),
+            ));
+        }
+
+        let mut kinds = kinds_from_markers(|name| names.contains(name));
+        let virtual_manifest = cargo
+            .as_ref()
+            .is_some_and(|cargo| cargo.contains_key("workspace") && !cargo.contains_key("package"));
+        if virtual_manifest {
+            kinds.retain(|kind| *kind != ProjectKind::Rust);
+        }
+        if kinds.is_empty() {
+            continue;
+        }
+        let name = manifest_name(cargo.as_ref(), package.as_ref(), || {
+            read("pyproject.toml").and_then(|text| text.parse::<toml::Table>().ok())
+        })
+        .unwrap_or_else(|| dir_name(root, dir));
+        projects.push(Project {
+            path: dir.clone(),
+            name,
+            kinds,
+            workspace: None,
+        });
+    }
+
+    for project in &mut projects {
+        project.workspace = workspaces
+            .iter()
+            .filter(|(dir, _)| *dir != project.path)
+            .filter_map(|(dir, members)| {
+                let relative = relative_to(&project.path, dir)?;
+                members
+                    .iter()
+                    .any(|member| member.is_match(&relative))
+                    .then(|| dir.clone())
+            })
+            .max_by_key(|dir| dir.len());
+    }
+    Ok(projects)
+}
+
+/// Directories of the projects nested inside `project`.
+pub fn nested_project_dirs(root: &Path, project: &Project, projects: &[Project]) -> Vec<PathBuf> {
+    projects
+        .iter()
+        .filter(|other| other.path != project.path)
+        .filter(|other| relative_to(&other.path, &project.path).is_some())
+        .map(|other| root.join(&other.path))
+        .collect()
+}
+
+/// Violations located in `project` rather than in a project nested inside it.
+///
+/// Violations without a path belong to the root project.
+pub fn project_violations(
+    project: &Project,
+    projects: &[Project],
+    violations: &[Violation],
+) -> Vec<Violation> {
+    violations
+        .iter()
+        .filter(|violation| {
+            let owner = match &violation.path {
+                Some(path) => projects
+                    .iter()
+                    .filter(|candidate| relative_to(path, &candidate.path).is_some())
+                    .max_by_key(|candidate| candidate.path.len())
+                    .map(|candidate| candidate.path.as_str()),
+                None => Some(ROOT_PROJECT),
+            };
+            owner == Some(project.path.as_str())
+        })
+        .cloned()
+        .collect()
+}
+
+/// A filesystem view of one project that hides nested projects.
+#[derive(Debug, Clone)]
+pub struct ProjectFileSystem<'a, F: FileSystem> {
+    fs: &'a F,
+    nested: Vec<PathBuf>,
+}
+
+impl<'a, F: FileSystem> ProjectFileSystem<'a, F> {
+    /// Wrap `fs`, hiding files under the `nested` project directories.
+    pub fn new(fs: &'a F, nested: Vec<PathBuf>) -> Self {
+        Self { fs, nested }
+    }
+}
+
+impl<F: FileSystem> FileSystem for ProjectFileSystem<'_, F> {
+    fn list_files(&self, root: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = self.fs.list_files(root)?;
+        files.retain(|path| !self.nested.iter().any(|dir| path.starts_with(dir)));
+        Ok(files)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.fs.read_to_string(path)
+    }
+
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        self.fs.read(path)
+    }
+}
+
+fn kinds_from_markers(has: impl Fn(&str) -> bool) -> Vec<ProjectKind> {
+    let kinds: BTreeSet<ProjectKind> = MARKERS
+        .iter()
+        .filter(|(marker, _)| has(marker))
+        .map(|(_, kind)| *kind)
+        .collect();
+    kinds.into_iter().collect()
+}
+
+/// Member patterns declared by any workspace manifest in one directory.
+fn workspace_members(
+    cargo: Option<&toml::Table>,
+    package: Option<&serde_json::Value>,
+    pnpm: Option<&str>,
+    go_work: Option<&str>,
+) -> Vec<String> {
+    let mut members = Vec::new();
+    if let Some(list) = cargo
+        .and_then(|cargo| cargo.get("workspace"))
+        .and_then(|workspace| workspace.get("members"))
+        .and_then(|members| members.as_array())
+    {
+        members.extend(
+            list.iter()
+                .filter_map(|member| member.as_str())
+                .map(str::to_string),
+        );
+    }
+    if let Some(workspaces) = package.and_then(|package| package.get("workspaces")) {
+        let list = workspaces.as_array().or_else(|| {
+            workspaces
+                .get("packages")
+                .and_then(|packages| packages.as_array())
+        });
+        members.extend(
+            list.into_iter()
+                .flatten()
+                .filter_map(|member| member.as_str())
+                .map(str::to_string),
+        );
+    }
+    if let Some(pnpm) = pnpm {
+        let mut in_packages = false;
+        for line in pnpm.lines() {
+            if !line.starts_with([' ', '-']) {
+                in_packages = line.trim_end() == "packages:";
+            } else if in_packages && let Some(item) = line.trim().strip_prefix('-') {
+                members.push(item.trim().trim_matches(['\'', '"']).to_string());
+            }
+        }
+    }
+    if let Some(go_work) = go_work {
+        let mut in_use = false;
+        for line in go_work.lines().map(str::trim) {
+            if line == "use (" {
+                in_use = true;
+            } else if in_use && line == ")" {
+                in_use = false;
+            } else if let Some(dir) = line
+                .strip_prefix("use ")
+                .filter(|dir| !dir.starts_with('('))
+            {
+                members.push(dir.trim().to_string());
+            } else if in_use && !line.is_empty() && !line.starts_with("//") {
+                members.push(line.to_string());
+            }
+        }
+    }
+    members
+        .into_iter()
+        .filter(|member| !member.starts_with('!'))
+        .map(|member| {
+            member
+                .trim_start_matches("./")
+                .trim_end_matches('/')
+                .to_string()
+        })
+        .collect()
+}
+
+fn member_matcher(pattern: &str) -> Option<GlobMatcher> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+fn manifest_name(
+    cargo: Option<&toml::Table>,
+    package: Option<&serde_json::Value>,
+    pyproject: impl FnOnce() -> Option<toml::Table>,
+) -> Option<String> {
+    let cargo_name = cargo
+        .and_then(|cargo| cargo.get("package"))
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str());
+    let package_name = package
+        .and_then(|package| package.get("name"))
+        .and_then(|name| name.as_str());
+    if let Some(name) = cargo_name.or(package_name) {
+        return Some(name.to_string());
+    }
+    let pyproject = pyproject()?;
+    pyproject
+        .get("project")
+        .or_else(|| pyproject.get("tool").and_then(|tool| tool.get("poetry")))
+        .and_then(|project| project.get("name"))
+        .and_then(|name| name.as_str())
+        .map(str::to_string)
+}
+
+/// `path` relative to the directory `dir`, when it lies inside it.
+pub(crate) fn relative_to(path: &str, dir: &str) -> Option<String> {
+    if dir == ROOT_PROJECT {
+        return Some(path.to_string());
+    }
+    match path.strip_prefix(dir) {
+        Some("") => Some(String::new()),
+        Some(rest) => rest.strip_prefix('/').map(str::to_string),
+        None => None,
+    }
+}
+
+/// `dir` and each of its parents, ending with the repository root.
+pub(crate) fn ancestors(dir: &str) -> Vec<String> {
+    let mut dirs = Vec::new();
+    let mut current = dir.trim_matches('/');
+    while !current.is_empty() && current != ROOT_PROJECT {
+        dirs.push(current.to_string());
+        current = current
+            .rsplit_once('/')
+            .map(|(parent, _)| parent)
+            .unwrap_or("");
+    }
+    dirs.push(ROOT_PROJECT.to_string());
+    dirs
+}
+
+/// `name` inside the project directory `dir`, relative to the repository root.
+pub(crate) fn in_dir(dir: &str, name: &str) -> String {
+    if dir == ROOT_PROJECT {
+        name.to_string()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+fn dir_key(parent: Option<&Path>) -> String {
+    match parent {
+        Some(parent) if !parent.as_os_str().is_empty() => {
+            parent.to_string_lossy().replace('\\', "/")
+        }
+        _ => ROOT_PROJECT.to_string(),
+    }
+}
+
+fn dir_name(root: &Path, dir: &str) -> String {
+    let path = if dir == ROOT_PROJECT {
+        root.to_path_buf()
+    } else {
+        root.join(dir)
+    };
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dir.to_string())
+}
+
+fn in_skipped_dir(relative: &Path) -> bool {
+    relative.parent().is_some_and(|parent| {
+        parent.components().any(|component| {
+            SKIPPED_DIRS.contains(&component.as_os_str().to_string_lossy().as_ref())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Severity;
+    use crate::fs::MockFileSystem;
+
+    fn discover(files: Vec<(&'static str, &'static str)>) -> Vec<Project> {
+        let paths: Vec<PathBuf> = files
+            .iter()
+            .map(|(path, _)| Path::new("/mono").join(path))
+            .collect();
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(move |_| Ok(paths.clone()));
+        fs.expect_read_to_string().returning(move |path| {
+            let relative = path.strip_prefix("/mono").expect("relative");
+            Ok(files
+                .iter()
+                .find(|(name, _)| Path::new(name) == relative)
+                .map(|(_, contents)| contents.to_string())
+                .unwrap_or_default())
+        });
+        discover_projects(&fs, Path::new("/mono")).expect("discover")
+    }
+
+    #[test]
+    fn discovers_projects_and_workspaces() {
+        let projects = discover(vec![
+            ("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n"),
+            (
+                "crates/core/Cargo.toml",
+                "[package]\nname = \"mono-core\"\n",
+            ),
+            ("crates/core/src/lib.rs", ""),
+            ("crates/cli/Cargo.toml", "[package]\nname = \"mono-cli\"\n"),
+            (
+                "web/package.json",
+                "{\"name\": \"web\", \"workspaces\": [\"packages/*\"]}",
+            ),
+            ("web/packages/ui/package.json", "{\"name\": \"@mono/ui\"}"),
+            (
+                "web/node_modules/left-pad/package.json",
+                "{\"name\": \"left-pad\"}",
+            ),
+            ("tools/pyproject.toml", "[project]\nname = \"mono-tools\"\n"),
+            ("tools/setup.cfg", ""),
+            ("services/go.work", "go 1.22\n\nuse (\n\t./api\n)\n"),
+            ("services/api/go.mod", "module example.com/api\n"),
+            ("native/CMakeLists.txt", ""),
+            ("docs/requirements.txt", "sphinx\n"),
+            ("crates/core/tests/fixtures/demo/Cargo.toml", ""),
+        ]);
+
+        let summary: Vec<(&str, &str, Vec<ProjectKind>, Option<&str>)> = projects
+            .iter()
+            .map(|project| {
+                (
+                    project.path.as_str(),
+                    project.name.as_str(),
+                    project.kinds.clone(),
+                    project.workspace.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("crates/cli", "mono-cli", vec![ProjectKind::Rust], Some(".")),
+                (
+                    "crates/core",
+                    "mono-core",
+                    vec![ProjectKind::Rust],
+                    Some(".")
+                ),
+                ("native", "native", vec![ProjectKind::CMake], None),
+                (
+                    "services/api",
+                    "api",
+                    vec![ProjectKind::Go],
+                    Some("services")
+                ),
+                ("tools", "mono-tools", vec![ProjectKind::Python], None),
+                ("web", "web", vec![ProjectKind::Node], None),
+                (
+                    "web/packages/ui",
+                    "@mono/ui",
+                    vec![ProjectKind::Node],
+                    Some("web")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn assigns_violations_to_the_innermost_project() {
+        let project = |path: &str| Project {
+            path: path.to_string(),
+            name: path.to_string(),
+            kinds: vec![ProjectKind::Node],
+            workspace: None,
+        };
+        let projects = vec![project("."), project("web"), project("web/packages/ui")];
+        let violation = |path: Option<&str>| Violation {
+            id: "demo".to_string(),
+            message: "demo".to_string(),
+            severity: Severity::Warning,
+            path: path.map(str::to_string),
+            line: None,
+        };
+        let violations = vec![
+            violation(Some("web/src/app.ts")),
+            violation(Some("web/packages/ui/button.ts")),
+            violation(Some("webapp/index.ts")),
+            violation(None),
+        ];
+
+        let paths = |project: &Project| -> Vec<Option<String>> {
+            project_violations(project, &projects, &violations)
+                .into_iter()
+                .map(|violation| violation.path)
+                .collect()
+        };
+        assert_eq!(
+            paths(&projects[0]),
+            vec![Some("webapp/index.ts".to_string()), None]
+        );
+        assert_eq!(
+            paths(&projects[1]),
+            vec![Some("web/src/app.ts".to_string())]
+        );
+        assert_eq!(
+            nested_project_dirs(Path::new("/mono"), &projects[1], &projects),
+            vec![PathBuf::from("/mono/web/packages/ui")]
+        );
+    }
+}
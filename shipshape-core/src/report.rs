@@ -1,12 +1,18 @@
 //! Report formatting utilities for ShipShape outputs.
 
+use std::collections::BTreeMap;
 use std::fmt::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
 use crate::Violation;
-use crate::domain::LanguageDistribution;
+use crate::domain::{
+    CodeSpan, ComplexityReport, CoverageReport, CoverageSource, DetectedStack, DiffReport,
+    DuplicationReport, ExcludedFile, ExcludedKind, HealthBreakdown, HealthCategory,
+    HealthDeduction, HistoryReport, JobOutcome, LanguageDistribution, LicenseReport, Project,
+    TestMappingReport, Verification,
+};
 
 /// Status of a repository clone or local load operation.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -36,6 +42,35 @@ pub struct RepoReport {
     pub language_stats: Option<LanguageDistribution>,
     /// Violations found during audit.
     pub violations: Vec<Violation>,
+    /// Test and documentation coverage, measured or heuristic.
+    pub coverage: Option<CoverageReport>,
+    /// Git history metrics, when the repository has history.
+    #[serde(default)]
+    pub history: Option<HistoryReport>,
+    /// Per-function complexity summary.
+    #[serde(default)]
+    pub complexity: Option<ComplexityReport>,
+    /// Duplicate code found within the repository and shared with others.
+    #[serde(default)]
+    pub duplication: Option<DuplicationReport>,
+    /// Source files paired with their tests.
+    #[serde(default)]
+    pub test_mapping: Option<TestMappingReport>,
+    /// Repository license and source header findings.
+    #[serde(default)]
+    pub license: Option<LicenseReport>,
+    /// Generated and vendored files left out of the audit.
+    #[serde(default)]
+    pub excluded_files: Vec<ExcludedFile>,
+    /// Per-project results when the repository contains several projects.
+    #[serde(default)]
+    pub projects: Vec<ProjectReport>,
+    /// Diff scope when only changed lines were audited; `violations` then
+    /// holds just the new findings.
+    #[serde(default)]
+    pub diff: Option<DiffReport>,
+    /// Aggregate health score with the deductions that produced it.
+    pub health: Option<HealthBreakdown>,
     /// Errors encountered during auditing.
     pub audit_errors: Vec<String>,
 }
@@ -49,6 +84,16 @@ impl RepoReport {
             clone_status: CloneStatus::Pending,
             language_stats: None,
             violations: Vec::new(),
+            coverage: None,
+            history: None,
+            complexity: None,
+            duplication: None,
+            test_mapping: None,
+            license: None,
+            excluded_files: Vec::new(),
+            projects: Vec::new(),
+            diff: None,
+            health: None,
             audit_errors: Vec::new(),
         }
     }
@@ -61,11 +106,56 @@ impl RepoReport {
             clone_status: CloneStatus::Failed(error.into()),
             language_stats: None,
             violations: Vec::new(),
+            coverage: None,
+            history: None,
+            complexity: None,
+            duplication: None,
+            test_mapping: None,
+            license: None,
+            excluded_files: Vec::new(),
+            projects: Vec::new(),
+            diff: None,
+            health: None,
             audit_errors: Vec::new(),
         }
     }
 }
 
+/// Audit results for one project inside a repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReport {
+    /// The discovered project.
+    pub project: Project,
+    /// Language distribution of the project's own files.
+    pub language_stats: Option<LanguageDistribution>,
+    /// Test and documentation coverage of the project.
+    pub coverage: Option<CoverageReport>,
+    /// Project source files paired with their tests.
+    pub test_mapping: Option<TestMappingReport>,
+    /// Per-function complexity summary.
+    pub complexity: Option<ComplexityReport>,
+    /// Repository violations located in this project.
+    pub violations: Vec<Violation>,
+    /// Project health score with the deductions that produced it.
+    pub health: Option<HealthBreakdown>,
+}
+
+impl ProjectReport {
+    /// Create an empty report for a project.
+    pub fn new(project: Project) -> Self {
+        Self {
+            project,
+            language_stats: None,
+            coverage: None,
+            test_mapping: None,
+            complexity: None,
+            violations: Vec::new(),
+            health: None,
+        }
+    }
+}
+
 /// Refit report for a repository.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -118,8 +208,22 @@ pub struct LaunchReport {
     pub clone_status: CloneStatus,
     /// Generated Dockerfile contents, if available.
     pub dockerfile: Option<String>,
-    /// Generated CI config contents, if available.
-    pub ci_config: Option<String>,
+    /// Generated `.dockerignore` contents for the Dockerfile's build context.
+    #[serde(default)]
+    pub dockerignore: Option<String>,
+    /// Generated CI configuration files keyed by path relative to the
+    /// repository root, such as `.gitlab-ci.yml`.
+    #[serde(default)]
+    pub ci_files: BTreeMap<String, String>,
+    /// Build stacks detected for drydock, with their evidence.
+    #[serde(default)]
+    pub stacks: Vec<DetectedStack>,
+    /// Per-project drydock output when the repository contains several projects.
+    #[serde(default)]
+    pub projects: Vec<ProjectLaunch>,
+    /// Result of running the drydock jobs locally, when requested.
+    #[serde(default)]
+    pub verification: Option<Verification>,
     /// Errors encountered during launch.
     pub errors: Vec<String>,
 }
@@ -132,7 +236,11 @@ impl LaunchReport {
             path,
             clone_status: CloneStatus::Pending,
             dockerfile: None,
-            ci_config: None,
+            dockerignore: None,
+            ci_files: BTreeMap::new(),
+            stacks: Vec::new(),
+            projects: Vec::new(),
+            verification: None,
             errors: Vec::new(),
         }
     }
@@ -144,12 +252,32 @@ impl LaunchReport {
             path,
             clone_status: CloneStatus::Failed(error.into()),
             dockerfile: None,
-            ci_config: None,
+            dockerignore: None,
+            ci_files: BTreeMap::new(),
+            stacks: Vec::new(),
+            projects: Vec::new(),
+            verification: None,
             errors: Vec::new(),
         }
     }
 }
 
+/// Drydock output for one project inside a repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectLaunch {
+    /// The discovered project.
+    pub project: Project,
+    /// Generated Dockerfile contents.
+    pub dockerfile: String,
+    /// Generated `.dockerignore` contents.
+    #[serde(default)]
+    pub dockerignore: String,
+    /// Generated CI configuration files keyed by path relative to the project.
+    #[serde(default)]
+    pub ci_files: BTreeMap<String, String>,
+}
+
 /// Render a list of audit reports as Markdown.
 pub fn render_audit_markdown(reports: &[RepoReport]) -> String {
     let mut output = String::new();
@@ -158,7 +286,38 @@ pub fn render_audit_markdown(reports: &[RepoReport]) -> String {
         let _ = writeln!(output, "## {}\n", report.source);
         append_clone_status(&mut output, &report.clone_status, &report.path);
         append_language_stats(&mut output, report.language_stats.as_ref());
-        append_violations(&mut output, &report.violations);
+        append_health(&mut output, report.health.as_ref());
+        append_coverage(&mut output, report.coverage.as_ref());
+        append_test_mapping(&mut output, report.test_mapping.as_ref());
+        append_history(&mut output, report.history.as_ref());
+        append_complexity(&mut output, report.complexity.as_ref());
+        append_duplication(&mut output, report.duplication.as_ref());
+        append_license(&mut output, report.license.as_ref());
+        append_excluded_files(&mut output, &report.excluded_files);
+        match &report.diff {
+            Some(diff) => {
+                append_diff(&mut output, diff);
+                append_violations(
+                    &mut output,
+                    "New Violations",
+                    &report.violations,
+                    "No new violations.",
+                );
+                append_violations(
+                    &mut output,
+                    "Pre-existing Violations",
+                    &diff.preexisting_violations,
+                    "No pre-existing violations in changed files.",
+                );
+            }
+            None => append_violations(
+                &mut output,
+                "Violations",
+                &report.violations,
+                "No violations found.",
+            ),
+        }
+        append_projects(&mut output, &report.projects);
         append_errors(&mut output, "Audit errors", &report.audit_errors);
         let _ = writeln!(output);
     }
@@ -191,18 +350,58 @@ pub fn render_launch_markdown(reports: &[LaunchReport]) -> String {
     for report in reports {
         let _ = writeln!(output, "## {}\n", report.source);
         append_clone_status(&mut output, &report.clone_status, &report.path);
+        append_stacks(&mut output, &report.stacks);
         append_code_block(
             &mut output,
             "Dockerfile",
             report.dockerfile.as_deref(),
             "Dockerfile unavailable.",
         );
-        append_code_block(
-            &mut output,
-            "CI config",
-            report.ci_config.as_deref(),
-            "CI config unavailable.",
-        );
+        if let Some(dockerignore) = &report.dockerignore {
+            append_code_block(&mut output, ".dockerignore", Some(dockerignore), "");
+        }
+        if report.ci_files.is_empty() {
+            append_code_block(&mut output, "CI config", None, "CI config unavailable.");
+        }
+        for (file, contents) in &report.ci_files {
+            append_code_block(&mut output, file, Some(contents), "");
+        }
+        for launch in &report.projects {
+            let path = &launch.project.path;
+            append_code_block(
+                &mut output,
+                &format!("Dockerfile ({path})"),
+                Some(&launch.dockerfile),
+                "",
+            );
+            append_code_block(
+                &mut output,
+                &format!(".dockerignore ({path})"),
+                Some(&launch.dockerignore),
+                "",
+            );
+            for (file, contents) in &launch.ci_files {
+                append_code_block(&mut output, &format!("{file} ({path})"), Some(contents), "");
+            }
+        }
+        if let Some(verification) = &report.verification {
+            append_list(
+                &mut output,
+                "Local Verification",
+                &verification_lines(verification),
+                "",
+            );
+            for job in &verification.jobs {
+                if job.outcome != JobOutcome::Passed {
+                    append_code_block(
+                        &mut output,
+                        &format!("Verification log ({})", job.job),
+                        Some(&job.log_tail.join("\n")),
+                        "",
+                    );
+                }
+            }
+        }
         append_errors(&mut output, "Launch errors", &report.errors);
         let _ = writeln!(output);
     }
@@ -221,7 +420,7 @@ pub fn format_language_stats(stats: &LanguageDistribution) -> Vec<(String, f64)>
     items
 }
 
-fn append_clone_status(output: &mut String, status: &CloneStatus, path: &PathBuf) {
+fn append_clone_status(output: &mut String, status: &CloneStatus, path: &Path) {
     let _ = writeln!(output, "- Path: `{}`", path.display());
     match status {
         CloneStatus::Cloned => {
@@ -258,18 +457,508 @@ fn append_language_stats(output: &mut String, stats: Option<&LanguageDistributio
     }
 }
 
-fn append_violations(output: &mut String, violations: &[Violation]) {
+fn append_health(output: &mut String, health: Option<&HealthBreakdown>) {
+    let _ = writeln!(output, "### Health");
+    let Some(health) = health else {
+        let _ = writeln!(output, "Health score unavailable.\n");
+        return;
+    };
+    let _ = writeln!(output, "- Health score: {}/100", health.score);
+    if health.deductions.is_empty() {
+        let _ = writeln!(output, "- No deductions.");
+    }
+    for deduction in &health.deductions {
+        let _ = writeln!(output, "- {}", format_health_deduction(deduction));
+    }
+    let _ = writeln!(output);
+}
+
+/// Format a health deduction as a single display line, e.g. `-10 tests: ...`.
+pub fn format_health_deduction(deduction: &HealthDeduction) -> String {
+    let category = match deduction.category {
+        HealthCategory::Tests => "tests",
+        HealthCategory::Docs => "docs",
+        HealthCategory::Violations => "violations",
+        HealthCategory::Signal => "signal",
+    };
+    format!("-{} {category}: {}", deduction.points, deduction.reason)
+}
+
+fn append_coverage(output: &mut String, coverage: Option<&CoverageReport>) {
+    let _ = writeln!(output, "### Coverage");
+    let Some(coverage) = coverage else {
+        let _ = writeln!(output, "Coverage unavailable.\n");
+        return;
+    };
+    for line in coverage_lines(coverage) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Summarise a coverage report as display lines shared by text and Markdown output.
+pub fn coverage_lines(coverage: &CoverageReport) -> Vec<String> {
+    let mut lines = Vec::new();
+    match coverage.source {
+        CoverageSource::Measured => {
+            lines.push(format!(
+                "Source: measured ({})",
+                coverage.artifacts.join(", ")
+            ));
+        }
+        CoverageSource::Heuristic => lines.push("Source: heuristic".to_string()),
+    }
+    if let Some(line_coverage) = coverage.line_coverage {
+        lines.push(format!("Line coverage: {:.1}%", line_coverage * 100.0));
+    }
+    if let Some(branch_coverage) = coverage.branch_coverage {
+        lines.push(format!("Branch coverage: {:.1}%", branch_coverage * 100.0));
+    }
+    lines.push(format!(
+        "Test files: {:.1}% ({}/{})",
+        coverage.test_coverage * 100.0,
+        coverage.test_files,
+        coverage.code_files
+    ));
+    lines.push(format!(
+        "Doc files: {:.1}% ({}/{})",
+        coverage.doc_coverage * 100.0,
+        coverage.doc_files,
+        coverage.code_files
+    ));
+    if let Some(api_doc_coverage) = coverage.api_doc_coverage {
+        lines.push(format!(
+            "API docs: {:.1}% ({}/{} public items)",
+            api_doc_coverage * 100.0,
+            coverage.documented_items,
+            coverage.public_items
+        ));
+    }
+    lines
+}
+
+fn append_test_mapping(output: &mut String, mapping: Option<&TestMappingReport>) {
+    let _ = writeln!(output, "### Test Mapping");
+    let Some(mapping) = mapping else {
+        let _ = writeln!(output, "Test mapping unavailable.\n");
+        return;
+    };
+    for line in test_mapping_lines(mapping) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Number of untested modules listed by [`test_mapping_lines`].
+pub const UNTESTED_MODULES_LISTED: usize = 20;
+
+/// Summarise test mapping as display lines shared by text and Markdown output.
+pub fn test_mapping_lines(mapping: &TestMappingReport) -> Vec<String> {
+    let ratio = if mapping.source_files == 0 {
+        0.0
+    } else {
+        mapping.tested_files as f64 / mapping.source_files as f64
+    };
+    let mut lines = vec![format!(
+        "Modules with tests: {:.1}% ({}/{})",
+        ratio * 100.0,
+        mapping.tested_files,
+        mapping.source_files
+    )];
+    for path in mapping.untested.iter().take(UNTESTED_MODULES_LISTED) {
+        lines.push(format!("Untested: {path}"));
+    }
+    if mapping.untested.len() > UNTESTED_MODULES_LISTED {
+        lines.push(format!(
+            "Untested: {} more",
+            mapping.untested.len() - UNTESTED_MODULES_LISTED
+        ));
+    }
+    lines
+}
+
+fn append_stacks(output: &mut String, stacks: &[DetectedStack]) {
+    let _ = writeln!(output, "### Detected Stacks");
+    if stacks.is_empty() {
+        let _ = writeln!(output, "None detected.\n");
+        return;
+    }
+    for line in stack_lines(stacks) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Summarise a local verification as display lines shared by text and
+/// Markdown output: the overall result, then one line per job.
+pub fn verification_lines(verification: &Verification) -> Vec<String> {
+    let result = if verification.passed {
+        "passed"
+    } else {
+        "failed"
+    };
+    let mut lines = vec![format!("{result} ({})", verification.runtime)];
+    for job in &verification.jobs {
+        let mut line = format!(
+            "{} ({}): {} in {:.1}s",
+            job.job,
+            job.path,
+            job.outcome.name(),
+            job.duration_ms as f64 / 1000.0
+        );
+        if let Some(code) = job.exit_code
+            && job.outcome != JobOutcome::Passed
+        {
+            let _ = write!(line, ", exit code {code}");
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Describe detected stacks, their evidence, toolchain pins, package managers
+/// and test runners as display lines shared by text and Markdown output.
+pub fn stack_lines(stacks: &[DetectedStack]) -> Vec<String> {
+    stacks
+        .iter()
+        .map(|stack| {
+            let mut line = format!(
+                "{} ({}): {}",
+                stack.kind.name(),
+                stack.path,
+                stack.evidence.join(", ")
+            );
+            if let Some(toolchain) = &stack.toolchain {
+                let _ = write!(
+                    line,
+                    "; toolchain {} from {}",
+                    toolchain.version, toolchain.source
+                );
+            }
+            if let Some(detected) = &stack.package_manager {
+                let _ = write!(
+                    line,
+                    "; package manager {} from {}",
+                    detected.manager.name(),
+                    detected.source
+                );
+            }
+            if let Some(runner) = stack.test_runner {
+                let _ = write!(line, "; test runner {}", runner.name());
+            }
+            line
+        })
+        .collect()
+}
+
+fn append_diff(output: &mut String, diff: &DiffReport) {
+    let _ = writeln!(output, "### Diff Scope");
+    for line in diff_lines(diff) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Summarise a diff-scoped audit as display lines shared by text and Markdown output.
+pub fn diff_lines(diff: &DiffReport) -> Vec<String> {
+    let head = diff.head.as_deref().unwrap_or("working tree");
+    vec![
+        format!("Changes: {} to {head}", diff.base),
+        format!(
+            "Changed: {} files, {} lines",
+            diff.changed_files.len(),
+            diff.changed_lines
+        ),
+        format!(
+            "Pre-existing violations in changed files: {}",
+            diff.preexisting_violations.len()
+        ),
+    ]
+}
+
+fn append_license(output: &mut String, license: Option<&LicenseReport>) {
+    let _ = writeln!(output, "### License");
+    let Some(license) = license else {
+        let _ = writeln!(output, "License unavailable.\n");
+        return;
+    };
+    for line in license_lines(license) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Summarise license findings as display lines shared by text and Markdown output.
+pub fn license_lines(license: &LicenseReport) -> Vec<String> {
+    let mut lines = Vec::new();
+    match &license.license {
+        Some(spdx) => {
+            let files: Vec<&str> = license
+                .license_files
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect();
+            lines.push(format!("License: {spdx} ({})", files.join(", ")));
+        }
+        None if license.license_files.is_empty() => lines.push("License: none".to_string()),
+        None => lines.push("License: unrecognised".to_string()),
+    }
+    lines.push(format!(
+        "Files with license headers: {}/{}",
+        license.files_with_headers, license.files_scanned
+    ));
+    for (spdx, count) in &license.header_licenses {
+        lines.push(format!("Header license: {spdx} ({count} files)"));
+    }
+    for file in &license.incompatible {
+        lines.push(format!("Incompatible: {} ({})", file.path, file.spdx));
+    }
+    lines
+}
+
+fn append_excluded_files(output: &mut String, files: &[ExcludedFile]) {
+    let _ = writeln!(output, "### Generated and Vendored Files");
+    if files.is_empty() {
+        let _ = writeln!(output, "None detected.\n");
+        return;
+    }
+    for line in excluded_files_lines(files) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Number of excluded files listed by [`excluded_files_lines`].
+pub const EXCLUDED_FILES_LISTED: usize = 20;
+
+/// Summarise excluded files as display lines shared by text and Markdown output.
+pub fn excluded_files_lines(files: &[ExcludedFile]) -> Vec<String> {
+    let count = |kind| files.iter().filter(|file| file.kind == kind).count();
+    let mut lines = vec![format!(
+        "Excluded: {} generated, {} vendored",
+        count(ExcludedKind::Generated),
+        count(ExcludedKind::Vendored)
+    )];
+    for file in files.iter().take(EXCLUDED_FILES_LISTED) {
+        let kind = match file.kind {
+            ExcludedKind::Generated => "Generated",
+            ExcludedKind::Vendored => "Vendored",
+        };
+        lines.push(format!("{kind}: {} ({})", file.path, file.reason));
+    }
+    if files.len() > EXCLUDED_FILES_LISTED {
+        lines.push(format!(
+            "Excluded: {} more",
+            files.len() - EXCLUDED_FILES_LISTED
+        ));
+    }
+    lines
+}
+
+fn append_projects(output: &mut String, projects: &[ProjectReport]) {
+    if projects.is_empty() {
+        return;
+    }
+    let _ = writeln!(output, "### Projects");
+    for project in projects {
+        let _ = writeln!(
+            output,
+            "#### {} ({})",
+            project.project.name, project.project.path
+        );
+        for line in project_lines(project) {
+            let _ = writeln!(output, "- {line}");
+        }
+        let _ = writeln!(output);
+    }
+}
+
+/// Summarise a project audit as display lines shared by text and Markdown output.
+pub fn project_lines(report: &ProjectReport) -> Vec<String> {
+    let project = &report.project;
+    let kinds: Vec<String> = project
+        .kinds
+        .iter()
+        .map(|kind| format!("{kind:?}"))
+        .collect();
+    let mut lines = vec![format!("Kind: {}", kinds.join(", "))];
+    if let Some(workspace) = &project.workspace {
+        lines.push(format!("Workspace: {workspace}"));
+    }
+    if let Some(health) = &report.health {
+        lines.push(format!("Health score: {}/100", health.score));
+    }
+    if let Some(stats) = &report.language_stats {
+        let languages: Vec<String> = format_language_stats(stats)
+            .into_iter()
+            .map(|(language, percentage)| format!("{language} {percentage:.1}%"))
+            .collect();
+        if !languages.is_empty() {
+            lines.push(format!("Languages: {}", languages.join(", ")));
+        }
+    }
+    if let Some(coverage) = &report.coverage {
+        lines.extend(coverage_lines(coverage));
+    }
+    if let Some(mapping) = &report.test_mapping {
+        lines.extend(test_mapping_lines(mapping).into_iter().take(1));
+    }
+    if let Some(complexity) = &report.complexity {
+        lines.extend(complexity_lines(complexity).into_iter().take(2));
+    }
+    lines.push(format!("Violations: {}", report.violations.len()));
+    lines
+}
+
+fn append_history(output: &mut String, history: Option<&HistoryReport>) {
+    let _ = writeln!(output, "### History");
+    let Some(history) = history else {
+        let _ = writeln!(output, "History unavailable.\n");
+        return;
+    };
+    for line in history_lines(history) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Summarise git history metrics as display lines shared by text and Markdown output.
+pub fn history_lines(history: &HistoryReport) -> Vec<String> {
+    let mut lines = vec![
+        format!("Last commit: {} days ago", history.last_commit_age_days),
+        format!(
+            "Commits (last {} days): {} ({:.1}/week)",
+            history.window_days, history.commits_in_window, history.commits_per_week
+        ),
+        format!(
+            "Active contributors (last {} days): {} of {}",
+            history.window_days, history.active_contributors, history.total_contributors
+        ),
+    ];
+    if !history.bus_factor.is_empty() {
+        let owners: Vec<String> = history
+            .bus_factor
+            .iter()
+            .map(|owner| format!("{} {}", owner.path, owner.bus_factor))
+            .collect();
+        lines.push(format!("Bus factor: {}", owners.join(", ")));
+    }
+    for file in &history.churn {
+        lines.push(format!(
+            "Churn: {} ({} commits, +{}/-{})",
+            file.path, file.commits, file.lines_added, file.lines_deleted
+        ));
+    }
+    lines
+}
+
+fn append_complexity(output: &mut String, complexity: Option<&ComplexityReport>) {
+    let _ = writeln!(output, "### Complexity");
+    let Some(complexity) = complexity else {
+        let _ = writeln!(output, "Complexity unavailable.\n");
+        return;
+    };
+    for line in complexity_lines(complexity) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Summarise complexity metrics as display lines shared by text and Markdown output.
+pub fn complexity_lines(complexity: &ComplexityReport) -> Vec<String> {
+    let mut lines = vec![
+        format!(
+            "Functions: {} across {} files",
+            complexity.functions_analyzed, complexity.files_analyzed
+        ),
+        format!(
+            "Cyclomatic complexity: {:.1} average, {} max",
+            complexity.average_cyclomatic, complexity.max_cyclomatic
+        ),
+    ];
+    for function in &complexity.hotspots {
+        lines.push(format!(
+            "Hotspot: {} ({}:{}) cyclomatic {}, nesting {}, {} lines",
+            function.name,
+            function.path,
+            function.line,
+            function.cyclomatic,
+            function.max_nesting,
+            function.length
+        ));
+    }
+    lines
+}
+
+fn append_duplication(output: &mut String, duplication: Option<&DuplicationReport>) {
+    let _ = writeln!(output, "### Duplication");
+    let Some(duplication) = duplication else {
+        let _ = writeln!(output, "Duplication unavailable.\n");
+        return;
+    };
+    for line in duplication_lines(duplication) {
+        let _ = writeln!(output, "- {line}");
+    }
+    let _ = writeln!(output);
+}
+
+/// Summarise duplicate code as display lines shared by text and Markdown output.
+pub fn duplication_lines(duplication: &DuplicationReport) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Duplicated lines: {:.1}% ({} lines in {} blocks across {} files)",
+        duplication.duplication_ratio * 100.0,
+        duplication.duplicated_lines,
+        duplication.duplicate_blocks,
+        duplication.files_analyzed
+    )];
+    for block in &duplication.blocks {
+        lines.push(format!(
+            "Duplicate: {} and {} ({} tokens, {:.0}% identical)",
+            format_span(&block.first),
+            format_span(&block.second),
+            block.tokens,
+            block.similarity * 100.0
+        ));
+    }
+    for block in &duplication.cross_repo {
+        lines.push(format!(
+            "Shared: {} and {} ({} tokens, {:.0}% identical)",
+            format_span(&block.first),
+            format_span(&block.second),
+            block.tokens,
+            block.similarity * 100.0
+        ));
+    }
+    lines
+}
+
+fn format_span(span: &CodeSpan) -> String {
+    match &span.repo {
+        Some(repo) => format!("{repo}:{}:{}-{}", span.path, span.start_line, span.end_line),
+        None => format!("{}:{}-{}", span.path, span.start_line, span.end_line),
+    }
+}
+
+fn append_violations(output: &mut String, title: &str, violations: &[Violation], empty: &str) {
     if violations.is_empty() {
-        let _ = writeln!(output, "### Violations\nNo violations found.\n");
+        let _ = writeln!(output, "### {title}\n{empty}\n");
         return;
     }
-    let _ = writeln!(output, "### Violations");
+    let _ = writeln!(output, "### {title}");
     for violation in violations {
-        let _ = writeln!(output, "- [{}] {}", violation.id, violation.message);
+        let _ = writeln!(output, "- {}", format_violation(violation));
     }
     let _ = writeln!(output);
 }
 
+/// Format a violation as `[id] message`, followed by its location when known.
+pub fn format_violation(violation: &Violation) -> String {
+    match violation.location() {
+        Some(location) => format!("[{}] {} ({location})", violation.id, violation.message),
+        None => format!("[{}] {}", violation.id, violation.message),
+    }
+}
+
 fn append_errors(output: &mut String, title: &str, errors: &[String]) {
     append_list(output, title, errors, "No errors reported.");
 }
@@ -301,7 +990,12 @@ fn append_code_block(output: &mut String, title: &str, contents: Option<&str>, e
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Violation;
+    use crate::domain::{
+        DetectedPackageManager, DirectoryOwnership, DuplicateBlock, FileChurn, FileLicense,
+        FunctionComplexity, JobVerification, LicenseFile, PackageManager, ProjectKind, TestRunner,
+        ToolchainRequirement,
+    };
+    use crate::{Severity, Violation};
     use std::collections::BTreeMap;
 
     fn sample_audit_report() -> RepoReport {
@@ -316,7 +1010,148 @@ mod tests {
         report.violations = vec![Violation {
             id: "docs".to_string(),
             message: "Missing docs".to_string(),
+            severity: Severity::Warning,
+            path: None,
+            line: None,
+        }];
+        report.coverage = Some(CoverageReport {
+            code_files: 4,
+            test_files: 1,
+            doc_files: 1,
+            test_coverage: 0.25,
+            doc_coverage: 0.25,
+            low_test_coverage: false,
+            low_doc_coverage: false,
+            source: CoverageSource::Measured,
+            line_coverage: Some(0.8),
+            branch_coverage: Some(0.5),
+            artifacts: vec!["lcov.info".to_string()],
+            public_items: 0,
+            documented_items: 0,
+            api_doc_coverage: None,
+        });
+        report.health = Some(HealthBreakdown {
+            score: 88,
+            deductions: vec![HealthDeduction {
+                category: HealthCategory::Violations,
+                points: 12,
+                reason: "6 docs violations".to_string(),
+            }],
+        });
+        report.history = Some(HistoryReport {
+            commits_analyzed: 12,
+            last_commit_at: 1_700_000_000,
+            last_commit_age_days: 3,
+            window_days: 90,
+            commits_in_window: 9,
+            commits_per_week: 0.7,
+            active_contributors: 1,
+            total_contributors: 2,
+            bus_factor: vec![DirectoryOwnership {
+                path: "src".to_string(),
+                bus_factor: 1,
+                authors: 2,
+                commits: 10,
+            }],
+            churn: vec![FileChurn {
+                path: "src/lib.rs".to_string(),
+                commits: 8,
+                lines_added: 120,
+                lines_deleted: 40,
+            }],
+        });
+        report.complexity = Some(ComplexityReport {
+            files_analyzed: 3,
+            functions_analyzed: 12,
+            average_cyclomatic: 2.5,
+            max_cyclomatic: 9,
+            hotspots: vec![FunctionComplexity {
+                path: "src/lib.rs".to_string(),
+                name: "parse".to_string(),
+                line: 42,
+                cyclomatic: 9,
+                max_nesting: 3,
+                length: 60,
+            }],
+        });
+        report.test_mapping = Some(TestMappingReport {
+            source_files: 4,
+            tested_files: 3,
+            mappings: Vec::new(),
+            untested: vec!["src/cli.rs".to_string()],
+        });
+        report.duplication = Some(DuplicationReport {
+            files_analyzed: 3,
+            duplicate_blocks: 1,
+            duplicated_lines: 16,
+            duplication_ratio: 0.08,
+            blocks: vec![DuplicateBlock {
+                first: CodeSpan {
+                    repo: None,
+                    path: "src/a.rs".to_string(),
+                    start_line: 2,
+                    end_line: 9,
+                },
+                second: CodeSpan {
+                    repo: None,
+                    path: "src/b.rs".to_string(),
+                    start_line: 6,
+                    end_line: 13,
+                },
+                tokens: 64,
+                similarity: 0.75,
+            }],
+            cross_repo: vec![DuplicateBlock {
+                first: CodeSpan {
+                    repo: Some("repo".to_string()),
+                    path: "src/a.rs".to_string(),
+                    start_line: 2,
+                    end_line: 9,
+                },
+                second: CodeSpan {
+                    repo: Some("other".to_string()),
+                    path: "lib/a.rs".to_string(),
+                    start_line: 1,
+                    end_line: 8,
+                },
+                tokens: 64,
+                similarity: 1.0,
+            }],
+        });
+        report.license = Some(LicenseReport {
+            license: Some("MIT".to_string()),
+            license_files: vec![LicenseFile {
+                path: "LICENSE".to_string(),
+                spdx: Some("MIT".to_string()),
+            }],
+            files_scanned: 4,
+            files_with_headers: 2,
+            header_licenses: BTreeMap::from([
+                ("GPL-3.0-only".to_string(), 1),
+                ("MIT".to_string(), 1),
+            ]),
+            incompatible: vec![FileLicense {
+                path: "src/gpl.rs".to_string(),
+                spdx: "GPL-3.0-only".to_string(),
+            }],
+        });
+        report.excluded_files = vec![ExcludedFile {
+            path: "Cargo.lock".to_string(),
+            kind: ExcludedKind::Generated,
+            reason: "lockfile".to_string(),
         }];
+        let mut project = ProjectReport::new(Project {
+            path: "web".to_string(),
+            name: "frontend".to_string(),
+            kinds: vec![ProjectKind::Node],
+            workspace: Some(".".to_string()),
+        });
+        project.language_stats = Some(BTreeMap::from([("TypeScript".to_string(), 100.0)]));
+        project.health = Some(HealthBreakdown {
+            score: 90,
+            deductions: Vec::new(),
+        });
+        report.projects = vec![project];
         report.audit_errors = vec!["lint failed".to_string()];
         report
     }
@@ -330,6 +1165,84 @@ mod tests {
         assert!(output.contains("Rust: 55.50%"));
         assert!(output.contains("[docs] Missing docs"));
         assert!(output.contains("lint failed"));
+        assert!(output.contains("### Health\n- Health score: 88/100"));
+        assert!(output.contains("- -12 violations: 6 docs violations"));
+        assert!(output.contains("Source: measured (lcov.info)"));
+        assert!(output.contains("Line coverage: 80.0%"));
+        assert!(output.contains("Branch coverage: 50.0%"));
+        assert!(output.contains("Test files: 25.0% (1/4)"));
+        assert!(output.contains("### Test Mapping\n- Modules with tests: 75.0% (3/4)"));
+        assert!(output.contains("- Untested: src/cli.rs"));
+        assert!(
+            output.contains(
+                "### License\n- License: MIT (LICENSE)\n- Files with license headers: 2/4"
+            )
+        );
+        assert!(output.contains("- Incompatible: src/gpl.rs (GPL-3.0-only)"));
+        assert!(output.contains(
+            "### Projects\n#### frontend (web)\n- Kind: Node\n- Workspace: .\n- Health score: 90/100\n- Languages: TypeScript 100.0%\n- Violations: 0\n"
+        ));
+        assert!(output.contains(
+            "### Generated and Vendored Files\n- Excluded: 1 generated, 0 vendored\n- Generated: Cargo.lock (lockfile)"
+        ));
+        assert!(output.contains("### History\n- Last commit: 3 days ago"));
+        assert!(output.contains("Active contributors (last 90 days): 1 of 2"));
+        assert!(output.contains("Bus factor: src 1"));
+        assert!(output.contains("Churn: src/lib.rs (8 commits, +120/-40)"));
+        assert!(output.contains("### Complexity\n- Functions: 12 across 3 files"));
+        assert!(output.contains("Cyclomatic complexity: 2.5 average, 9 max"));
+        assert!(
+            output.contains("Hotspot: parse (src/lib.rs:42) cyclomatic 9, nesting 3, 60 lines")
+        );
+        assert!(output.contains(
+            "### Duplication\n- Duplicated lines: 8.0% (16 lines in 1 blocks across 3 files)"
+        ));
+        assert!(
+            output.contains("Duplicate: src/a.rs:2-9 and src/b.rs:6-13 (64 tokens, 75% identical)")
+        );
+        assert!(output.contains(
+            "Shared: repo:src/a.rs:2-9 and other:lib/a.rs:1-8 (64 tokens, 100% identical)"
+        ));
+    }
+
+    #[test]
+    fn renders_audit_markdown_without_coverage() {
+        let report = RepoReport::new("repo".to_string(), PathBuf::from("/tmp/repo"));
+        let output = render_audit_markdown(&[report]);
+        assert!(output.contains("Health score unavailable."));
+        assert!(output.contains("History unavailable."));
+        assert!(output.contains("Complexity unavailable."));
+        assert!(output.contains("Duplication unavailable."));
+        assert!(output.contains("Test mapping unavailable."));
+        assert!(output.contains("Coverage unavailable."));
+    }
+
+    #[test]
+    fn renders_diff_scoped_violations_separately() {
+        let mut report = sample_audit_report();
+        report.diff = Some(DiffReport {
+            base: "origin/main".to_string(),
+            head: None,
+            changed_files: vec!["src/lib.rs".to_string()],
+            changed_lines: 4,
+            preexisting_violations: vec![Violation {
+                id: "old".to_string(),
+                message: "predates the change".to_string(),
+                severity: Severity::Info,
+                path: Some("src/lib.rs".to_string()),
+                line: Some(40),
+            }],
+        });
+        let output = render_audit_markdown(&[report]);
+        assert!(output.contains("### Diff Scope\n- Changes: origin/main to working tree\n"));
+        assert!(output.contains("- Changed: 1 files, 4 lines"));
+        assert!(output.contains("### New Violations\n- ["));
+        assert!(
+            output.contains(
+                "### Pre-existing Violations\n- [old] predates the change (src/lib.rs:40)"
+            )
+        );
+        assert!(!output.contains("### Violations"));
     }
 
     #[test]
@@ -348,10 +1261,78 @@ mod tests {
         let mut report = LaunchReport::new("repo".to_string(), PathBuf::from("/tmp/repo"));
         report.clone_status = CloneStatus::Failed("boom".to_string());
         report.dockerfile = Some("FROM rust".to_string());
+        report.dockerignore = Some(".git".to_string());
+        report
+            .ci_files
+            .insert(".gitlab-ci.yml".to_string(), "stages:".to_string());
+        report.stacks = vec![DetectedStack {
+            kind: ProjectKind::Rust,
+            path: ".".to_string(),
+            evidence: vec![
+                "Cargo.toml".to_string(),
+                "crates/cli/Cargo.toml".to_string(),
+            ],
+            toolchain: Some(ToolchainRequirement {
+                version: "1.80.0".to_string(),
+                source: "rust-toolchain.toml".to_string(),
+            }),
+            package_manager: None,
+            test_runner: None,
+        }];
+        report.stacks.push(DetectedStack {
+            kind: ProjectKind::Node,
+            path: "web".to_string(),
+            evidence: vec!["web/package.json".to_string()],
+            toolchain: None,
+            package_manager: Some(DetectedPackageManager {
+                manager: PackageManager::Pnpm,
+                version: None,
+                source: "pnpm-lock.yaml".to_string(),
+                lockfile: Some("pnpm-lock.yaml".to_string()),
+            }),
+            test_runner: Some(TestRunner::NpmScript),
+        });
+        report.verification = Some(Verification {
+            runtime: "podman".to_string(),
+            passed: false,
+            jobs: vec![
+                JobVerification {
+                    job: "rust".to_string(),
+                    path: ".".to_string(),
+                    outcome: JobOutcome::Passed,
+                    exit_code: Some(0),
+                    duration_ms: 1500,
+                    log_tail: vec!["ok".to_string()],
+                },
+                JobVerification {
+                    job: "node-web".to_string(),
+                    path: "web".to_string(),
+                    outcome: JobOutcome::Failed,
+                    exit_code: Some(1),
+                    duration_ms: 300,
+                    log_tail: vec!["+ pnpm test".to_string(), "1 failing".to_string()],
+                },
+            ],
+        });
         let output = render_launch_markdown(&[report]);
         assert!(output.contains("ShipShape Launch Report"));
         assert!(output.contains("Status: failed (boom)"));
         assert!(output.contains("FROM rust"));
+        assert!(output.contains("### .dockerignore\n```text\n.git\n```"));
+        assert!(output.contains("### .gitlab-ci.yml\n```text\nstages:\n```"));
+        assert!(
+            output.contains(
+                "### Detected Stacks\n- rust (.): Cargo.toml, crates/cli/Cargo.toml; toolchain 1.80.0 from rust-toolchain.toml\n- node (web): web/package.json; package manager pnpm from pnpm-lock.yaml; test runner package.json test script\n"
+            )
+        );
+        assert!(output.contains(
+            "### Local Verification\n- failed (podman)\n- rust (.): passed in 1.5s\n- node-web (web): failed in 0.3s, exit code 1\n"
+        ));
+        assert!(
+            output
+                .contains("### Verification log (node-web)\n```text\n+ pnpm test\n1 failing\n```")
+        );
+        assert!(!output.contains("Verification log (rust)"));
     }
 
     #[test]
@@ -0,0 +1,366 @@
+//! Source-to-test mapping.
+//!
+//! Pairs source files with their likely tests using language conventions:
+//! Rust `#[cfg(test)]` modules and crate `tests/` directories, Python
+//! `test_<name>.py` modules, Go `_test.go` siblings and JS/TS `.spec`/`.test`
+//! files. Source files without a match are reported as untested modules.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::Result;
+use crate::complexity::SourceLanguage;
+use crate::coverage::is_dependency_dir;
+use crate::domain::{TestMapping, TestMappingReport};
+use crate::fs::FileSystem;
+use crate::health::HealthSignals;
+use crate::inspector::is_test_file;
+
+/// Signal name for the share of source files without tests.
+pub const SIGNAL_UNTESTED_RATIO: &str = "untested_ratio";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Family {
+    Rust,
+    Python,
+    Go,
+    Script,
+}
+
+impl Family {
+    fn from_path(path: &Path) -> Option<Self> {
+        match SourceLanguage::from_path(path)? {
+            SourceLanguage::Rust => Some(Self::Rust),
+            SourceLanguage::Python => Some(Self::Python),
+            SourceLanguage::Go => Some(Self::Go),
+            SourceLanguage::JavaScript | SourceLanguage::TypeScript => Some(Self::Script),
+            SourceLanguage::C | SourceLanguage::Cpp => None,
+        }
+    }
+}
+
+/// Tests are looked up by language, scope and module name. The scope is the
+/// crate root for Rust, the directory for Go, and empty otherwise.
+type TestKey = (Family, String, String);
+
+/// Pair every source file under `root` with its likely tests.
+///
+/// Dependency directories, build scripts, examples, benches and config
+/// modules are skipped. Rust files are read to detect inline test modules.
+pub fn inspect_test_mapping<F: FileSystem>(fs: &F, root: &Path) -> Result<TestMappingReport> {
+    let mut files = fs.list_files(root)?;
+    files.sort();
+    let mut sources = Vec::new();
+    let mut tests: BTreeMap<TestKey, Vec<String>> = BTreeMap::new();
+    // Rust integration tests per crate root, which exercise `lib.rs`/`main.rs`.
+    let mut crate_tests: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for path in files {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if is_dependency_dir(relative) {
+            continue;
+        }
+        let Some(family) = Family::from_path(relative) else {
+            continue;
+        };
+        let display = relative.to_string_lossy().replace('\\', "/");
+        if let Some(key) = test_key(family, relative) {
+            if family == Family::Rust && has_component(relative, "tests") {
+                crate_tests
+                    .entry(key.1.clone())
+                    .or_default()
+                    .push(display.clone());
+            }
+            tests.entry(key).or_default().push(display);
+        } else if !is_support_file(family, relative) {
+            sources.push((path.clone(), display, family));
+        }
+    }
+
+    let mut mappings = Vec::new();
+    let mut untested = Vec::new();
+    for (path, display, family) in sources {
+        let relative = Path::new(&display);
+        let mut found = Vec::new();
+        if family == Family::Rust
+            && fs
+                .read_to_string(&path)
+                .is_ok_and(|source| source.contains("#[cfg(test)]"))
+        {
+            found.push(display.clone());
+        }
+        let scope = scope(family, relative);
+        let module = module_name(family, relative);
+        if family == Family::Rust && matches!(module.as_str(), "lib" | "main") {
+            found.extend(crate_tests.get(&scope).cloned().unwrap_or_default());
+        }
+        found.extend(
+            tests
+                .get(&(family, scope, module))
+                .cloned()
+                .unwrap_or_default(),
+        );
+        found.dedup();
+
+        if found.is_empty() {
+            untested.push(display);
+        } else {
+            mappings.push(TestMapping {
+                source: display,
+                tests: found,
+            });
+        }
+    }
+
+    Ok(TestMappingReport {
+        source_files: mappings.len() + untested.len(),
+        tested_files: mappings.len(),
+        mappings,
+        untested,
+    })
+}
+
+/// Expose the untested share of source files as a health model signal.
+pub fn test_mapping_signals(report: &TestMappingReport) -> HealthSignals {
+    let mut signals = HealthSignals::new();
+    if report.source_files > 0 {
+        signals.insert(
+            SIGNAL_UNTESTED_RATIO.to_string(),
+            report.untested.len() as f64 / report.source_files as f64,
+        );
+    }
+    signals
+}
+
+/// Lookup key of the module a test file exercises, or `None` for non-tests.
+fn test_key(family: Family, relative: &Path) -> Option<TestKey> {
+    let stem = file_stem(relative);
+    let module = match family {
+        // `mod tests;` files test their parent module.
+        Family::Rust if matches!(stem.as_str(), "tests" | "test") => parent_name(relative),
+        _ if !is_test_file(relative) && !has_component(relative, "__tests__") => return None,
+        Family::Rust | Family::Python => strip_test_affixes(&stem).to_string(),
+        Family::Go => stem.strip_suffix("_test")?.to_string(),
+        Family::Script => {
+            let name = file_name(relative);
+            [".spec.", ".test."]
+                .iter()
+                .find_map(|marker| name.find(marker).map(|index| name[..index].to_string()))
+                .unwrap_or_else(|| script_module(&name))
+        }
+    };
+    Some((family, scope(family, relative), module))
+}
+
+fn module_name(family: Family, relative: &Path) -> String {
+    match family {
+        Family::Rust if file_stem(relative) == "mod" => parent_name(relative),
+        Family::Script => script_module(&file_name(relative)),
+        _ => file_stem(relative),
+    }
+}
+
+fn scope(family: Family, relative: &Path) -> String {
+    match family {
+        Family::Rust => crate_root(relative),
+        Family::Go => parent_path(relative),
+        Family::Python | Family::Script => String::new(),
+    }
+}
+
+/// Directory above the first `src` or `tests` component.
+fn crate_root(relative: &Path) -> String {
+    let mut root = Vec::new();
+    for component in relative.components() {
+        let segment = component.as_os_str().to_string_lossy();
+        if segment == "src" || segment == "tests" {
+            return root.join("/");
+        }
+        root.push(segment.into_owned());
+    }
+    parent_path(relative)
+}
+
+fn is_support_file(family: Family, relative: &Path) -> bool {
+    let name = file_name(relative);
+    match family {
+        Family::Rust => {
+            name == "build.rs"
+                || has_component(relative, "examples")
+                || has_component(relative, "benches")
+        }
+        Family::Python => matches!(
+            name.as_str(),
+            "__init__.py" | "__main__.py" | "conftest.py" | "setup.py"
+        ),
+        Family::Go => name == "doc.go",
+        Family::Script => {
+            name.ends_with(".d.ts") || name.contains(".config.") || name.contains(".conf.")
+        }
+    }
+}
+
+fn strip_test_affixes(stem: &str) -> &str {
+    let stem = stem.strip_prefix("test_").unwrap_or(stem);
+    stem.strip_suffix("_tests")
+        .or_else(|| stem.strip_suffix("_test"))
+        .unwrap_or(stem)
+}
+
+/// File name without its last extension, e.g. `app.component` for `app.component.ts`.
+fn script_module(name: &str) -> String {
+    name.rsplit_once('.')
+        .map_or(name, |(module, _)| module)
+        .to_string()
+}
+
+fn has_component(relative: &Path, segment: &str) -> bool {
+    relative
+        .components()
+        .any(|component| component.as_os_str() == segment)
+}
+
+fn file_name(relative: &Path) -> String {
+    relative
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn file_stem(relative: &Path) -> String {
+    relative
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn parent_name(relative: &Path) -> String {
+    relative
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn parent_path(relative: &Path) -> String {
+    relative
+        .parent()
+        .map(|parent| parent.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::MockFileSystem;
+    use std::path::PathBuf;
+
+    fn inspect(paths: &[&str]) -> TestMappingReport {
+        let files: Vec<PathBuf> = paths
+            .iter()
+            .map(|path| Path::new("/repo").join(path))
+            .collect();
+        let mut fs = MockFileSystem::new();
+        fs.expect_list_files().returning(move |_| Ok(files.clone()));
+        fs.expect_read_to_string().returning(|path| {
+            Ok(if path.ends_with("parser.rs") {
+                "fn parse() {}\n\n#[cfg(test)]\nmod tests {}\n".to_string()
+            } else {
+                "fn f() {}\n".to_string()
+            })
+        });
+        inspect_test_mapping(&fs, Path::new("/repo")).expect("mapping")
+    }
+
+    fn tests_for<'a>(report: &'a TestMappingReport, source: &str) -> Vec<&'a str> {
+        report
+            .mappings
+            .iter()
+            .find(|mapping| mapping.source == source)
+            .map(|mapping| mapping.tests.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn maps_rust_inline_and_integration_tests() {
+        let report = inspect(&[
+            "core/src/lib.rs",
+            "core/src/parser.rs",
+            "core/src/render/mod.rs",
+            "core/src/render/tests.rs",
+            "core/src/store.rs",
+            "core/tests/store_tests.rs",
+            "core/build.rs",
+            "cli/src/main.rs",
+        ]);
+
+        assert_eq!(
+            tests_for(&report, "core/src/lib.rs"),
+            vec!["core/tests/store_tests.rs"]
+        );
+        assert_eq!(
+            tests_for(&report, "core/src/parser.rs"),
+            vec!["core/src/parser.rs"]
+        );
+        assert_eq!(
+            tests_for(&report, "core/src/render/mod.rs"),
+            vec!["core/src/render/tests.rs"]
+        );
+        assert_eq!(
+            tests_for(&report, "core/src/store.rs"),
+            vec!["core/tests/store_tests.rs"]
+        );
+        assert_eq!(report.untested, vec!["cli/src/main.rs"]);
+        assert_eq!((report.tested_files, report.source_files), (4, 5));
+    }
+
+    #[test]
+    fn maps_python_go_and_script_conventions() {
+        let report = inspect(&[
+            "app/__init__.py",
+            "app/models.py",
+            "app/views.py",
+            "tests/test_models.py",
+            "pkg/store/store.go",
+            "pkg/store/store_test.go",
+            "pkg/store/cache.go",
+            "pkg/other/store.go",
+            "web/src/app.component.ts",
+            "web/src/app.component.spec.ts",
+            "web/src/utils.js",
+            "web/src/__tests__/utils.js",
+            "web/src/router.ts",
+            "web/src/env.d.ts",
+            "web/playwright.config.ts",
+        ]);
+
+        assert_eq!(
+            tests_for(&report, "app/models.py"),
+            vec!["tests/test_models.py"]
+        );
+        assert_eq!(
+            tests_for(&report, "pkg/store/store.go"),
+            vec!["pkg/store/store_test.go"]
+        );
+        assert_eq!(
+            tests_for(&report, "web/src/app.component.ts"),
+            vec!["web/src/app.component.spec.ts"]
+        );
+        assert_eq!(
+            tests_for(&report, "web/src/utils.js"),
+            vec!["web/src/__tests__/utils.js"]
+        );
+        assert_eq!(
+            report.untested,
+            vec![
+                "app/views.py",
+                "pkg/other/store.go",
+                "pkg/store/cache.go",
+                "web/src/router.ts"
+            ]
+        );
+
+        let signals = test_mapping_signals(&report);
+        assert_eq!(signals[SIGNAL_UNTESTED_RATIO], 0.5);
+    }
+}
@@ -0,0 +1,699 @@
+//! Toolchain version detection for drydock images.
+//!
+//! Repositories pin toolchains in dedicated files (`rust-toolchain(.toml)`,
+//! `.python-version`, `.nvmrc`, `.tool-versions`) or constrain them in their
+//! manifests (`rust-version`, `requires-python`, `engines.node`, the `go`
+//! directive). Pin files apply to their directory and everything below it, so
+//! they are searched from a stack's directory up to the repository root;
+//! manifest fields are read from the stack's own manifest. An exact pin
+//! selects that image tag. A range keeps the default version when it
+//! satisfies the range and otherwise picks the lowest version the range names.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ProjectKind, ToolchainRequirement};
+use crate::error::{Result, ShipShapeError};
+use crate::fs::FileSystem;
+use crate::projects::{ancestors, in_dir};
+
+/// Stacks with a toolchain image, in table order.
+const STACKS: &[ProjectKind] = &[
+    ProjectKind::Python,
+    ProjectKind::Node,
+    ProjectKind::Rust,
+    ProjectKind::Go,
+    ProjectKind::CMake,
+];
+
+/// Built-in image template and fallback version of a stack.
+fn builtin(kind: ProjectKind) -> (&'static str, &'static str) {
+    match kind {
+        ProjectKind::Python => ("python:{version}-slim", "3.11"),
+        ProjectKind::Node => ("node:{version}-alpine", "20"),
+        ProjectKind::Rust => ("rust:{version}", "1.76"),
+        ProjectKind::Go => ("golang:{version}", "1.21"),
+        ProjectKind::CMake => ("ubuntu:{version}", "22.04"),
+    }
+}
+
+/// Image templates and fallback versions, overriding the built-in table.
+///
+/// Keys are stack names (`python`, `node`, `rust`, `go`, `cmake`); stacks
+/// without an entry keep the built-in value. Templates substitute
+/// `{version}`. Resolved images listed under `digests` are pinned to that
+/// digest:
+///
+/// ```toml
+/// [versions]
+/// python = "3.12"
+///
+/// [images]
+/// node = "registry.example.com/node:{version}-bookworm"
+///
+/// [digests]
+/// "python:3.12-slim" = "sha256:..."
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ToolchainDefaults {
+    /// Version used when a repository sets no constraint, per stack.
+    pub versions: BTreeMap<String, String>,
+    /// Image template per stack.
+    pub images: BTreeMap<String, String>,
+    /// Content digest (`sha256:...`) per resolved image reference.
+    pub digests: BTreeMap<String, String>,
+}
+
+impl ToolchainDefaults {
+    /// Load defaults from a `.toml` or `.json` file.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let is_json = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        if is_json {
+            Self::from_json_str(&contents)
+        } else {
+            Self::from_toml_str(&contents)
+        }
+    }
+
+    /// Parse defaults from TOML.
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        toml::from_str::<Self>(contents)
+            .map_err(|err| ShipShapeError::Other(format!("invalid toolchain defaults: {err}")))?
+            .validated()
+    }
+
+    /// Parse defaults from JSON.
+    pub fn from_json_str(contents: &str) -> Result<Self> {
+        serde_json::from_str::<Self>(contents)
+            .map_err(|err| ShipShapeError::Other(format!("invalid toolchain defaults: {err}")))?
+            .validated()
+    }
+
+    /// Version used for `kind` when the repository sets no constraint.
+    pub fn version(&self, kind: ProjectKind) -> &str {
+        self.versions
+            .get(kind.name())
+            .map(String::as_str)
+            .unwrap_or(builtin(kind).1)
+    }
+
+    /// Version to build a `kind` stack with, honouring the repository's
+    /// `requirement`.
+    pub fn resolve(&self, kind: ProjectKind, requirement: Option<&ToolchainRequirement>) -> String {
+        let default = self.version(kind);
+        match requirement {
+            Some(requirement) => resolve_version(&requirement.version, default),
+            None => default.to_string(),
+        }
+    }
+
+    /// Image for a `kind` stack, honouring the repository's `requirement`,
+    /// with its digest appended when one is configured.
+    pub fn image(&self, kind: ProjectKind, requirement: Option<&ToolchainRequirement>) -> String {
+        let version = self.resolve(kind, requirement);
+        let image = self
+            .images
+            .get(kind.name())
+            .map(String::as_str)
+            .unwrap_or(builtin(kind).0)
+            .replace("{version}", &version);
+        match self.digests.get(&image) {
+            Some(digest) => format!("{image}@{digest}"),
+            None => image,
+        }
+    }
+
+    fn validated(self) -> Result<Self> {
+        let unknown = self
+            .versions
+            .keys()
+            .chain(self.images.keys())
+            .find(|key| !STACKS.iter().any(|kind| kind.name() == key.as_str()));
+        if let Some(key) = unknown {
+            return Err(ShipShapeError::Other(format!(
+                "invalid toolchain defaults: unknown stack `{key}`"
+            )));
+        }
+        let malformed = self.digests.iter().find(|(_, digest)| {
+            digest
+                .strip_prefix("sha256:")
+                .is_none_or(|hex| hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()))
+        });
+        match malformed {
+            Some((image, _)) => Err(ShipShapeError::Other(format!(
+                "invalid toolchain defaults: digest for `{image}` is not a sha256 digest"
+            ))),
+            None => Ok(self),
+        }
+    }
+}
+
+/// Find the toolchain version the repository sets for a `kind` stack built
+/// from `dir` (relative to `root`, `.` for the root).
+///
+/// The nearest pin file wins; a `.tool-versions` entry counts as a pin file
+/// of its directory. Manifest constraints apply when no pin is found.
+pub fn detect_toolchain<F: FileSystem>(
+    fs: &F,
+    root: &Path,
+    dir: &str,
+    kind: ProjectKind,
+) -> Option<ToolchainRequirement> {
+    let read = |path: &str| fs.read_to_string(&root.join(path)).ok();
+    for dir in ancestors(dir) {
+        let pinned = match kind {
+            ProjectKind::Rust => rust_toolchain_file(&read, &dir),
+            ProjectKind::Python => version_file(&read, &dir, ".python-version"),
+            ProjectKind::Node => version_file(&read, &dir, ".nvmrc")
+                .or_else(|| version_file(&read, &dir, ".node-version")),
+            ProjectKind::Go | ProjectKind::CMake => None,
+        }
+        .or_else(|| tool_versions(&read, &dir, kind));
+        if pinned.is_some() {
+            return pinned;
+        }
+    }
+    manifest_constraint(&read, dir, kind)
+}
+
+/// Pick the version to build with: an exact `requirement` as written, or
+/// `default` when it satisfies the range, or else the lowest version the
+/// range names. Unparseable ranges fall back to `default`.
+pub fn resolve_version(requirement: &str, default: &str) -> String {
+    let requirement = requirement.trim();
+    let exact = requirement.strip_prefix('v').unwrap_or(requirement);
+    if is_version(exact) {
+        return exact.to_string();
+    }
+    let alternatives: Vec<Vec<Comparator>> =
+        requirement.split("||").map(parse_comparators).collect();
+    let satisfies = |version: &[u64]| {
+        alternatives
+            .iter()
+            .any(|set| !set.is_empty() && set.iter().all(|comparator| comparator.matches(version)))
+    };
+    if satisfies(&parse_version(default)) {
+        return default.to_string();
+    }
+    alternatives
+        .iter()
+        .flatten()
+        .filter_map(Comparator::lower_bound)
+        .find(|candidate| satisfies(candidate))
+        .map(|candidate| join_version(&candidate))
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn requirement(version: impl Into<String>, source: impl Into<String>) -> ToolchainRequirement {
+    ToolchainRequirement {
+        version: version.into(),
+        source: source.into(),
+    }
+}
+
+/// `rust-toolchain.toml`, or a legacy `rust-toolchain` holding either TOML
+/// or a bare channel. Named channels (`stable`, `nightly-...`) pin nothing.
+fn rust_toolchain_file(
+    read: &impl Fn(&str) -> Option<String>,
+    dir: &str,
+) -> Option<ToolchainRequirement> {
+    ["rust-toolchain.toml", "rust-toolchain"]
+        .into_iter()
+        .find_map(|name| {
+            let path = in_dir(dir, name);
+            let contents = read(&path)?;
+            let channel = match toml::from_str::<toml::Value>(&contents) {
+                Ok(value) => value
+                    .get("toolchain")?
+                    .get("channel")?
+                    .as_str()?
+                    .to_string(),
+                Err(_) => first_line(&contents)?.to_string(),
+            };
+            is_version(&channel).then(|| requirement(channel, path))
+        })
+}
+
+/// A file holding a version on its first line, such as `.nvmrc`.
+fn version_file(
+    read: &impl Fn(&str) -> Option<String>,
+    dir: &str,
+    name: &str,
+) -> Option<ToolchainRequirement> {
+    let path = in_dir(dir, name);
+    let contents = read(&path)?;
+    let line = first_line(&contents)?;
+    let version = line.strip_prefix('v').unwrap_or(line);
+    is_version(version).then(|| requirement(version, path))
+}
+
+/// The asdf/mise `.tool-versions` entry for `kind`.
+fn tool_versions(
+    read: &impl Fn(&str) -> Option<String>,
+    dir: &str,
+    kind: ProjectKind,
+) -> Option<ToolchainRequirement> {
+    let tools: &[&str] = match kind {
+        ProjectKind::Python => &["python"],
+        ProjectKind::Node => &["nodejs", "node"],
+        ProjectKind::Rust => &["rust"],
+        ProjectKind::Go => &["golang", "go"],
+        ProjectKind::CMake => return None,
+    };
+    let path = in_dir(dir, ".tool-versions");
+    let contents = read(&path)?;
+    contents.lines().find_map(|line| {
+        let mut fields = line.split('#').next()?.split_whitespace();
+        let tool = fields.next()?;
+        let version = fields.next()?;
+        (tools.contains(&tool) && is_version(version)).then(|| requirement(version, path.clone()))
+    })
+}
+
+fn manifest_constraint(
+    read: &impl Fn(&str) -> Option<String>,
+    dir: &str,
+    kind: ProjectKind,
+) -> Option<ToolchainRequirement> {
+    match kind {
+        ProjectKind::Rust => {
+            let path = in_dir(dir, "Cargo.toml");
+            let manifest = toml::from_str::<toml::Value>(&read(&path)?).ok()?;
+            let version = manifest
+                .get("package")
+                .or_else(|| manifest.get("workspace")?.get("package"))?
+                .get("rust-version")?
+                .as_str()?;
+            Some(requirement(
+                format!(">={version}"),
+                format!("{path} (rust-version)"),
+            ))
+        }
+        ProjectKind::Python => {
+            let path = in_dir(dir, "pyproject.toml");
+            let manifest = toml::from_str::<toml::Value>(&read(&path)?).ok()?;
+            let requires = manifest.get("project").and_then(|project| {
+                Some((project.get("requires-python")?.as_str()?, "requires-python"))
+            });
+            let poetry = || {
+                let python = manifest
+                    .get("tool")?
+                    .get("poetry")?
+                    .get("dependencies")?
+                    .get("python")?;
+                Some((python.as_str()?, "tool.poetry.dependencies.python"))
+            };
+            let (version, field) = requires.or_else(poetry)?;
+            Some(requirement(version, format!("{path} ({field})")))
+        }
+        ProjectKind::Node => {
+            let path = in_dir(dir, "package.json");
+            let manifest = serde_json::from_str::<serde_json::Value>(&read(&path)?).ok()?;
+            let version = manifest.get("engines")?.get("node")?.as_str()?;
+            Some(requirement(version, format!("{path} (engines.node)")))
+        }
+        ProjectKind::Go => {
+            let path = in_dir(dir, "go.mod");
+            let contents = read(&path)?;
+            let directive = |name: &str| {
+                contents.lines().find_map(|line| {
+                    let mut fields = line.split_whitespace();
+                    (fields.next()? == name).then(|| fields.next())?
+                })
+            };
+            if let Some(version) = directive("toolchain")
+                .and_then(|toolchain| toolchain.strip_prefix("go"))
+                .filter(|version| is_version(version))
+            {
+                return Some(requirement(version, format!("{path} (toolchain)")));
+            }
+            let version = directive("go").filter(|version| is_version(version))?;
+            Some(requirement(format!(">={version}"), format!("{path} (go)")))
+        }
+        ProjectKind::CMake => None,
+    }
+}
+
+fn first_line(contents: &str) -> Option<&str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Whether `value` is a plain dotted version such as `3.12` or `1.76.0`.
+fn is_version(value: &str) -> bool {
+    value.starts_with(|c: char| c.is_ascii_digit())
+        && value
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Leading numeric components of a version; wildcards (`x`, `*`) end it.
+fn parse_version(value: &str) -> Vec<u64> {
+    value
+        .trim_start_matches('v')
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// Dotted form of a version without trailing zero components, so a bound
+/// of `18.0.0` selects the `18` image.
+fn join_version(version: &[u64]) -> String {
+    let mut len = version.len();
+    while len > 1 && version[len - 1] == 0 {
+        len -= 1;
+    }
+    version[..len]
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn compare(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            a.get(i)
+                .copied()
+                .unwrap_or(0)
+                .cmp(&b.get(i).copied().unwrap_or(0))
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// The version after `version` at component `index`: `bump([1, 2, 3], 0)`
+/// is `[2]`.
+fn bump(version: &[u64], index: usize) -> Vec<u64> {
+    let mut bumped = version[..=index].to_vec();
+    bumped[index] += 1;
+    bumped
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Any,
+    Ge,
+    Gt,
+    Le,
+    Lt,
+    /// `==`, `=` or a bare version: every version starting with it.
+    Prefix,
+    Ne,
+    /// `^`: up to the next change of the first non-zero component.
+    Caret,
+    /// `~`: up to the next minor version.
+    Tilde,
+    /// `~=`: up to the next change of the second-to-last component.
+    Compatible,
+}
+
+/// One comparator of a PEP 440 or npm-style version range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: Vec<u64>,
+}
+
+impl Comparator {
+    fn parse(token: &str) -> Option<Self> {
+        const OPS: &[(&str, Op)] = &[
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("==", Op::Prefix),
+            ("!=", Op::Ne),
+            ("~=", Op::Compatible),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("=", Op::Prefix),
+            ("^", Op::Caret),
+            ("~", Op::Tilde),
+        ];
+        let (op, rest) = OPS
+            .iter()
+            .find_map(|(prefix, op)| Some((*op, token.strip_prefix(prefix)?)))
+            .unwrap_or((Op::Prefix, token));
+        let version = parse_version(rest.trim());
+        if version.is_empty() {
+            return matches!(rest.trim(), "*" | "x" | "X").then_some(Self {
+                op: Op::Any,
+                version,
+            });
+        }
+        Some(Self { op, version })
+    }
+
+    fn matches(&self, candidate: &[u64]) -> bool {
+        let ordering = compare(candidate, &self.version);
+        let below = |upper: Vec<u64>| ordering.is_ge() && compare(candidate, &upper).is_lt();
+        match self.op {
+            Op::Any => true,
+            Op::Ge => ordering.is_ge(),
+            Op::Gt => ordering.is_gt(),
+            Op::Le => ordering.is_le(),
+            Op::Lt => ordering.is_lt(),
+            Op::Prefix => self.is_prefix_of(candidate),
+            Op::Ne => !self.is_prefix_of(candidate),
+            Op::Caret => {
+                let index = self
+                    .version
+                    .iter()
+                    .position(|part| *part != 0)
+                    .unwrap_or(self.version.len() - 1);
+                below(bump(&self.version, index))
+            }
+            Op::Tilde => below(bump(&self.version, 1.min(self.version.len() - 1))),
+            Op::Compatible => below(bump(&self.version, self.version.len().saturating_sub(2))),
+        }
+    }
+
+    fn is_prefix_of(&self, candidate: &[u64]) -> bool {
+        self.version
+            .iter()
+            .enumerate()
+            .all(|(i, part)| candidate.get(i).copied().unwrap_or(0) == *part)
+    }
+
+    /// The lowest version this comparator admits, if it names one.
+    fn lower_bound(&self) -> Option<Vec<u64>> {
+        match self.op {
+            Op::Ge | Op::Prefix | Op::Caret | Op::Tilde | Op::Compatible => {
+                Some(self.version.clone())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Comparators that must all hold, separated by spaces or commas. An npm
+/// hyphen range (`16 - 18`) becomes `>=16 <19`.
+fn parse_comparators(range: &str) -> Vec<Comparator> {
+    let mut tokens: Vec<String> = Vec::new();
+    for token in range.split([' ', ',']).filter(|token| !token.is_empty()) {
+        match tokens.last_mut() {
+            // Join operators written apart from their version (`>= 18`).
+            Some(last) if last.chars().all(|c| "<>=!~^".contains(c)) => last.push_str(token),
+            _ => tokens.push(token.to_string()),
+        }
+    }
+    let mut comparators = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        if tokens.peek().map(String::as_str) == Some("-") {
+            tokens.next();
+            let (lower, upper) = (parse_version(&token), tokens.next());
+            let upper = upper.map(|upper| parse_version(&upper)).unwrap_or_default();
+            if lower.is_empty() || upper.is_empty() {
+                return Vec::new();
+            }
+            comparators.push(Comparator {
+                op: Op::Ge,
+                version: lower,
+            });
+            comparators.push(Comparator {
+                op: Op::Lt,
+                version: bump(&upper, upper.len() - 1),
+            });
+            continue;
+        }
+        match Comparator::parse(&token) {
+            Some(comparator) => comparators.push(comparator),
+            None => return Vec::new(),
+        }
+    }
+    comparators
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ToolchainDefaults, detect_toolchain, resolve_version};
+    use crate::domain::{ProjectKind, ToolchainRequirement};
+    use crate::fs::MemoryFileSystem;
+    use std::path::Path;
+
+    #[test]
+    fn resolves_ranges_against_the_default() {
+        let cases = [
+            ("1.80.0", "1.76", "1.80.0"),
+            ("v20.11.1", "20", "20.11.1"),
+            (">=3.9", "3.11", "3.11"),
+            (">=3.12", "3.11", "3.12"),
+            (">=3.8,<3.11", "3.11", "3.8"),
+            ("^3.12", "3.11", "3.12"),
+            ("~=3.9", "3.11", "3.11"),
+            ("==3.10.*", "3.11", "3.10"),
+            (">= 16 < 19", "20", "16"),
+            ("^18.0.0 || ^20.0.0", "22", "18"),
+            ("18.x", "20", "18"),
+            ("16 - 18", "20", "16"),
+            ("~1.21.0", "1.22", "1.21"),
+            ("*", "20", "20"),
+            ("latest", "20", "20"),
+        ];
+        for (requirement, default, expected) in cases {
+            assert_eq!(
+                resolve_version(requirement, default),
+                expected,
+                "{requirement} with default {default}"
+            );
+        }
+    }
+
+    #[test]
+    fn detects_the_nearest_pin_before_manifest_constraints() {
+        let fs = MemoryFileSystem::new()
+            .with_file("/repo/.tool-versions", "nodejs 18.19.0\npython 3.10.4\n")
+            .with_file(
+                "/repo/rust-toolchain.toml",
+                "[toolchain]\nchannel = \"1.80.0\"\n",
+            )
+            .with_file("/repo/web/.nvmrc", "v20.11.1\n")
+            .with_file(
+                "/repo/web/package.json",
+                "{\"engines\": {\"node\": \">=18\"}}",
+            )
+            .with_file(
+                "/repo/admin/package.json",
+                "{\"engines\": {\"node\": \"^18\"}}",
+            )
+            .with_file(
+                "/repo/api/pyproject.toml",
+                "[project]\nrequires-python = \">=3.9\"\n",
+            )
+            .with_file("/repo/svc/go.mod", "module svc\n\ngo 1.22\n")
+            .with_file(
+                "/repo/tools/go.mod",
+                "module tools\n\ngo 1.21\ntoolchain go1.22.3\n",
+            )
+            .with_file("/repo/lib/rust-toolchain", "nightly-2024-01-01\n")
+            .with_file(
+                "/repo/lib/Cargo.toml",
+                "[package]\nrust-version = \"1.70\"\n",
+            );
+        let root = Path::new("/repo");
+        let detect = |dir: &str, kind| {
+            detect_toolchain(&fs, root, dir, kind)
+                .map(|requirement| (requirement.version, requirement.source))
+        };
+        let pin = |version: &str, source: &str| Some((version.to_string(), source.to_string()));
+
+        assert_eq!(
+            detect("web", ProjectKind::Node),
+            pin("20.11.1", "web/.nvmrc")
+        );
+        assert_eq!(
+            detect("admin", ProjectKind::Node),
+            pin("18.19.0", ".tool-versions")
+        );
+        assert_eq!(
+            detect("api", ProjectKind::Python),
+            pin("3.10.4", ".tool-versions")
+        );
+        assert_eq!(
+            detect("lib", ProjectKind::Rust),
+            pin("1.80.0", "rust-toolchain.toml")
+        );
+        assert_eq!(
+            detect("svc", ProjectKind::Go),
+            pin(">=1.22", "svc/go.mod (go)")
+        );
+        assert_eq!(
+            detect("tools", ProjectKind::Go),
+            pin("1.22.3", "tools/go.mod (toolchain)")
+        );
+
+        let bare = MemoryFileSystem::new()
+            .with_file(
+                "/repo/Cargo.toml",
+                "[workspace.package]\nrust-version = \"1.74\"\n",
+            )
+            .with_file(
+                "/repo/pyproject.toml",
+                "[tool.poetry.dependencies]\npython = \"^3.12\"\n",
+            );
+        assert_eq!(
+            detect_toolchain(&bare, root, ".", ProjectKind::Rust),
+            Some(ToolchainRequirement {
+                version: ">=1.74".to_string(),
+                source: "Cargo.toml (rust-version)".to_string(),
+            })
+        );
+        assert_eq!(
+            detect_toolchain(&bare, root, ".", ProjectKind::Python)
+                .map(|requirement| requirement.source),
+            Some("pyproject.toml (tool.poetry.dependencies.python)".to_string())
+        );
+        assert_eq!(detect_toolchain(&bare, root, ".", ProjectKind::Node), None);
+    }
+
+    #[test]
+    fn defaults_table_overrides_built_in_images() {
+        let defaults = ToolchainDefaults::from_toml_str(
+            "[versions]\npython = \"3.12\"\n\n[images]\nnode = \"registry.example.com/node:{version}\"\n",
+        )
+        .expect("defaults");
+        let pinned = ToolchainRequirement {
+            version: "^18".to_string(),
+            source: "package.json (engines.node)".to_string(),
+        };
+
+        assert_eq!(
+            defaults.image(ProjectKind::Python, None),
+            "python:3.12-slim"
+        );
+        assert_eq!(
+            defaults.image(ProjectKind::Node, Some(&pinned)),
+            "registry.example.com/node:18"
+        );
+        assert_eq!(
+            ToolchainDefaults::default().image(ProjectKind::Rust, None),
+            "rust:1.76"
+        );
+        assert!(ToolchainDefaults::from_json_str("{\"versions\": {\"java\": \"21\"}}").is_err());
+
+        let digest = format!("sha256:{}", "ab".repeat(32));
+        let pinned = ToolchainDefaults::from_toml_str(&format!(
+            "[digests]\n\"python:3.11-slim\" = \"{digest}\"\n"
+        ))
+        .expect("digests");
+        assert_eq!(
+            pinned.image(ProjectKind::Python, None),
+            format!("python:3.11-slim@{digest}")
+        );
+        assert!(
+            ToolchainDefaults::from_toml_str("[digests]\n\"rust:1.76\" = \"latest\"\n").is_err()
+        );
+    }
+}
@@ -0,0 +1,457 @@
+//! Local drydock verification.
+//!
+//! [`verify_local`] runs the jobs of a generated [`Drydock`](crate::Drydock)
+//! without pushing to a CI mirror. Each job gets a fresh copy of the
+//! repository and runs its commands with `sh -ex`, either directly on the host
+//! or inside the job's image through a docker-compatible runtime such as
+//! `docker` or `podman`. Output is streamed line by line as it arrives, and
+//! jobs that outlive the timeout are killed.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::domain::{JobOutcome, JobVerification, Verification};
+use crate::drydock::DrydockJob;
+use crate::error::{Result, ShipShapeError};
+use crate::projects::ROOT_PROJECT;
+
+/// Lines of output kept per job in [`JobVerification::log_tail`].
+pub const LOG_TAIL_LINES: usize = 50;
+
+/// Time a job may run before it is killed, unless overridden.
+pub const DEFAULT_VERIFY_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Where verification jobs run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyRuntime {
+    /// Directly on the host, with whatever toolchains it has installed.
+    /// Distribution packages the job asks for are assumed to be present.
+    Host,
+    /// Inside each job's image, started with `<command> run`; any runtime
+    /// that accepts docker's `run` flags works.
+    Container(String),
+}
+
+impl VerifyRuntime {
+    /// Short name used on the command line and in reports.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Host => "host",
+            Self::Container(command) => command,
+        }
+    }
+}
+
+impl Default for VerifyRuntime {
+    fn default() -> Self {
+        Self::Container("docker".to_string())
+    }
+}
+
+impl FromStr for VerifyRuntime {
+    type Err = ShipShapeError;
+
+    /// `host`, or the container runtime command, e.g. `docker` or `podman`.
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim() {
+            "" => Err(ShipShapeError::Other(
+                "empty verify runtime (expected host or a container command)".to_string(),
+            )),
+            value if value.eq_ignore_ascii_case("host") => Ok(Self::Host),
+            command => Ok(Self::Container(command.to_string())),
+        }
+    }
+}
+
+/// Settings for [`verify_local`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Where the jobs run.
+    pub runtime: VerifyRuntime,
+    /// Time each job may run before it is killed.
+    pub timeout: Duration,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            runtime: VerifyRuntime::default(),
+            timeout: DEFAULT_VERIFY_TIMEOUT,
+        }
+    }
+}
+
+/// Run `jobs` against copies of the repository at `root`, one job at a time.
+///
+/// `on_line` receives each job's name and every line it prints, stdout and
+/// stderr interleaved, while the job runs. The copies skip `.git` and are
+/// removed afterwards; the repository itself is never modified.
+pub fn verify_local<F: FnMut(&str, &str)>(
+    root: &Path,
+    jobs: &[DrydockJob],
+    options: &VerifyOptions,
+    mut on_line: F,
+) -> Result<Verification> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    let run_id = format!("shipshape-verify-{}-{nanos}", std::process::id());
+    let scratch = std::env::temp_dir().join(&run_id);
+    let mut results = Vec::new();
+    for job in jobs {
+        let copy = scratch.join(&job.name);
+        if let Err(err) = copy_tree(root, &copy) {
+            let _ = std::fs::remove_dir_all(&scratch);
+            return Err(err.into());
+        }
+        let container = format!("{run_id}-{}", job.name);
+        results.push(run_job(job, &copy, options, &container, &mut on_line));
+        let _ = std::fs::remove_dir_all(&copy);
+    }
+    let _ = std::fs::remove_dir_all(&scratch);
+    Ok(Verification {
+        runtime: options.runtime.name().to_string(),
+        passed: results.iter().all(|job| job.outcome == JobOutcome::Passed),
+        jobs: results,
+    })
+}
+
+/// Run one job in `copy`, streaming its output until it exits or times out.
+fn run_job<F: FnMut(&str, &str)>(
+    job: &DrydockJob,
+    copy: &Path,
+    options: &VerifyOptions,
+    container: &str,
+    on_line: &mut F,
+) -> JobVerification {
+    let started = Instant::now();
+    let mut tail: VecDeque<String> = VecDeque::new();
+    let mut record = |line: String| {
+        on_line(&job.name, &line);
+        if tail.len() == LOG_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    };
+    let result = |outcome, exit_code, tail: VecDeque<String>| JobVerification {
+        job: job.name.clone(),
+        path: job.path.clone(),
+        outcome,
+        exit_code,
+        duration_ms: started.elapsed().as_millis() as u64,
+        log_tail: tail.into(),
+    };
+
+    if options.runtime == VerifyRuntime::Host && !job.packages.is_empty() {
+        record(format!(
+            "running on the host; expecting {} to be installed",
+            job.packages.join(", ")
+        ));
+    }
+    let mut command = job_command(job, copy, &options.runtime, container);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            let program = command.get_program().to_string_lossy().into_owned();
+            record(format!("failed to start {program}: {err}"));
+            return result(JobOutcome::Error, None, tail);
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, sender.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, sender);
+    }
+    let deadline = started + options.timeout;
+    let mut timed_out = false;
+    loop {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(line) => record(line),
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {
+                timed_out = true;
+                break;
+            }
+        }
+    }
+
+    if timed_out {
+        #[cfg(unix)]
+        let _ = Command::new("kill")
+            .arg("-KILL")
+            .arg(format!("-{}", child.id()))
+            .output();
+        let _ = child.kill();
+        if let VerifyRuntime::Container(runtime) = &options.runtime {
+            let _ = Command::new(runtime).args(["rm", "-f", container]).output();
+        }
+        let _ = child.wait();
+        record(format!(
+            "killed after the {}s timeout",
+            options.timeout.as_secs_f64()
+        ));
+        return result(JobOutcome::TimedOut, None, tail);
+    }
+    match child.wait() {
+        Ok(status) if status.success() => result(JobOutcome::Passed, status.code(), tail),
+        Ok(status) => result(JobOutcome::Failed, status.code(), tail),
+        Err(err) => {
+            record(format!("failed to wait for the job: {err}"));
+            result(JobOutcome::Error, None, tail)
+        }
+    }
+}
+
+/// `sh -ex` running the job's script in `copy`, on the host or in a
+/// container with `copy` mounted at `/app`.
+fn job_command(job: &DrydockJob, copy: &Path, runtime: &VerifyRuntime, container: &str) -> Command {
+    let script = job_script(job, runtime);
+    match runtime {
+        VerifyRuntime::Host => {
+            let mut command = Command::new("sh");
+            command.arg("-exc").arg(script).current_dir(copy);
+            command
+        }
+        VerifyRuntime::Container(program) => {
+            let mut command = Command::new(program);
+            command
+                .args(["run", "--rm", "--name", container, "--volume"])
+                .arg(format!("{}:/app", copy.display()))
+                .args(["--workdir", "/app", &job.image, "sh", "-exc"])
+                .arg(script);
+            command
+        }
+    }
+}
+
+/// The job's commands as one script, run from its directory. Containers
+/// install the job's distribution packages first, as the GitLab job does.
+fn job_script(job: &DrydockJob, runtime: &VerifyRuntime) -> String {
+    let mut lines = Vec::new();
+    if job.path != ROOT_PROJECT {
+        lines.push(format!("cd {}", job.path));
+    }
+    if matches!(runtime, VerifyRuntime::Container(_)) && !job.packages.is_empty() {
+        lines.push("apt-get update".to_string());
+        lines.push(format!("apt-get install -y {}", job.packages.join(" ")));
+    }
+    lines.extend(job.commands.iter().cloned());
+    lines.join("\n")
+}
+
+/// Send each line `reader` produces to `sender` from a background thread.
+fn forward_lines<R: Read + Send + 'static>(reader: R, sender: Sender<String>) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Copy the tree at `from` to `to`, skipping `.git`. Symlinks are recreated
+/// on Unix and skipped elsewhere.
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_name() == ".git" {
+            continue;
+        }
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_tree(&source, &target)?;
+        } else if file_type.is_file() {
+            std::fs::copy(&source, &target)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(std::fs::read_link(&source)?, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VerifyOptions, VerifyRuntime, verify_local};
+    use crate::domain::JobOutcome;
+    use crate::drydock::DrydockJob;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn job(name: &str, path: &str, commands: &[&str]) -> DrydockJob {
+        DrydockJob {
+            name: name.to_string(),
+            path: path.to_string(),
+            image: "ubuntu:22.04".to_string(),
+            packages: Vec::new(),
+            commands: commands.iter().map(|command| command.to_string()).collect(),
+        }
+    }
+
+    fn temp_repo() -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("shipshape_verify_test_{nanos}"));
+        std::fs::create_dir_all(root.join(".git")).expect("create git dir");
+        std::fs::create_dir_all(root.join("web")).expect("create web dir");
+        std::fs::write(root.join(".git/HEAD"), "ref: refs/heads/main\n").expect("write head");
+        std::fs::write(root.join("marker"), "root").expect("write marker");
+        std::fs::write(root.join("web/package.json"), "{}").expect("write package");
+        root
+    }
+
+    #[test]
+    fn runs_jobs_on_the_host_in_isolated_copies() {
+        let root = temp_repo();
+        let mut passing = job(
+            "pass",
+            ".",
+            &[
+                "test -f marker",
+                "test ! -e .git",
+                "echo hello",
+                "touch built",
+            ],
+        );
+        passing.packages = vec!["make".to_string()];
+        let jobs = vec![
+            passing,
+            job("web", "web", &["test -f package.json", "test ! -e built"]),
+            job("fail", ".", &["echo before", "exit 3", "echo after"]),
+        ];
+        let options = VerifyOptions {
+            runtime: VerifyRuntime::Host,
+            timeout: Duration::from_secs(30),
+        };
+        let mut streamed = Vec::new();
+        let verification = verify_local(&root, &jobs, &options, |job, line| {
+            streamed.push((job.to_string(), line.to_string()))
+        })
+        .expect("verify");
+
+        assert_eq!(verification.runtime, "host");
+        assert!(!verification.passed);
+        let outcomes: Vec<_> = verification
+            .jobs
+            .iter()
+            .map(|job| (job.job.as_str(), job.outcome, job.exit_code))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("pass", JobOutcome::Passed, Some(0)),
+                ("web", JobOutcome::Passed, Some(0)),
+                ("fail", JobOutcome::Failed, Some(3)),
+            ]
+        );
+        assert!(streamed.contains(&("pass".to_string(), "hello".to_string())));
+        assert!(streamed.contains(&(
+            "pass".to_string(),
+            "running on the host; expecting make to be installed".to_string()
+        )));
+        let fail = &verification.jobs[2].log_tail;
+        assert!(fail.contains(&"before".to_string()));
+        assert!(!fail.contains(&"after".to_string()));
+        assert!(!root.join("built").exists());
+
+        std::fs::remove_dir_all(root).expect("cleanup");
+    }
+
+    #[test]
+    fn kills_jobs_that_outlive_the_timeout() {
+        let root = temp_repo();
+        let options = VerifyOptions {
+            runtime: VerifyRuntime::Host,
+            timeout: Duration::from_millis(200),
+        };
+        let verification = verify_local(
+            &root,
+            &[job("slow", ".", &["sleep 5"])],
+            &options,
+            |_, _| {},
+        )
+        .expect("verify");
+
+        let slow = &verification.jobs[0];
+        assert_eq!(slow.outcome, JobOutcome::TimedOut);
+        assert_eq!(slow.exit_code, None);
+        assert!(slow.duration_ms < 5000);
+        assert_eq!(
+            slow.log_tail.last().map(String::as_str),
+            Some("killed after the 0.2s timeout")
+        );
+
+        std::fs::remove_dir_all(root).expect("cleanup");
+    }
+
+    #[test]
+    fn runs_jobs_through_the_container_runtime() {
+        let root = temp_repo();
+        let mut web = job("web", "web", &["npm test"]);
+        web.packages = vec!["make".to_string()];
+        // `echo` stands in for docker and prints the arguments it was given.
+        let options = VerifyOptions {
+            runtime: "echo".parse().expect("runtime"),
+            timeout: Duration::from_secs(30),
+        };
+        let verification = verify_local(&root, &[web], &options, |_, _| {}).expect("verify");
+
+        assert!(verification.passed);
+        let log = verification.jobs[0].log_tail.join("\n");
+        assert!(log.starts_with("run --rm --name shipshape-verify-"));
+        assert!(log.contains(":/app --workdir /app ubuntu:22.04 sh -exc cd web\n"));
+        assert!(log.contains("\napt-get install -y make\nnpm test"));
+
+        let options = VerifyOptions {
+            runtime: VerifyRuntime::Container("shipshape-missing-runtime".to_string()),
+            ..options
+        };
+        let verification = verify_local(&root, &[job("test", ".", &["true"])], &options, |_, _| {})
+            .expect("verify");
+        assert!(!verification.passed);
+        assert_eq!(verification.jobs[0].outcome, JobOutcome::Error);
+        assert!(
+            verification.jobs[0].log_tail[0]
+                .starts_with("failed to start shipshape-missing-runtime:")
+        );
+
+        std::fs::remove_dir_all(root).expect("cleanup");
+    }
+
+    #[test]
+    fn parses_runtimes() {
+        assert_eq!(
+            "host".parse::<VerifyRuntime>().unwrap(),
+            VerifyRuntime::Host
+        );
+        assert_eq!(
+            " podman ".parse::<VerifyRuntime>().unwrap(),
+            VerifyRuntime::Container("podman".to_string())
+        );
+        assert!("".parse::<VerifyRuntime>().is_err());
+        assert_eq!(VerifyRuntime::default().name(), "docker");
+    }
+}
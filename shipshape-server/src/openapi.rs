@@ -2,7 +2,10 @@
 
 use utoipa::OpenApi;
 
-use shipshape_core::{CoverageReport, FleetReport, Violation};
+use shipshape_core::{
+    CoverageReport, CoverageSource, FleetReport, HealthCategory, HealthDeduction, Severity,
+    Violation,
+};
 
 use crate::routes::{
     ActivityLog, AuthConfigResponse, AuthGithubRequest, AuthGithubResponse, AuthGithubTokenRequest,
@@ -71,7 +74,11 @@ use crate::workflows::{
             PullRequestSpec,
             GitLabSpec,
             CoverageReport,
+            CoverageSource,
             FleetReport,
+            HealthCategory,
+            HealthDeduction,
+            Severity,
             Violation,
             WorkflowRequest,
             WorkflowResult,
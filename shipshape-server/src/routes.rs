@@ -6,6 +6,7 @@ use diesel::prelude::*;
 use diesel::result::OptionalExtension;
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use shipshape_core::{HealthCategory, HealthDeduction};
 use std::sync::{Arc, RwLock};
 use utoipa::{OpenApi, ToSchema};
 use uuid::Uuid;
@@ -253,6 +254,8 @@ pub struct VesselStatus {
     pub name: String,
     /// Health score value.
     pub health_score: u8,
+    /// Deductions that explain the health score.
+    pub health_breakdown: Vec<HealthDeduction>,
     /// Coverage risk label.
     pub coverage_risk: String,
     /// Last run description.
@@ -811,6 +814,18 @@ pub async fn dashboard(state: web::Data<AppState>, req: HttpRequest) -> impl Res
             id: "VX-118".to_string(),
             name: "Apollo Navigation".to_string(),
             health_score: 92,
+            health_breakdown: vec![
+                HealthDeduction {
+                    category: HealthCategory::Docs,
+                    points: 5,
+                    reason: "doc coverage 18.0% (9/50)".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Violations,
+                    points: 3,
+                    reason: "3 lint violations".to_string(),
+                },
+            ],
             coverage_risk: "Low".to_string(),
             last_run: "2 hours ago".to_string(),
             tone: "good".to_string(),
@@ -820,6 +835,23 @@ pub async fn dashboard(state: web::Data<AppState>, req: HttpRequest) -> impl Res
             id: "VX-204".to_string(),
             name: "Kepler Analytics".to_string(),
             health_score: 78,
+            health_breakdown: vec![
+                HealthDeduction {
+                    category: HealthCategory::Tests,
+                    points: 10,
+                    reason: "test file ratio 32.0% (16/50)".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Docs,
+                    points: 5,
+                    reason: "doc coverage 16.0% (8/50)".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Violations,
+                    points: 7,
+                    reason: "7 lint violations".to_string(),
+                },
+            ],
             coverage_risk: "Medium".to_string(),
             last_run: "4 hours ago".to_string(),
             tone: "warn".to_string(),
@@ -829,6 +861,23 @@ pub async fn dashboard(state: web::Data<AppState>, req: HttpRequest) -> impl Res
             id: "VX-330".to_string(),
             name: "Orion Payments".to_string(),
             health_score: 63,
+            health_breakdown: vec![
+                HealthDeduction {
+                    category: HealthCategory::Tests,
+                    points: 25,
+                    reason: "measured line coverage 52.0%".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Docs,
+                    points: 5,
+                    reason: "doc coverage 12.0% (6/50)".to_string(),
+                },
+                HealthDeduction {
+                    category: HealthCategory::Violations,
+                    points: 7,
+                    reason: "7 lint violations".to_string(),
+                },
+            ],
             coverage_risk: "High".to_string(),
             last_run: "9 hours ago".to_string(),
             tone: "bad".to_string(),
@@ -355,11 +355,7 @@ impl GitLabClient for MockGitLabClient {
         if gitlab.project_path.trim().is_empty() {
             return Err(WorkflowError::new("gitlab project_path is required"));
         }
-        let reference = gitlab
-            .pipeline_ref
-            .as_ref()
-            .map(String::as_str)
-            .unwrap_or(branch);
+        let reference = gitlab.pipeline_ref.as_deref().unwrap_or(branch);
         Ok(format!(
             "https://gitlab.example.com/{}/-/pipelines/{}",
             gitlab.project_path, reference
@@ -773,11 +769,7 @@ impl GitLabClient for GitLabApiClient {
         if gitlab.project_path.trim().is_empty() {
             return Err(WorkflowError::new("gitlab project_path is required"));
         }
-        let reference = gitlab
-            .pipeline_ref
-            .as_ref()
-            .map(String::as_str)
-            .unwrap_or(branch);
+        let reference = gitlab.pipeline_ref.as_deref().unwrap_or(branch);
         let encoded = urlencoding::encode(&gitlab.project_path);
         let url = format!(
             "{}/api/v4/projects/{}/pipeline",
@@ -1370,7 +1362,7 @@ mod tests {
     use super::*;
     use httpmock::Method::{GET, POST};
     use httpmock::MockServer;
-    use shipshape_core::{CoverageReport, Violation};
+    use shipshape_core::{CoverageReport, CoverageSource, Severity, Violation};
     use std::collections::BTreeMap;
     use std::path::{Path, PathBuf};
     use std::process::Command;
@@ -1715,6 +1707,9 @@ mod tests {
             violations: vec![Violation {
                 id: "doc-1".to_string(),
                 message: "Missing docs".to_string(),
+                severity: Severity::Warning,
+                path: None,
+                line: None,
             }],
             coverage: CoverageReport {
                 code_files: 10,
@@ -1724,8 +1719,16 @@ mod tests {
                 doc_coverage: 0.2,
                 low_test_coverage: false,
                 low_doc_coverage: true,
+                source: CoverageSource::Heuristic,
+                line_coverage: None,
+                branch_coverage: None,
+                artifacts: Vec::new(),
+                public_items: 0,
+                documented_items: 0,
+                api_doc_coverage: None,
             },
             health_score: 84,
+            health_breakdown: Vec::new(),
         }
     }
 
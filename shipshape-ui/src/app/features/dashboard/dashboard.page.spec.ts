@@ -28,6 +28,9 @@ const mockResponse: DashboardResponse = {
       id: "VX-2",
       name: "Nova",
       healthScore: 65,
+      healthBreakdown: [
+        { category: "tests", points: 35, reason: "test file ratio 5.0% (1/20)" },
+      ],
       coverageRisk: "High",
       lastRun: "2 hours ago",
       tone: "bad",
@@ -83,4 +86,24 @@ describe("DashboardPage", () => {
     expect(fixture.componentInstance.metrics().length).toBe(1);
     expect(fixture.componentInstance.alerts().length).toBe(1);
   });
+
+  it("renders health deductions", () => {
+    TestBed.configureTestingModule({
+      imports: [DashboardPage],
+      providers: [
+        {
+          provide: ApiService,
+          useValue: {
+            getDashboard: () => of(mockResponse),
+          },
+        },
+      ],
+    });
+
+    const fixture = TestBed.createComponent(DashboardPage);
+    fixture.detectChanges();
+
+    const text = fixture.nativeElement.textContent as string;
+    expect(text).toContain("-35 tests: test file ratio 5.0% (1/20)");
+  });
 });
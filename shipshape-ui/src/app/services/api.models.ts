@@ -6,10 +6,17 @@ export interface FleetMetric {
   tone: "good" | "warn" | "bad" | "info";
 }
 
+export interface HealthDeduction {
+  category: "tests" | "docs" | "violations" | "signal";
+  points: number;
+  reason: string;
+}
+
 export interface VesselStatus {
   id: string;
   name: string;
   healthScore: number;
+  healthBreakdown?: HealthDeduction[];
   coverageRisk: string;
   lastRun: string;
   tone: "good" | "warn" | "bad" | "info";